@@ -2,24 +2,17 @@ use std::env;
 use std::fs;
 use std::path::Path;
 
-extern crate rustc_version;
-use rustc_version::{version_meta, Channel};
-
 use field_assembly::generate_macro_string;
 
-const NUM_LIMBS: usize = 8;
+const NUM_LIMBS: usize = 13;
 
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
 
-    let is_nightly = version_meta().expect("nightly check failed").channel == Channel::Nightly;
-
-    let should_use_asm = cfg!(all(
-        feature = "llvm_asm",
-        target_feature = "bmi2",
-        target_feature = "adx",
-        target_arch = "x86_64"
-    )) && is_nightly;
+    // Whether the CPU supports the assembly backend is checked at runtime, see
+    // `has_adx_bmi2`.
+    let should_use_asm = env::var_os("CARGO_FEATURE_ASM").is_some()
+        && env::var("CARGO_CFG_TARGET_ARCH").map_or(false, |arch| arch == "x86_64");
     if should_use_asm {
         let out_dir = env::var_os("OUT_DIR").unwrap();
         let dest_path = Path::new(&out_dir).join("field_assembly.rs");
@@ -11,56 +11,99 @@ use mince::assemble;
 
 use std::cell::RefCell;
 
-const MAX_REGS: usize = 6;
+/// Number of registers available to hold limbs of the accumulator. The
+/// remaining limbs are spilled to the stack.
+const MAX_REGS: usize = 7;
 
 pub fn generate_macro_string(num_limbs: usize) -> std::string::String {
-    if num_limbs > 3 * MAX_REGS {
-        panic!(
-            "Number of limbs must be <= {} and MAX_REGS >= 6",
-            3 * MAX_REGS
-        );
+    if num_limbs < 2 {
+        panic!("Number of limbs must be >= 2");
     }
     let mut macro_string = String::from(
         "
-        macro_rules! llvm_asm_mul {
+        macro_rules! asm_mul {
         ($limbs:expr, $a:expr, $b:expr, $modulus:expr, $mod_prime:expr) => {
             match $limbs {",
     );
     macro_string += &generate_matches(num_limbs, true);
 
     macro_string += &"
-        macro_rules! llvm_asm_square {
+        macro_rules! asm_square {
         ($limbs:expr, $a:expr, $modulus:expr, $mod_prime:expr) => {
             match $limbs {";
     macro_string += &generate_matches(num_limbs, false);
     macro_string
 }
 
+/// Generates Montgomery multiplication of the `limbs`-limb integers pointed to
+/// by `RSI` and `RDI` (or `RSI` twice when squaring) modulo the integer
+/// pointed to by `RCX`, with `mod_prime` in `RDX`. The result is written back
+/// to `RSI`.
+///
+/// The accumulator limbs are rotated through a fixed set of slots, so that
+/// dropping the lowest limb after each reduction step needs no moves. Slots
+/// that do not fit in registers live on the stack below `mod_prime`.
 #[assemble]
-fn generate_llvm_asm_mul_string(
-    a: &str,
-    b: &str,
-    modulus: &str,
-    zero: &str,
-    mod_prime: &str,
-    limbs: usize,
-) -> String {
+fn generate_asm_mul_string(limbs: usize, is_square: bool) -> String {
+    let a = RSI;
+    let b = if is_square { RSI } else { RDI };
+    let modulus = RCX;
     reg!(a0, a1, a, limbs);
     reg!(b0, b1, b, limbs);
     reg!(m, m1, modulus, limbs);
 
-    xorq(RCX, RCX);
-    for i in 0..limbs {
-        if i == 0 {
-            mul_1!(a1[0], b1, zero, limbs);
+    let hi = R[7];
+    let mut regs = R[..MAX_REGS].to_vec();
+    if is_square {
+        regs.push(RDI);
+    }
+    let num_slots = limbs + 1;
+    let tmp = if num_slots > regs.len() {
+        regs.pop().unwrap()
+    } else {
+        ""
+    };
+    let num_spilled = num_slots.saturating_sub(regs.len());
+    let slots: Vec<String> = (0..num_slots)
+        .map(|k| match regs.get(k) {
+            Some(reg) => reg.to_string(),
+            None => format!("{}({})", 8 * (k - regs.len() + 1), RSP),
+        })
+        .collect();
+    let frame_size = format!("${}", 8 * (num_spilled + 1));
+    let mod_prime = format!("0({})", RSP);
+
+    subq(&frame_size, RSP);
+    movq(RDX, &mod_prime);
+    for slot in &slots[..limbs] {
+        if is_register(slot) {
+            xorq(slot, slot);
         } else {
-            mul_add_1!(a1, b1, zero, i, limbs);
+            movq("$0", slot);
         }
-        mul_add_shift_1!(m1, mod_prime, zero, i, limbs);
     }
     for i in 0..limbs {
-        movq(R[i], a1[i]);
+        let t: Vec<&str> = (0..num_slots)
+            .map(|k| &*slots[(k + i) % num_slots])
+            .collect();
+        xorq(RAX, RAX);
+        movq(b1[i], RDX);
+        mul_add_1!(a1, t, hi, tmp, limbs);
+        xorq(RAX, RAX);
+        movq(&mod_prime, RDX);
+        mulxq(t[0], RDX, RAX);
+        mul_add_shift_1!(m1, t, hi, tmp, limbs);
+    }
+    for k in 0..limbs {
+        let slot = &slots[(k + limbs) % num_slots];
+        if is_register(slot) {
+            movq(slot, a1[k]);
+        } else {
+            movq(slot, tmp);
+            movq(tmp, a1[k]);
+        }
     }
+    addq(&frame_size, RSP);
 }
 
 fn generate_matches(num_limbs: usize, is_mul: bool) -> String {
@@ -68,35 +111,22 @@ fn generate_matches(num_limbs: usize, is_mul: bool) -> String {
     for limbs in 2..(num_limbs + 1) {
         ctx.reset();
 
-        ctx.add_declaration("a", "r", "&mut $a");
-        if is_mul {
-            ctx.add_declaration("b", "r", "&$b");
-        }
-        ctx.add_declaration("modulus", "r", "&$modulus");
-        ctx.add_declaration("0", "i", "0u64");
-        ctx.add_declaration("mod_prime", "i", "$mod_prime");
-
         ctx.add_limb(limbs);
-        if limbs > MAX_REGS {
-            ctx.add_buffer(2 * limbs);
-            ctx.add_declaration("buf", "r", "&mut spill_buffer");
+        ctx.add_operand("rsi", "$a.as_mut_ptr()", false);
+        if is_mul {
+            ctx.add_operand("rdi", "$b.as_ptr()", false);
         }
+        ctx.add_operand("rcx", "$modulus.as_ptr()", false);
+        ctx.add_operand("rdx", "$mod_prime", true);
 
-        let llvm_asm_string = generate_llvm_asm_mul_string(
-            &ctx.clone().get("a"),
-            &ctx.clone().try_get("b", "a"),
-            &ctx.clone().get("modulus"),
-            &ctx.clone().get("0"),
-            &ctx.clone().get("mod_prime"),
-            limbs,
-        );
+        let asm_string = generate_asm_mul_string(limbs, !is_mul);
 
-        ctx.add_llvm_asm(llvm_asm_string);
-        ctx.add_clobber_from_vec(vec!["rcx", "rbx", "rdx", "rax"]);
-        for j in 0..std::cmp::min(limbs, 8) {
-            ctx.add_clobber(REG_CLOBBER[j]);
+        ctx.add_asm(asm_string);
+        ctx.add_clobber("rax");
+        if !is_mul {
+            ctx.add_clobber("rdi");
         }
-        ctx.add_clobber_from_vec(vec!["cc", "memory"]);
+        ctx.add_clobber_from_vec(REG_CLOBBER.to_vec());
         ctx.build();
     }
     ctx.end(num_limbs);
@@ -4,8 +4,14 @@ pub const RCX: &'static str = "%rcx";
 pub const RDX: &'static str = "%rdx";
 pub const RDI: &'static str = "%rdi";
 pub const RSI: &'static str = "%rsi";
+pub const RSP: &'static str = "%rsp";
 pub const R: [&'static str; 8] = ["%r8", "%r9", "%r10", "%r11", "%r12", "%r13", "%r14", "%r15"];
 
+/// Returns whether `operand` is a register rather than a memory location.
+pub fn is_register(operand: &str) -> bool {
+    operand.starts_with('%')
+}
+
 macro_rules! reg {
     ($a_0:ident, $a_1:ident, $a:ident, $range:expr) => {
         let mut $a_0 = Vec::new();
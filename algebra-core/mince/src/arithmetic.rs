@@ -4,54 +4,49 @@ use quote::quote;
 pub fn define_arithmetic() -> TokenStream {
     (quote! {
         {
-            macro_rules! mul_1 {
-                ($a:expr, $b:ident, $zero:ident, $limbs:expr) => {
-                    movq($a, RDX);
-                    mulxq($b[0], R[0], R[1]);
-                    for j in 1..$limbs-1 {
-                        mulxq($b[j], RAX, R[((j + 1) % $limbs)]);
-                        adcxq(RAX, R[j]);
+            // Adds `$src` to the accumulator limb `$slot`. `adcxq` and `adoxq`
+            // only write to registers, so limbs spilled to the stack go through
+            // `$tmp`; `movq` leaves the flags untouched.
+            macro_rules! add_to {
+                ($add:ident, $src:expr, $slot:expr, $tmp:expr) => {
+                    if is_register($slot) {
+                        $add($src, $slot);
+                    } else {
+                        movq($slot, $tmp);
+                        $add($src, $tmp);
+                        movq($tmp, $slot);
                     }
-                    mulxq($b[$limbs-1], RAX, RCX);
-                    movq($zero, RBX);
-                    adcxq(RAX, R[$limbs-1]);
-                    adcxq(RBX, RCX);
                 }
             }
 
+            // t[0..=limbs] = t[0..limbs] + RDX * a
             macro_rules! mul_add_1 {
-                ($a:ident, $b:ident, $zero:ident, $i:ident, $limbs:expr) => {
-                    movq($a[$i], RDX);
-                    for j in 0..$limbs-1 {
-                        mulxq($b[j], RAX, RBX);
-                        adcxq(RAX, R[(j+$i) % $limbs]);
-                        adoxq(RBX, R[(j+$i+1) % $limbs]);
+                ($a:ident, $t:ident, $hi:expr, $tmp:expr, $limbs:expr) => {
+                    for j in 0..$limbs {
+                        mulxq($a[j], RAX, $hi);
+                        add_to!(adcxq, RAX, $t[j], $tmp);
+                        if j < $limbs - 1 {
+                            add_to!(adoxq, $hi, $t[j + 1], $tmp);
+                        } else {
+                            movq($hi, $t[$limbs]);
+                            movq("$0", RAX);
+                            add_to!(adoxq, RAX, $t[$limbs], $tmp);
+                            add_to!(adcxq, RAX, $t[$limbs], $tmp);
+                        }
                     }
-                    mulxq($b[$limbs-1], RAX, RCX);
-                    movq($zero, RBX);
-                    adcxq(RAX, R[($i+$limbs-1) % $limbs]);
-                    adoxq(RBX, RCX);
-                    adcxq(RBX, RCX);
                 }
             }
 
+            // t[0..=limbs] += RDX * a, after which t[0] is zero and dropped.
             macro_rules! mul_add_shift_1 {
-                ($a:ident, $mod_prime:ident, $zero:ident, $i:ident, $limbs:expr) => {
-                    movq($mod_prime, RDX);
-                    mulxq(R[$i], RDX, RAX);
-                    mulxq($a[0], RAX, RBX);
-                    adcxq(R[$i % $limbs], RAX);
-                    adoxq(RBX, R[($i+1) % $limbs]);
-                    for j in 1..$limbs-1 {
-                        mulxq($a[j], RAX, RBX);
-                        adcxq(RAX, R[(j+$i) % $limbs]);
-                        adoxq(RBX, R[(j+$i+1) % $limbs]);
+                ($a:ident, $t:ident, $hi:expr, $tmp:expr, $limbs:expr) => {
+                    for j in 0..$limbs {
+                        mulxq($a[j], RAX, $hi);
+                        add_to!(adcxq, RAX, $t[j], $tmp);
+                        add_to!(adoxq, $hi, $t[j + 1], $tmp);
                     }
-                    mulxq($a[$limbs-1], RAX, R[$i % $limbs]);
-                    movq($zero, RBX);
-                    adcxq(RAX, R[($i+$limbs-1) % $limbs]);
-                    adoxq(RCX, R[$i % $limbs]);
-                    adcxq(RBX, R[$i % $limbs]);
+                    movq("$0", RAX);
+                    add_to!(adcxq, RAX, $t[$limbs], $tmp);
                 }
             }
         }
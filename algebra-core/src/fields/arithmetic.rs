@@ -1,33 +1,58 @@
+/// Returns whether the CPU supports the `adcxq`, `adoxq` and `mulxq`
+/// instructions used by the assembly backend.
+///
+/// If `bmi2` and `adx` are enabled target features, this is known at compile
+/// time. Otherwise, it is detected once at runtime, so that binaries built for
+/// generic `x86_64` still use the assembly backend on capable CPUs. Without
+/// `std`, runtime detection is unavailable and the portable implementation is
+/// used.
+#[cfg(use_asm)]
+#[inline]
+pub(crate) fn has_adx_bmi2() -> bool {
+    if cfg!(all(target_feature = "bmi2", target_feature = "adx")) {
+        return true;
+    }
+    #[cfg(feature = "std")]
+    {
+        use core::sync::atomic::{AtomicU8, Ordering};
+
+        const UNKNOWN: u8 = 0;
+        const UNSUPPORTED: u8 = 1;
+        const SUPPORTED: u8 = 2;
+        static SUPPORT: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+        match SUPPORT.load(Ordering::Relaxed) {
+            UNKNOWN => {
+                let supported = is_x86_feature_detected!("bmi2") && is_x86_feature_detected!("adx");
+                let support = if supported { SUPPORTED } else { UNSUPPORTED };
+                SUPPORT.store(support, Ordering::Relaxed);
+                supported
+            },
+            support => support == SUPPORTED,
+        }
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        false
+    }
+}
+
 /// This modular multiplication algorithm uses Montgomery
 /// reduction for efficient implementation. It also additionally
 /// uses the "no-carry optimization" outlined
 /// [here](https://hackmd.io/@zkteam/modular_multiplication) if
 /// `P::MODULUS` has (a) a non-zero MSB, and (b) at least one
 /// zero bit in the rest of the modulus.
-macro_rules! impl_field_mul_assign {
-    ($limbs:expr) => {
+macro_rules! impl_field_mul_without_asm {
+    ($vis:vis, $limbs:expr) => {
+        /// Multiplies `self` by `other` in portable Rust, even if the assembly
+        /// backend is enabled.
+        #[doc(hidden)]
         #[inline]
         #[unroll_for_loops]
-        fn mul_assign(&mut self, other: &Self) {
-            // Checking the modulus at compile time
-            let first_bit_set = P::MODULUS.0[$limbs - 1] >> 63 != 0;
-            let mut all_bits_set = P::MODULUS.0[$limbs - 1] == !0 - (1 << 63);
-            for i in 1..$limbs {
-                all_bits_set &= P::MODULUS.0[$limbs - i - 1] == !0u64;
-            }
-            let _no_carry: bool = !(first_bit_set || all_bits_set);
-
+        $vis fn mul_assign_without_asm(&mut self, other: &Self) {
             // No-carry optimisation applied to CIOS
-            if _no_carry {
-                #[cfg(use_asm)]
-                #[allow(unsafe_code, unused_mut)]
-                {
-                    if $limbs <= 6 {
-                        llvm_asm_mul!($limbs, (self.0).0, (other.0).0, P::MODULUS.0, P::INV);
-                        self.reduce();
-                        return;
-                    }
-                }
+            if Self::can_use_no_carry_mul() {
                 let mut r = [0u64; $limbs];
                 let mut carry1 = 0u64;
                 let mut carry2 = 0u64;
@@ -75,6 +100,46 @@ macro_rules! impl_field_mul_assign {
     };
 }
 
+/// Multiplies with the assembly backend if it is enabled, the CPU supports it
+/// and the modulus allows the no-carry optimization, and in portable Rust
+/// otherwise.
+macro_rules! impl_field_mul_assign {
+    ($limbs:expr) => {
+        #[inline]
+        fn mul_assign(&mut self, other: &Self) {
+            #[cfg(use_asm)]
+            #[allow(unsafe_code, unused_mut)]
+            {
+                if Self::can_use_no_carry_mul() && has_adx_bmi2() {
+                    asm_mul!($limbs, (self.0).0, (other.0).0, P::MODULUS.0, P::INV);
+                    self.reduce();
+                    return;
+                }
+            }
+            self.mul_assign_without_asm(other)
+        }
+    };
+}
+
+/// Returns whether the "no-carry" optimization applies to `P::MODULUS`, that
+/// is, whether its most significant bit is zero and at least one other bit is
+/// zero.
+macro_rules! impl_field_can_use_no_carry_mul {
+    ($limbs:expr) => {
+        #[inline]
+        #[unroll_for_loops]
+        fn can_use_no_carry_mul() -> bool {
+            // Checking the modulus at compile time
+            let first_bit_set = P::MODULUS.0[$limbs - 1] >> 63 != 0;
+            let mut all_bits_set = P::MODULUS.0[$limbs - 1] == !0 - (1 << 63);
+            for i in 1..$limbs {
+                all_bits_set &= P::MODULUS.0[$limbs - i - 1] == !0u64;
+            }
+            !(first_bit_set || all_bits_set)
+        }
+    };
+}
+
 macro_rules! impl_field_into_repr {
     ($limbs:expr, $BigIntegerType:ty) => {
         #[inline]
@@ -103,26 +168,30 @@ macro_rules! impl_field_into_repr {
 macro_rules! impl_field_square_in_place {
     ($limbs: expr) => {
         #[inline]
-        #[unroll_for_loops]
-        #[allow(unused_braces)]
         fn square_in_place(&mut self) -> &mut Self {
-            // Checking the modulus at compile time
-            let first_bit_set = P::MODULUS.0[$limbs - 1] >> 63 != 0;
-            let mut all_bits_set = P::MODULUS.0[$limbs - 1] == !0 - (1 << 63);
-            for i in 1..$limbs {
-                all_bits_set &= P::MODULUS.0[$limbs - i - 1] == !0u64;
-            }
-            let _no_carry: bool = !(first_bit_set || all_bits_set);
-
             #[cfg(use_asm)]
             #[allow(unsafe_code, unused_mut)]
             {
-                if $limbs <= 6 && _no_carry {
-                    llvm_asm_square!($limbs, (self.0).0, P::MODULUS.0, P::INV);
+                if Self::can_use_no_carry_mul() && has_adx_bmi2() {
+                    asm_square!($limbs, (self.0).0, P::MODULUS.0, P::INV);
                     self.reduce();
                     return self;
                 }
             }
+            self.square_in_place_without_asm()
+        }
+    };
+}
+
+macro_rules! impl_field_square_without_asm {
+    ($vis:vis, $limbs: expr) => {
+        /// Squares `self` in portable Rust, even if the assembly backend is
+        /// enabled.
+        #[doc(hidden)]
+        #[inline]
+        #[unroll_for_loops]
+        #[allow(unused_braces)]
+        $vis fn square_in_place_without_asm(&mut self) -> &mut Self {
             let mut r = [0u64; $limbs * 2];
 
             let mut carry = 0;
@@ -38,6 +38,20 @@ macro_rules! impl_Fp {
                     self.0.sub_noborrow(&P::MODULUS);
                 }
             }
+
+            impl_field_can_use_no_carry_mul!($limbs);
+
+            // The portable arithmetic is only public so that it can be tested
+            // against the assembly backend.
+            #[cfg(feature = "portable-arithmetic")]
+            impl_field_mul_without_asm!(pub, $limbs);
+            #[cfg(not(feature = "portable-arithmetic"))]
+            impl_field_mul_without_asm!(pub(crate), $limbs);
+
+            #[cfg(feature = "portable-arithmetic")]
+            impl_field_square_without_asm!(pub, $limbs);
+            #[cfg(not(feature = "portable-arithmetic"))]
+            impl_field_square_without_asm!(pub(crate), $limbs);
         }
 
         impl<P: $FpParameters> Zero for $Fp<P> {
@@ -20,7 +20,9 @@ use crate::{
 };
 
 #[cfg(use_asm)]
-use std::mem::MaybeUninit;
+use crate::fields::arithmetic::has_adx_bmi2;
+#[cfg(use_asm)]
+use core::arch::asm;
 
 #[cfg(use_asm)]
 include!(concat!(env!("OUT_DIR"), "/field_assembly.rs"));
@@ -17,7 +17,9 @@ use crate::{
     bls12_381::{
         Fq, Fq12, Fq12Parameters, Fq2, Fq2Parameters, Fq6, Fq6Parameters, FqParameters, Fr,
     },
-    tests::fields::{field_test, frobenius_test, primefield_test, sqrt_field_test},
+    tests::fields::{
+        field_test, frobenius_test, portable_arithmetic_test, primefield_test, sqrt_field_test,
+    },
 };
 
 pub(crate) const ITERATIONS: usize = 5;
@@ -30,6 +32,7 @@ fn test_fr() {
         let b: Fr = UniformRand::rand(&mut rng);
         field_test(a, b);
         primefield_test::<Fr>();
+        portable_arithmetic_test::<Fr>(Fr::mul_assign_without_asm, Fr::square_in_place_without_asm);
         sqrt_field_test(b);
     }
 }
@@ -42,6 +45,7 @@ fn test_fq() {
         let b: Fq = UniformRand::rand(&mut rng);
         field_test(a, b);
         primefield_test::<Fq>();
+        portable_arithmetic_test::<Fq>(Fq::mul_assign_without_asm, Fq::square_in_place_without_asm);
         sqrt_field_test(a);
     }
 }
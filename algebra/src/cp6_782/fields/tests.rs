@@ -4,7 +4,8 @@ use rand::Rng;
 use crate::cp6_782::*;
 
 use crate::tests::fields::{
-    field_serialization_test, field_test, frobenius_test, primefield_test, sqrt_field_test,
+    field_serialization_test, field_test, frobenius_test, portable_arithmetic_test,
+    primefield_test, sqrt_field_test,
 };
 
 #[test]
@@ -15,6 +16,7 @@ fn test_fr() {
     field_test(a, b);
     sqrt_field_test(a);
     primefield_test::<Fr>();
+    portable_arithmetic_test::<Fr>(Fr::mul_assign_without_asm, Fr::square_in_place_without_asm);
 }
 
 #[test]
@@ -24,6 +26,7 @@ fn test_fq() {
     let b: Fq = rng.gen();
     field_test(a, b);
     primefield_test::<Fq>();
+    portable_arithmetic_test::<Fq>(Fq::mul_assign_without_asm, Fq::square_in_place_without_asm);
     sqrt_field_test(a);
 
     let byte_size = a.serialized_size();
@@ -3,7 +3,9 @@ use rand::Rng;
 
 use crate::mnt6_753::*;
 
-use crate::tests::fields::{field_test, frobenius_test, primefield_test, sqrt_field_test};
+use crate::tests::fields::{
+    field_test, frobenius_test, portable_arithmetic_test, primefield_test, sqrt_field_test,
+};
 
 #[test]
 fn test_fr() {
@@ -13,6 +15,7 @@ fn test_fr() {
     field_test(a, b);
     sqrt_field_test(a);
     primefield_test::<Fr>();
+    portable_arithmetic_test::<Fr>(Fr::mul_assign_without_asm, Fr::square_in_place_without_asm);
 }
 
 #[test]
@@ -23,6 +26,7 @@ fn test_fq() {
     field_test(a, b);
     sqrt_field_test(a);
     primefield_test::<Fq>();
+    portable_arithmetic_test::<Fq>(Fq::mul_assign_without_asm, Fq::square_in_place_without_asm);
 }
 
 #[test]
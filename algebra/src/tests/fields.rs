@@ -347,6 +347,26 @@ pub fn primefield_test<F: PrimeField>() {
     fft_field_test::<F>();
 }
 
+/// Checks that `mul_assign` and `square_in_place` agree with the portable
+/// implementations `mul` and `square`, which differ from them when the
+/// assembly backend is enabled.
+pub fn portable_arithmetic_test<F: PrimeField>(mul: fn(&mut F, &F), square: fn(&mut F) -> &mut F) {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    let mut elements = vec![F::zero(), F::one(), -F::one(), F::one().double()];
+    elements.extend((0..ITERATIONS).map(|_| F::rand(&mut rng)));
+
+    for a in &elements {
+        for b in &elements {
+            let mut expected = *a;
+            mul(&mut expected, b);
+            assert_eq!(*a * b, expected);
+        }
+        let mut expected = *a;
+        square(&mut expected);
+        assert_eq!(a.square(), expected);
+    }
+}
+
 pub fn sqrt_field_test<F: SquareRootField>(elem: F) {
     let square = elem.square();
     let sqrt = square.sqrt().unwrap();
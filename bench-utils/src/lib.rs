@@ -6,6 +6,7 @@ pub use self::inner::*;
 #[macro_use]
 #[cfg(feature = "print-trace")]
 pub mod inner {
+    pub use crate::span_tree::*;
     pub use colored::Colorize;
     use std::sync::atomic::AtomicUsize;
     pub static NUM_INDENT: AtomicUsize = AtomicUsize::new(0);
@@ -15,6 +16,9 @@ pub mod inner {
     pub struct TimerInfo {
         pub msg: String,
         pub time: Instant,
+        /// The span recording this timer, if it was started while the span
+        /// tree sink was selected.
+        pub span: Option<SpanId>,
     }
 
     #[macro_export]
@@ -24,15 +28,19 @@ pub mod inner {
             use $crate::{compute_indent, Colorize, NUM_INDENT, PAD_CHAR};
 
             let msg = $msg();
-            let start_info = "Start:".yellow().bold();
-            let indent_amount = 2 * NUM_INDENT.fetch_add(0, Ordering::Relaxed);
-            let indent = compute_indent(indent_amount);
-
-            println!("{}{:8} {}", indent, start_info, msg);
-            NUM_INDENT.fetch_add(1, Ordering::Relaxed);
+            let span = $crate::open_span(&msg.to_string());
+            if span.is_none() {
+                let start_info = "Start:".yellow().bold();
+                let indent_amount = 2 * NUM_INDENT.fetch_add(0, Ordering::Relaxed);
+                let indent = compute_indent(indent_amount);
+
+                println!("{}{:8} {}", indent, start_info, msg);
+                NUM_INDENT.fetch_add(1, Ordering::Relaxed);
+            }
             $crate::TimerInfo {
                 msg: msg.to_string(),
                 time: Instant::now(),
+                span,
             }
         }};
     }
@@ -48,39 +56,43 @@ pub mod inner {
 
             let time = $time.time;
             let final_time = time.elapsed();
-            let final_time = {
-                let secs = final_time.as_secs();
-                let millis = final_time.subsec_millis();
-                let micros = final_time.subsec_micros() % 1000;
-                let nanos = final_time.subsec_nanos() % 1000;
-                if secs != 0 {
-                    format!("{}.{}s", secs, millis).bold()
-                } else if millis > 0 {
-                    format!("{}.{}ms", millis, micros).bold()
-                } else if micros > 0 {
-                    format!("{}.{}µs", micros, nanos).bold()
-                } else {
-                    format!("{}ns", final_time.subsec_nanos()).bold()
-                }
-            };
-
-            let end_info = "End:".green().bold();
-            let message = format!("{} {}", $time.msg, $msg());
-
-            NUM_INDENT.fetch_sub(1, Ordering::Relaxed);
-            let indent_amount = 2 * NUM_INDENT.fetch_add(0, Ordering::Relaxed);
-            let indent = compute_indent(indent_amount);
-
-            // Todo: Recursively ensure that *entire* string is of appropriate
-            // width (not just message).
-            println!(
-                "{}{:8} {:.<pad$}{}",
-                indent,
-                end_info,
-                message,
-                final_time,
-                pad = 75 - indent_amount
-            );
+            if let Some(span) = $time.span {
+                $crate::close_span(span, final_time, &$msg().to_string());
+            } else {
+                let final_time = {
+                    let secs = final_time.as_secs();
+                    let millis = final_time.subsec_millis();
+                    let micros = final_time.subsec_micros() % 1000;
+                    let nanos = final_time.subsec_nanos() % 1000;
+                    if secs != 0 {
+                        format!("{}.{}s", secs, millis).bold()
+                    } else if millis > 0 {
+                        format!("{}.{}ms", millis, micros).bold()
+                    } else if micros > 0 {
+                        format!("{}.{}µs", micros, nanos).bold()
+                    } else {
+                        format!("{}ns", final_time.subsec_nanos()).bold()
+                    }
+                };
+
+                let end_info = "End:".green().bold();
+                let message = format!("{} {}", $time.msg, $msg());
+
+                NUM_INDENT.fetch_sub(1, Ordering::Relaxed);
+                let indent_amount = 2 * NUM_INDENT.fetch_add(0, Ordering::Relaxed);
+                let indent = compute_indent(indent_amount);
+
+                // Todo: Recursively ensure that *entire* string is of appropriate
+                // width (not just message).
+                println!(
+                    "{}{:8} {:.<pad$}{}",
+                    indent,
+                    end_info,
+                    message,
+                    final_time,
+                    pad = 75 - indent_amount
+                );
+            }
         }};
     }
 
@@ -92,27 +104,30 @@ pub mod inner {
                 compute_indent, compute_indent_whitespace, Colorize, NUM_INDENT, PAD_CHAR,
             };
 
-            let start_msg = "StartMsg".yellow().bold();
-            let end_msg = "EndMsg".green().bold();
             let title = $title();
-            let start_msg = format!("{}: {}", start_msg, title);
-            let end_msg = format!("{}: {}", end_msg, title);
-
-            let start_indent_amount = 2 * NUM_INDENT.fetch_add(0, Ordering::Relaxed);
-            let start_indent = compute_indent(start_indent_amount);
+            let msg = $msg();
+            if !$crate::add_note(&title.to_string(), &msg.to_string()) {
+                let start_msg = "StartMsg".yellow().bold();
+                let end_msg = "EndMsg".green().bold();
+                let start_msg = format!("{}: {}", start_msg, title);
+                let end_msg = format!("{}: {}", end_msg, title);
+
+                let start_indent_amount = 2 * NUM_INDENT.fetch_add(0, Ordering::Relaxed);
+                let start_indent = compute_indent(start_indent_amount);
+
+                let msg_indent_amount = 2 * NUM_INDENT.fetch_add(0, Ordering::Relaxed) + 2;
+                let msg_indent = compute_indent_whitespace(msg_indent_amount);
+                let mut final_message = "\n".to_string();
+                for line in msg.lines() {
+                    final_message += &format!("{}{}\n", msg_indent, line,);
+                }
 
-            let msg_indent_amount = 2 * NUM_INDENT.fetch_add(0, Ordering::Relaxed) + 2;
-            let msg_indent = compute_indent_whitespace(msg_indent_amount);
-            let mut final_message = "\n".to_string();
-            for line in $msg().lines() {
-                final_message += &format!("{}{}\n", msg_indent, line,);
+                // Todo: Recursively ensure that *entire* string is of appropriate
+                // width (not just message).
+                println!("{}{}", start_indent, start_msg);
+                println!("{}{}", msg_indent, final_message,);
+                println!("{}{}", start_indent, end_msg);
             }
-
-            // Todo: Recursively ensure that *entire* string is of appropriate
-            // width (not just message).
-            println!("{}{}", start_indent, start_msg);
-            println!("{}{}", msg_indent, final_message,);
-            println!("{}{}", start_indent, end_msg);
         }};
     }
 
@@ -163,6 +178,10 @@ mod inner {
     }
 }
 
+// Declared after `inner`, so that its tests can use the timer macros.
+#[cfg(feature = "print-trace")]
+pub mod span_tree;
+
 mod tests {
     use super::*;
 
@@ -0,0 +1,444 @@
+//! A trace sink that records timers as a tree of spans instead of printing
+//! them, so that it can be exported in machine-readable formats.
+//!
+//! Spans opened on a thread nest under the innermost span that is open on the
+//! same thread. Spans opened on a thread without open spans of its own, such
+//! as a rayon worker, nest under the innermost span open on a thread whose
+//! spans are not nested in this way, which is usually the thread that started
+//! the parallel region.
+
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Mutex, MutexGuard,
+    },
+    thread::{self, ThreadId},
+    time::{Duration, Instant},
+};
+
+/// Where `start_timer!`, `end_timer!` and `add_to_trace!` send their output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceSink {
+    /// Print coloured, indented text to stdout. This is the default.
+    Print,
+    /// Record a tree of spans, which can be retrieved with
+    /// [`take_span_tree`].
+    SpanTree,
+}
+
+static TRACE_SINK: AtomicU8 = AtomicU8::new(TraceSink::Print as u8);
+
+/// Selects where timers are sent from now on. Timers that are already running
+/// are still sent to the sink that was selected when they were started.
+pub fn set_trace_sink(sink: TraceSink) {
+    TRACE_SINK.store(sink as u8, Ordering::Relaxed);
+}
+
+/// Returns the currently selected trace sink.
+pub fn trace_sink() -> TraceSink {
+    if TRACE_SINK.load(Ordering::Relaxed) == TraceSink::SpanTree as u8 {
+        TraceSink::SpanTree
+    } else {
+        TraceSink::Print
+    }
+}
+
+/// Identifies a span opened by `start_timer!`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpanId {
+    generation: u64,
+    index: usize,
+}
+
+struct SpanRecord {
+    name: String,
+    parent: Option<usize>,
+    thread: String,
+    start: Duration,
+    duration: Option<Duration>,
+    end_message: Option<String>,
+    notes: Vec<(String, String)>,
+}
+
+struct Recorder {
+    /// Incremented by `take_span_tree`, so that spans taken while open are not
+    /// closed in the next tree.
+    generation: u64,
+    epoch: Option<Instant>,
+    spans: Vec<SpanRecord>,
+    /// The stack of open spans of each thread.
+    open: Vec<(ThreadId, Vec<usize>)>,
+}
+
+static RECORDER: Mutex<Recorder> = Mutex::new(Recorder {
+    generation: 0,
+    epoch: None,
+    spans: Vec::new(),
+    open: Vec::new(),
+});
+
+fn recorder() -> MutexGuard<'static, Recorder> {
+    // A panic while recording cannot leave the recorder in an inconsistent
+    // state, so poisoning is ignored.
+    RECORDER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Recorder {
+    fn open_stack(&mut self, thread: ThreadId) -> &mut Vec<usize> {
+        match self.open.iter().position(|(t, _)| *t == thread) {
+            Some(i) => &mut self.open[i].1,
+            None => {
+                self.open.push((thread, Vec::new()));
+                &mut self.open.last_mut().unwrap().1
+            },
+        }
+    }
+
+    /// The span under which a span opened on `thread` is nested.
+    fn current_span(&self, thread: ThreadId) -> Option<usize> {
+        let own = self
+            .open
+            .iter()
+            .find(|(t, _)| *t == thread)
+            .and_then(|(_, stack)| stack.last());
+        if own.is_some() {
+            return own.copied();
+        }
+        self.open
+            .iter()
+            .filter(
+                |(_, stack)| matches!(stack.first(), Some(&i) if self.spans[i].parent.is_none()),
+            )
+            .filter_map(|(_, stack)| stack.last().copied())
+            .max_by_key(|&i| self.spans[i].start)
+    }
+}
+
+fn thread_name() -> String {
+    let thread = thread::current();
+    match thread.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", thread.id()),
+    }
+}
+
+/// Opens a span named `name` if the span tree sink is selected.
+#[doc(hidden)]
+pub fn open_span(name: &str) -> Option<SpanId> {
+    if trace_sink() != TraceSink::SpanTree {
+        return None;
+    }
+    let now = Instant::now();
+    let thread = thread::current().id();
+    let mut recorder = recorder();
+    let epoch = *recorder.epoch.get_or_insert(now);
+    let parent = recorder.current_span(thread);
+    let index = recorder.spans.len();
+    recorder.spans.push(SpanRecord {
+        name: name.to_string(),
+        parent,
+        thread: thread_name(),
+        start: now - epoch,
+        duration: None,
+        end_message: None,
+        notes: Vec::new(),
+    });
+    recorder.open_stack(thread).push(index);
+    Some(SpanId {
+        generation: recorder.generation,
+        index,
+    })
+}
+
+/// Closes the span `id` after it ran for `duration`.
+#[doc(hidden)]
+pub fn close_span(id: SpanId, duration: Duration, end_message: &str) {
+    let mut recorder = recorder();
+    if id.generation != recorder.generation {
+        return;
+    }
+    let span = &mut recorder.spans[id.index];
+    span.duration = Some(duration);
+    if !end_message.is_empty() {
+        span.end_message = Some(end_message.to_string());
+    }
+    // Timers are usually, but not necessarily, ended on the thread and in the
+    // order they were started in.
+    for (_, stack) in &mut recorder.open {
+        if let Some(i) = stack.iter().rposition(|&i| i == id.index) {
+            stack.remove(i);
+            break;
+        }
+    }
+    recorder.open.retain(|(_, stack)| !stack.is_empty());
+}
+
+/// Attaches a message to the innermost open span if the span tree sink is
+/// selected, and returns whether it did.
+#[doc(hidden)]
+pub fn add_note(title: &str, message: &str) -> bool {
+    if trace_sink() != TraceSink::SpanTree {
+        return false;
+    }
+    let mut recorder = recorder();
+    if let Some(i) = recorder.current_span(thread::current().id()) {
+        recorder.spans[i]
+            .notes
+            .push((title.to_string(), message.to_string()));
+    }
+    true
+}
+
+/// Returns the spans recorded so far, and starts recording a new tree.
+///
+/// Spans that are still open are included without a duration, and are not
+/// recorded when they are closed later on.
+pub fn take_span_tree() -> SpanTree {
+    let mut recorder = recorder();
+    let records = std::mem::take(&mut recorder.spans);
+    recorder.generation += 1;
+    recorder.epoch = None;
+    recorder.open.clear();
+    drop(recorder);
+
+    // Parents are always recorded before their children.
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); records.len()];
+    let mut roots = Vec::new();
+    for (i, record) in records.iter().enumerate() {
+        match record.parent {
+            Some(parent) => children[parent].push(i),
+            None => roots.push(i),
+        }
+    }
+    let mut records: Vec<Option<SpanRecord>> = records.into_iter().map(Some).collect();
+    fn build(i: usize, records: &mut [Option<SpanRecord>], children: &[Vec<usize>]) -> Span {
+        let record = records[i].take().unwrap();
+        Span {
+            name: record.name,
+            thread: record.thread,
+            start: record.start,
+            duration: record.duration,
+            end_message: record.end_message,
+            notes: record.notes,
+            children: children[i]
+                .iter()
+                .map(|&child| build(child, records, children))
+                .collect(),
+        }
+    }
+    SpanTree {
+        roots: roots
+            .into_iter()
+            .map(|i| build(i, &mut records, &children))
+            .collect(),
+    }
+}
+
+/// A span recorded between a `start_timer!` and the matching `end_timer!`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// The message passed to `start_timer!`.
+    pub name: String,
+    /// The name of the thread that started the timer, or its id if it is
+    /// unnamed.
+    pub thread: String,
+    /// The time at which the timer was started, relative to the first span of
+    /// the tree.
+    pub start: Duration,
+    /// How long the timer ran, or `None` if it had not ended yet.
+    pub duration: Option<Duration>,
+    /// The message passed to `end_timer!`, if any.
+    pub end_message: Option<String>,
+    /// The titles and messages passed to `add_to_trace!` while this was the
+    /// innermost open span.
+    pub notes: Vec<(String, String)>,
+    /// The spans nested in this one, by start time.
+    pub children: Vec<Span>,
+}
+
+/// A forest of spans recorded by the [`TraceSink::SpanTree`] sink.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpanTree {
+    /// The outermost spans, by start time.
+    pub roots: Vec<Span>,
+}
+
+impl SpanTree {
+    /// Exports `self` as JSON. Times are in nanoseconds, and the duration of
+    /// spans that had not ended is `null`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"spans\":");
+        write_json_spans(&mut json, &self.roots);
+        json.push('}');
+        json
+    }
+
+    /// Exports `self` as folded stacks, as consumed by `flamegraph.pl` and
+    /// `inferno`: one line per span with the names of its enclosing spans and
+    /// its own, separated by `;`, followed by the nanoseconds spent in the
+    /// span but not in its children.
+    ///
+    /// Children that ran in parallel may take longer than their parent in
+    /// total, in which case the parent's own time is zero and it is omitted.
+    pub fn to_folded_stacks(&self) -> String {
+        let mut folded = String::new();
+        let mut stack = Vec::new();
+        for span in &self.roots {
+            write_folded_stacks(&mut folded, &mut stack, span);
+        }
+        folded
+    }
+}
+
+fn write_json_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+fn write_json_spans(json: &mut String, spans: &[Span]) {
+    json.push('[');
+    for (i, span) in spans.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str("{\"name\":");
+        write_json_string(json, &span.name);
+        json.push_str(",\"thread\":");
+        write_json_string(json, &span.thread);
+        write!(json, ",\"start_ns\":{}", span.start.as_nanos()).unwrap();
+        match span.duration {
+            Some(duration) => write!(json, ",\"duration_ns\":{}", duration.as_nanos()).unwrap(),
+            None => json.push_str(",\"duration_ns\":null"),
+        }
+        json.push_str(",\"end_message\":");
+        match &span.end_message {
+            Some(message) => write_json_string(json, message),
+            None => json.push_str("null"),
+        }
+        json.push_str(",\"notes\":[");
+        for (j, (title, message)) in span.notes.iter().enumerate() {
+            if j > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"title\":");
+            write_json_string(json, title);
+            json.push_str(",\"message\":");
+            write_json_string(json, message);
+            json.push('}');
+        }
+        json.push_str("],\"children\":");
+        write_json_spans(json, &span.children);
+        json.push('}');
+    }
+    json.push(']');
+}
+
+fn write_folded_stacks(folded: &mut String, stack: &mut Vec<String>, span: &Span) {
+    // `;` separates frames and each stack takes up one line.
+    stack.push(span.name.replace(';', ",").replace('\n', " "));
+    if let Some(duration) = span.duration {
+        let children: Duration = span.children.iter().filter_map(|c| c.duration).sum();
+        let own = duration.checked_sub(children).unwrap_or_default();
+        if own > Duration::default() {
+            writeln!(folded, "{} {}", stack.join(";"), own.as_nanos()).unwrap();
+        }
+    }
+    for child in &span.children {
+        write_folded_stacks(folded, stack, child);
+    }
+    stack.pop();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(spans: &'a [Span], name: &str) -> Option<&'a Span> {
+        spans.iter().find_map(|span| {
+            if span.name == name {
+                Some(span)
+            } else {
+                find(&span.children, name)
+            }
+        })
+    }
+
+    // Other tests may print timers concurrently, so a single test switches the
+    // sink and only looks at its own spans.
+    #[test]
+    fn records_span_tree() {
+        set_trace_sink(TraceSink::SpanTree);
+        let outer = start_timer!(|| "span_tree_outer");
+        let inner = start_timer!(|| "span_tree;inner");
+        add_to_trace!(|| "title", || "a \"quoted\"\nmessage");
+        end_timer!(inner, || "done");
+        std::thread::scope(|s| {
+            for _ in 0..2 {
+                s.spawn(|| {
+                    let worker = start_timer!(|| "span_tree_worker");
+                    end_timer!(worker);
+                });
+            }
+        });
+        std::thread::sleep(Duration::from_millis(1));
+        end_timer!(outer);
+        let unfinished = start_timer!(|| "span_tree_unfinished");
+        let tree = take_span_tree();
+        set_trace_sink(TraceSink::Print);
+        end_timer!(unfinished);
+
+        let outer = find(&tree.roots, "span_tree_outer").unwrap();
+        let inner = find(&outer.children, "span_tree;inner").unwrap();
+        assert_eq!(inner.end_message.as_deref(), Some("done"));
+        assert_eq!(
+            inner.notes,
+            vec![("title".to_string(), "a \"quoted\"\nmessage".to_string())]
+        );
+        assert!(inner.start >= outer.start);
+        assert!(inner.duration.unwrap() <= outer.duration.unwrap());
+        let workers: Vec<_> = outer
+            .children
+            .iter()
+            .filter(|span| span.name == "span_tree_worker")
+            .collect();
+        assert_eq!(workers.len(), 2);
+        for worker in workers {
+            assert_ne!(worker.thread, outer.thread);
+            assert!(worker.duration.is_some());
+        }
+        assert!(find(&tree.roots, "span_tree_unfinished")
+            .unwrap()
+            .duration
+            .is_none());
+
+        let json = tree.to_json();
+        assert!(json.starts_with("{\"spans\":["));
+        assert!(json.contains("\"name\":\"span_tree;inner\""));
+        assert!(json.contains("\"message\":\"a \\\"quoted\\\"\\nmessage\""));
+        assert!(json.contains("\"duration_ns\":null"));
+
+        let folded = tree.to_folded_stacks();
+        assert!(folded
+            .lines()
+            .any(|line| line.starts_with("span_tree_outer ")));
+        assert!(folded
+            .lines()
+            .any(|line| line.starts_with("span_tree_outer;span_tree,inner ")));
+        assert!(!folded.contains("span_tree_unfinished"));
+
+        // Ending a timer taken while open does not leak into the next tree.
+        assert!(find(&take_span_tree().roots, "span_tree_unfinished").is_none());
+    }
+}
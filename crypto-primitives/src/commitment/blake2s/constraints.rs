@@ -105,7 +105,7 @@ impl<ConstraintF: PrimeField> AllocGadget<[u8; 32], ConstraintF> for Blake2sRand
         T: Borrow<[u8; 32]>,
     {
         let zeros = [0u8; 32];
-        let value = match value_gen() {
+        let value = match cs.compute_assignment(value_gen) {
             Ok(val) => *(val.borrow()),
             Err(_) => zeros,
         };
@@ -124,7 +124,7 @@ impl<ConstraintF: PrimeField> AllocGadget<[u8; 32], ConstraintF> for Blake2sRand
         T: Borrow<[u8; 32]>,
     {
         let zeros = [0u8; 32];
-        let value = match value_gen() {
+        let value = match cs.compute_assignment(value_gen) {
             Ok(val) => *(val.borrow()),
             Err(_) => zeros,
         };
@@ -5,7 +5,7 @@ use crate::{
 };
 use algebra_core::{
     fields::{Field, PrimeField},
-    to_bytes, Group, ToBytes,
+    to_bytes, Group, ToBytes, Zero,
 };
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
@@ -175,8 +175,19 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<PedersenRandomness<G>>,
     {
-        let temp = value_gen()?;
-        let randomness = to_bytes![temp.borrow().0].unwrap();
+        // The randomness has a fixed length, so that it can be allocated
+        // without an assignment during setup.
+        let randomness = match cs.compute_assignment(value_gen) {
+            Ok(randomness) => to_bytes![randomness.borrow().0]
+                .unwrap()
+                .into_iter()
+                .map(Some)
+                .collect(),
+            Err(SynthesisError::AssignmentMissing) if cs.is_in_setup_mode() => {
+                vec![None; to_bytes![G::ScalarField::zero()].unwrap().len()]
+            },
+            Err(e) => return Err(e),
+        };
         Ok(PedersenRandomnessGadget(UInt8::alloc_vec(cs, &randomness)?))
     }
 
@@ -188,7 +199,7 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<PedersenRandomness<G>>,
     {
-        let temp = value_gen()?;
+        let temp = cs.compute_assignment(value_gen)?;
         let randomness = to_bytes![temp.borrow().0].unwrap();
         Ok(PedersenRandomnessGadget(UInt8::alloc_input_vec(
             cs,
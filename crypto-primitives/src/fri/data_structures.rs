@@ -0,0 +1,189 @@
+use crate::{
+    merkle_tree::{MerkleTreeConfig, MerkleTreeDigest, MerkleTreePath},
+    Vec,
+};
+use algebra_core::{
+    bytes::ToBytes,
+    io::{Read, Result as IoResult, Write},
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+    Field,
+};
+
+/// The parameters of the FRI protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FRIParameters {
+    /// The tested polynomial must have degree less than `degree_bound`.
+    pub degree_bound: usize,
+    /// The evaluation domain must have at least `degree_bound * blowup_factor`
+    /// elements.
+    pub blowup_factor: usize,
+    /// The number of evaluations that are combined into one in every round.
+    pub folding_factor: usize,
+    /// The number of query repetitions performed by the verifier.
+    pub num_queries: usize,
+}
+
+impl FRIParameters {
+    /// Creates a new set of parameters.
+    pub fn new(
+        degree_bound: usize,
+        blowup_factor: usize,
+        folding_factor: usize,
+        num_queries: usize,
+    ) -> Self {
+        Self {
+            degree_bound,
+            blowup_factor,
+            folding_factor,
+            num_queries,
+        }
+    }
+
+    /// Returns the number of folding rounds for an evaluation domain of size
+    /// `domain_size`, together with the sizes of the domains of all layers
+    /// and the degree bound for the final polynomial.
+    ///
+    /// Folding stops once the degree bound reaches 1, or once the size of
+    /// the current domain is no longer divisible by the folding factor.
+    pub(crate) fn layer_sizes(&self, domain_size: usize) -> (Vec<usize>, usize) {
+        let mut sizes = vec![domain_size];
+        let mut degree_bound = self.degree_bound;
+        let mut size = domain_size;
+        while degree_bound > 1 && self.folding_factor > 1 && size % self.folding_factor == 0 {
+            size /= self.folding_factor;
+            degree_bound = (degree_bound + self.folding_factor - 1) / self.folding_factor;
+            sizes.push(size);
+        }
+        (sizes, degree_bound)
+    }
+}
+
+impl ToBytes for FRIParameters {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        (self.degree_bound as u64).write(&mut writer)?;
+        (self.blowup_factor as u64).write(&mut writer)?;
+        (self.folding_factor as u64).write(&mut writer)?;
+        (self.num_queries as u64).write(&mut writer)
+    }
+}
+
+/// The opening of one coset of a committed layer: the evaluations at all
+/// the points of the coset, together with their Merkle tree paths.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "P: MerkleTreeConfig, F: Field"),
+    Debug(bound = "P: MerkleTreeConfig, F: Field, MerkleTreeDigest<P>: core::fmt::Debug")
+)]
+pub struct LayerOpening<P: MerkleTreeConfig, F: Field> {
+    /// The evaluations of the layer over the coset.
+    pub values: Vec<F>,
+    /// The Merkle tree paths for `values`.
+    pub paths: Vec<MerkleTreePath<P>>,
+}
+
+/// `FRIProof` is a proof that a committed function is close to a polynomial
+/// of bounded degree. It is output by `FRI::prove`.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "P: MerkleTreeConfig, F: Field"),
+    Debug(bound = "P: MerkleTreeConfig, F: Field, MerkleTreeDigest<P>: core::fmt::Debug")
+)]
+pub struct FRIProof<P: MerkleTreeConfig, F: Field> {
+    /// The Merkle tree roots of the layers, starting with the commitment to
+    /// the evaluations of the tested function.
+    pub layer_roots: Vec<MerkleTreeDigest<P>>,
+    /// The coefficients of the polynomial that the last layer evaluates.
+    pub final_polynomial: Vec<F>,
+    /// For every query, the openings of the queried coset in every layer.
+    pub query_openings: Vec<Vec<LayerOpening<P, F>>>,
+}
+
+impl<P: MerkleTreeConfig, F: Field> ToBytes for LayerOpening<P, F> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.values.write(&mut writer)?;
+        for path in &self.paths {
+            path.write(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<P: MerkleTreeConfig, F: Field> ToBytes for FRIProof<P, F> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.layer_roots.write(&mut writer)?;
+        self.final_polynomial.write(&mut writer)?;
+        for openings in &self.query_openings {
+            for opening in openings {
+                opening.write(&mut writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<P: MerkleTreeConfig, F: Field> CanonicalSerialize for LayerOpening<P, F>
+where
+    MerkleTreeDigest<P>: CanonicalSerialize,
+{
+    #[inline]
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.values.serialize(&mut writer)?;
+        self.paths.serialize(&mut writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.values.serialized_size() + self.paths.serialized_size()
+    }
+}
+
+impl<P: MerkleTreeConfig, F: Field> CanonicalDeserialize for LayerOpening<P, F>
+where
+    MerkleTreeDigest<P>: CanonicalDeserialize,
+{
+    #[inline]
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let values = Vec::deserialize(&mut reader)?;
+        let paths = Vec::deserialize(&mut reader)?;
+        Ok(Self { values, paths })
+    }
+}
+
+impl<P: MerkleTreeConfig, F: Field> CanonicalSerialize for FRIProof<P, F>
+where
+    MerkleTreeDigest<P>: CanonicalSerialize,
+{
+    #[inline]
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.layer_roots.serialize(&mut writer)?;
+        self.final_polynomial.serialize(&mut writer)?;
+        self.query_openings.serialize(&mut writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.layer_roots.serialized_size()
+            + self.final_polynomial.serialized_size()
+            + self.query_openings.serialized_size()
+    }
+}
+
+impl<P: MerkleTreeConfig, F: Field> CanonicalDeserialize for FRIProof<P, F>
+where
+    MerkleTreeDigest<P>: CanonicalDeserialize,
+{
+    #[inline]
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let layer_roots = Vec::deserialize(&mut reader)?;
+        let final_polynomial = Vec::deserialize(&mut reader)?;
+        let query_openings = Vec::deserialize(&mut reader)?;
+        Ok(Self {
+            layer_roots,
+            final_polynomial,
+            query_openings,
+        })
+    }
+}
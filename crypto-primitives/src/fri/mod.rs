@@ -0,0 +1,618 @@
+//! The FRI protocol of [Ben-Sasson, Bentov, Horesh and Riabzev][bbhr18], which
+//! proves that a committed function over an FFT domain is close to a
+//! polynomial of bounded degree.
+//!
+//! In every round, the prover commits to the current layer with a Merkle
+//! tree whose leaves are grouped by cosets of the subgroup of order
+//! `folding_factor`. The verifier replies with a random challenge, which the
+//! prover uses to fold each coset into a single evaluation of the next layer,
+//! whose degree bound is `folding_factor` times smaller. Once the degree bound
+//! reaches 1 (or the domain can no longer be folded), the prover sends the
+//! remaining polynomial in the clear. The verifier then checks the folding of
+//! a number of random cosets, from the first layer down to the final
+//! polynomial.
+//!
+//! The protocol is made non-interactive via the Fiat--Shamir transform, where
+//! challenges are derived by hashing the transcript with a `Digest`.
+//!
+//! [bbhr18]: https://eccc.weizmann.ac.il/report/2017/134/
+
+use crate::{
+    merkle_tree::{MerkleHashTree, MerkleTreeConfig, MerkleTreeParams},
+    Error, Vec,
+};
+use algebra_core::{bytes::ToBytes, FftField, Field};
+use core::marker::PhantomData;
+use digest::Digest;
+use ff_fft::{DensePolynomial, EvaluationDomain};
+
+mod data_structures;
+pub use data_structures::*;
+
+/// The FRI low-degree test, using Merkle trees configured by `P` for
+/// commitments and the hash function `D` for Fiat--Shamir challenges.
+pub struct FRI<P: MerkleTreeConfig, F: FftField, D: Digest> {
+    _merkle_tree: PhantomData<P>,
+    _field: PhantomData<F>,
+    _digest: PhantomData<D>,
+}
+
+impl<P: MerkleTreeConfig, F: FftField, D: Digest> FRI<P, F, D> {
+    /// Proves that `evaluations` are the evaluations over `domain` of a
+    /// polynomial of degree less than `parameters.degree_bound`.
+    pub fn prove<Domain: EvaluationDomain<F>>(
+        parameters: &FRIParameters,
+        merkle_tree_parameters: &MerkleTreeParams<P>,
+        domain: &Domain,
+        evaluations: &[F],
+    ) -> Result<FRIProof<P, F>, Error> {
+        let (layer_sizes, _) = Self::check_parameters(parameters, domain)?;
+        if evaluations.len() != domain.size() {
+            return Err(FRIError::IncorrectEvaluationsLength {
+                expected: domain.size(),
+                found: evaluations.len(),
+            }
+            .into());
+        }
+        let prove_time = start_timer!(|| format!(
+            "FRI::Prove for {} rounds over a domain of size {}",
+            layer_sizes.len() - 1,
+            domain.size()
+        ));
+        let k = parameters.folding_factor;
+        let generator = domain.group_gen();
+        let omega_inv = generator
+            .pow([(domain.size() / k) as u64])
+            .inverse()
+            .unwrap();
+
+        let mut transcript = Self::start_transcript(parameters, domain.size());
+        let mut layers = Vec::with_capacity(layer_sizes.len() - 1);
+        let mut current = evaluations.to_vec();
+        let mut generator_inv = domain.group_gen_inv();
+        let mut offset_inv = domain.coset_offset_inv();
+        for &next_size in &layer_sizes[1..] {
+            let commit_time =
+                start_timer!(|| format!("Committing to layer of size {}", next_size * k));
+            let leaves = coset_ordered_leaves(&current, next_size, k);
+            let tree = MerkleHashTree::<P>::new(merkle_tree_parameters.clone(), &leaves)?;
+            end_timer!(commit_time);
+            transcript.append(&tree.root());
+            let alpha: F = transcript.challenge();
+
+            let fold_time = start_timer!(|| "Folding layer");
+            let k_inv = F::from(k as u64).inverse().unwrap();
+            let mut x_inv = offset_inv;
+            let next = leaves
+                .chunks(k)
+                .map(|coset| {
+                    let folded = fold_coset(coset, x_inv, omega_inv, alpha, k_inv);
+                    x_inv *= &generator_inv;
+                    folded
+                })
+                .collect();
+            end_timer!(fold_time);
+
+            layers.push((tree, leaves));
+            current = next;
+            generator_inv = generator_inv.pow([k as u64]);
+            offset_inv = offset_inv.pow([k as u64]);
+        }
+
+        // The last layer is sent as the coefficients of the polynomial that it
+        // evaluates.
+        let final_generator = generator.pow([(domain.size() / current.len()) as u64]);
+        let final_offset = domain
+            .coset_offset()
+            .pow([(domain.size() / current.len()) as u64]);
+        let points: Vec<F> = (0..current.len())
+            .scan(final_offset, |x, _| {
+                let point = *x;
+                *x *= &final_generator;
+                Some(point)
+            })
+            .collect();
+        let final_polynomial = DensePolynomial::interpolate(&points, &current)
+            .expect("the points of a domain are distinct")
+            .coeffs;
+        transcript.append(&final_polynomial);
+
+        let query_time = start_timer!(|| format!("Answering {} queries", parameters.num_queries));
+        let queries = transcript.query_indices(parameters.num_queries, layer_sizes[1]);
+        let mut query_openings = Vec::with_capacity(queries.len());
+        for query in queries {
+            let mut openings = Vec::with_capacity(layers.len());
+            let mut position = query;
+            for ((tree, leaves), &next_size) in layers.iter().zip(&layer_sizes[1..]) {
+                let coset = position % next_size;
+                let values = leaves[coset * k..(coset + 1) * k].to_vec();
+                let paths = values
+                    .iter()
+                    .enumerate()
+                    .map(|(t, value)| tree.generate_proof(coset * k + t, value))
+                    .collect::<Result<Vec<_>, _>>()?;
+                openings.push(LayerOpening { values, paths });
+                position = coset;
+            }
+            query_openings.push(openings);
+        }
+        end_timer!(query_time);
+
+        end_timer!(prove_time);
+        Ok(FRIProof {
+            layer_roots: layers.iter().map(|(tree, _)| tree.root()).collect(),
+            final_polynomial,
+            query_openings,
+        })
+    }
+
+    /// Verifies that `proof` shows that the function committed in
+    /// `proof.layer_roots[0]` is close to a polynomial of degree less than
+    /// `parameters.degree_bound` over `domain`.
+    pub fn verify<Domain: EvaluationDomain<F>>(
+        parameters: &FRIParameters,
+        merkle_tree_parameters: &MerkleTreeParams<P>,
+        domain: &Domain,
+        proof: &FRIProof<P, F>,
+    ) -> Result<bool, Error> {
+        let (layer_sizes, final_degree_bound) = Self::check_parameters(parameters, domain)?;
+        let num_rounds = layer_sizes.len() - 1;
+        let verify_time = start_timer!(|| format!("FRI::Verify for {} rounds", num_rounds));
+        let result = Self::check_proof(
+            parameters,
+            merkle_tree_parameters,
+            domain,
+            proof,
+            &layer_sizes,
+            final_degree_bound,
+        );
+        end_timer!(verify_time);
+        result
+    }
+
+    /// Checks `proof` against the layer sizes and the degree bound of the
+    /// final polynomial returned by `check_parameters`.
+    fn check_proof<Domain: EvaluationDomain<F>>(
+        parameters: &FRIParameters,
+        merkle_tree_parameters: &MerkleTreeParams<P>,
+        domain: &Domain,
+        proof: &FRIProof<P, F>,
+        layer_sizes: &[usize],
+        final_degree_bound: usize,
+    ) -> Result<bool, Error> {
+        let num_rounds = layer_sizes.len() - 1;
+        let k = parameters.folding_factor;
+        if proof.layer_roots.len() != num_rounds
+            || proof.final_polynomial.len() > final_degree_bound
+            || proof.query_openings.len() != parameters.num_queries
+        {
+            return Ok(false);
+        }
+
+        let mut transcript = Self::start_transcript(parameters, domain.size());
+        let mut alphas = Vec::with_capacity(num_rounds);
+        for root in &proof.layer_roots {
+            transcript.append(root);
+            alphas.push(transcript.challenge::<F>());
+        }
+        transcript.append(&proof.final_polynomial);
+        let queries = transcript.query_indices(parameters.num_queries, layer_sizes[1]);
+
+        let generator = domain.group_gen();
+        let omega_inv = generator
+            .pow([(domain.size() / k) as u64])
+            .inverse()
+            .unwrap();
+        let k_inv = F::from(k as u64).inverse().unwrap();
+        let final_generator = generator.pow([(domain.size() / layer_sizes[num_rounds]) as u64]);
+        let final_offset = domain
+            .coset_offset()
+            .pow([(domain.size() / layer_sizes[num_rounds]) as u64]);
+        let final_polynomial = DensePolynomial::from_coefficients_slice(&proof.final_polynomial);
+        for (query, openings) in queries.into_iter().zip(&proof.query_openings) {
+            if openings.len() != num_rounds {
+                return Ok(false);
+            }
+            let mut position = query;
+            let mut expected = None;
+            let mut generator_inv = domain.group_gen_inv();
+            let mut offset_inv = domain.coset_offset_inv();
+            for (l, opening) in openings.iter().enumerate() {
+                if opening.values.len() != k || opening.paths.len() != k {
+                    return Ok(false);
+                }
+                let next_size = layer_sizes[l + 1];
+                let (coset, slot) = (position % next_size, position / next_size);
+                if let Some(expected) = expected {
+                    if opening.values[slot] != expected {
+                        return Ok(false);
+                    }
+                }
+                for (t, (value, path)) in opening.values.iter().zip(&opening.paths).enumerate() {
+                    let root = &proof.layer_roots[l];
+                    if !path.verify_with_index(
+                        merkle_tree_parameters,
+                        root,
+                        coset * k + t,
+                        value,
+                    )? {
+                        return Ok(false);
+                    }
+                }
+                let x_inv = offset_inv * &generator_inv.pow([coset as u64]);
+                expected = Some(fold_coset(
+                    &opening.values,
+                    x_inv,
+                    omega_inv,
+                    alphas[l],
+                    k_inv,
+                ));
+                position = coset;
+                generator_inv = generator_inv.pow([k as u64]);
+                offset_inv = offset_inv.pow([k as u64]);
+            }
+
+            let point = final_offset * &final_generator.pow([position as u64]);
+            if Some(final_polynomial.evaluate(point)) != expected {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Checks `parameters` against `domain`, and returns the sizes of the
+    /// domains of all layers together with the final degree bound.
+    fn check_parameters<Domain: EvaluationDomain<F>>(
+        parameters: &FRIParameters,
+        domain: &Domain,
+    ) -> Result<(Vec<usize>, usize), Error> {
+        if parameters.folding_factor < 2 {
+            return Err(FRIError::FoldingFactorTooSmall(parameters.folding_factor).into());
+        }
+        let required = parameters.degree_bound * parameters.blowup_factor;
+        if parameters.blowup_factor < 1 || domain.size() < required {
+            return Err(FRIError::DomainTooSmall {
+                domain_size: domain.size(),
+                required,
+            }
+            .into());
+        }
+        let (layer_sizes, final_degree_bound) = parameters.layer_sizes(domain.size());
+        if layer_sizes.len() < 2 {
+            return Err(FRIError::NoFoldingRounds.into());
+        }
+        let max_leaves = 1usize << (P::HEIGHT - 1);
+        if domain.size() > max_leaves {
+            return Err(FRIError::MerkleTreeTooSmall {
+                num_leaves: domain.size(),
+                max_leaves,
+            }
+            .into());
+        }
+        Ok((layer_sizes, final_degree_bound))
+    }
+
+    fn start_transcript(parameters: &FRIParameters, domain_size: usize) -> Transcript<D> {
+        let mut transcript = Transcript::new();
+        transcript.append(b"FRI");
+        transcript.append(parameters);
+        transcript.append(&(domain_size as u64));
+        transcript
+    }
+}
+
+/// Reorders the evaluations of a layer so that the `k` evaluations over the
+/// coset `x * <omega>` are adjacent, where `x` is the `i`-th element of the
+/// next domain (of size `next_size`) and `omega` has order `k`. The
+/// evaluation at `x * omega^t` is at index `i + t * next_size` of
+/// `evaluations`, and is moved to index `i * k + t`.
+fn coset_ordered_leaves<F: Field>(evaluations: &[F], next_size: usize, k: usize) -> Vec<F> {
+    (0..next_size)
+        .flat_map(|i| (0..k).map(move |t| evaluations[i + t * next_size]))
+        .collect()
+}
+
+/// Folds the evaluations `values[t] = f(x * omega^t)` into the evaluation
+/// at `x^k` of `\sum_j alpha^j f_j`, where `f(X) = \sum_j X^j f_j(X^k)`.
+///
+/// Since `f_j(x^k) = k^{-1} \sum_t values[t] (x * omega^t)^{-j}`, the result is
+/// `k^{-1} \sum_t values[t] \sum_{j < k} (alpha / (x * omega^t))^j`.
+fn fold_coset<F: Field>(values: &[F], x_inv: F, omega_inv: F, alpha: F, k_inv: F) -> F {
+    let mut result = F::zero();
+    let mut point_inv = x_inv;
+    for value in values {
+        let ratio = alpha * &point_inv;
+        let mut power = F::one();
+        let mut sum = F::zero();
+        for _ in 0..values.len() {
+            sum += &power;
+            power *= &ratio;
+        }
+        result += &(*value * &sum);
+        point_inv *= &omega_inv;
+    }
+    result * &k_inv
+}
+
+/// A Fiat--Shamir transcript that hashes every appended message into its
+/// state, and derives challenges from the state.
+struct Transcript<D: Digest> {
+    state: Vec<u8>,
+    _digest: PhantomData<D>,
+}
+
+impl<D: Digest> Transcript<D> {
+    fn new() -> Self {
+        Self {
+            state: Vec::new(),
+            _digest: PhantomData,
+        }
+    }
+
+    fn append<T: ToBytes + ?Sized>(&mut self, message: &T) {
+        let mut bytes = Vec::new();
+        message
+            .write(&mut bytes)
+            .expect("writing into a vector cannot fail");
+        let mut hasher = D::new();
+        hasher.input(&self.state);
+        hasher.input((bytes.len() as u64).to_le_bytes());
+        hasher.input(&bytes);
+        self.state = hasher.result().to_vec();
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(num_bytes);
+        while bytes.len() < num_bytes {
+            self.append(b"squeeze");
+            bytes.extend_from_slice(&self.state);
+        }
+        bytes.truncate(num_bytes);
+        bytes
+    }
+
+    fn challenge<F: Field>(&mut self) -> F {
+        loop {
+            let bytes = self.squeeze_bytes(F::SERIALIZED_SIZE);
+            if let Some(challenge) = F::from_random_bytes(&bytes) {
+                return challenge;
+            }
+        }
+    }
+
+    /// Samples `num_queries` indices in `[0, bound)`. The bias of the modular
+    /// reduction is negligible for the domain sizes that fit in a Merkle tree.
+    fn query_indices(&mut self, num_queries: usize, bound: usize) -> Vec<usize> {
+        (0..num_queries)
+            .map(|_| {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&self.squeeze_bytes(8));
+                (u64::from_le_bytes(bytes) % bound as u64) as usize
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub enum FRIError {
+    FoldingFactorTooSmall(usize),
+    DomainTooSmall {
+        domain_size: usize,
+        required: usize,
+    },
+    NoFoldingRounds,
+    MerkleTreeTooSmall {
+        num_leaves: usize,
+        max_leaves: usize,
+    },
+    IncorrectEvaluationsLength {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl core::fmt::Display for FRIError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let msg = match self {
+            FRIError::FoldingFactorTooSmall(k) => format!("folding factor is too small: {}", k),
+            FRIError::DomainTooSmall {
+                domain_size,
+                required,
+            } => format!(
+                "domain of size {} is smaller than degree bound times blowup factor: {}",
+                domain_size, required
+            ),
+            FRIError::NoFoldingRounds => "the parameters do not allow any folding rounds".into(),
+            FRIError::MerkleTreeTooSmall {
+                num_leaves,
+                max_leaves,
+            } => format!(
+                "{} leaves do not fit into a Merkle tree with {} leaves",
+                num_leaves, max_leaves
+            ),
+            FRIError::IncorrectEvaluationsLength { expected, found } => {
+                format!("expected {} evaluations, but found {}", expected, found)
+            },
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl algebra_core::Error for FRIError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crh::{pedersen::*, *},
+        fri::*,
+        merkle_tree::MerkleTreeConfig,
+    };
+    use algebra::{
+        ed_on_bls12_381::EdwardsAffine as JubJub, CanonicalDeserialize, CanonicalSerialize,
+        UniformRand,
+    };
+    use blake2::Blake2s;
+    use ff_fft::{
+        DensePolynomial, EvaluationDomain, MixedRadixEvaluationDomain, Radix2EvaluationDomain,
+    };
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    #[derive(Clone)]
+    struct Window4x256;
+    impl PedersenWindow for Window4x256 {
+        const WINDOW_SIZE: usize = 4;
+        const NUM_WINDOWS: usize = 256;
+    }
+
+    type H = PedersenCRH<JubJub, Window4x256>;
+
+    struct JubJubMerkleTreeParams;
+    impl MerkleTreeConfig for JubJubMerkleTreeParams {
+        const HEIGHT: usize = 9;
+        type H = H;
+    }
+
+    type TestFRI<F> = FRI<JubJubMerkleTreeParams, F, Blake2s>;
+
+    fn random_evaluations<F: FftField, Domain: EvaluationDomain<F>, R: Rng>(
+        degree: usize,
+        domain: &Domain,
+        rng: &mut R,
+    ) -> Vec<F> {
+        let polynomial = DensePolynomial::<F>::rand(degree, rng);
+        domain.fft(&polynomial.coeffs)
+    }
+
+    fn prove_and_verify<F: FftField, Domain: EvaluationDomain<F>>(
+        parameters: &FRIParameters,
+        domain: &Domain,
+        evaluations: &[F],
+    ) -> bool {
+        let mut rng = XorShiftRng::seed_from_u64(9174123u64);
+        let crh_parameters = H::setup(&mut rng).unwrap();
+        let proof = TestFRI::prove(parameters, &crh_parameters, domain, evaluations).unwrap();
+        TestFRI::verify(parameters, &crh_parameters, domain, &proof).unwrap()
+    }
+
+    #[test]
+    fn radix2_fri_test() {
+        use algebra::bls12_377::Fr;
+        let rng = &mut XorShiftRng::seed_from_u64(1231275789u64);
+        for folding_factor in &[2, 4, 8] {
+            let parameters = FRIParameters::new(32, 4, *folding_factor, 8);
+            let domain = Radix2EvaluationDomain::<Fr>::new(128).unwrap();
+            let evaluations = random_evaluations(31, &domain, rng);
+            assert!(prove_and_verify(&parameters, &domain, &evaluations));
+        }
+    }
+
+    #[test]
+    fn coset_fri_test() {
+        use algebra::bls12_377::Fr;
+        let rng = &mut XorShiftRng::seed_from_u64(1231275789u64);
+        for folding_factor in &[2, 4] {
+            let parameters = FRIParameters::new(16, 4, *folding_factor, 8);
+            let domain = Radix2EvaluationDomain::<Fr>::new_coset(64, Fr::rand(rng)).unwrap();
+            let evaluations = random_evaluations(15, &domain, rng);
+            assert!(prove_and_verify(&parameters, &domain, &evaluations));
+        }
+    }
+
+    #[test]
+    fn mixed_radix_fri_test() {
+        use algebra::mnt6_298::Fr;
+        let rng = &mut XorShiftRng::seed_from_u64(1231275789u64);
+        // The domain has size 2^2 * 7^2, and folding stops once its size is no
+        // longer divisible by the folding factor.
+        let domain = MixedRadixEvaluationDomain::<Fr>::new(196).unwrap();
+        assert_eq!(domain.size(), 196);
+        for folding_factor in &[2, 7] {
+            let parameters = FRIParameters::new(40, 4, *folding_factor, 8);
+            let evaluations = random_evaluations(39, &domain, rng);
+            assert!(prove_and_verify(&parameters, &domain, &evaluations));
+        }
+    }
+
+    #[test]
+    fn high_degree_rejected_test() {
+        use algebra::bls12_377::Fr;
+        let rng = &mut XorShiftRng::seed_from_u64(1231275789u64);
+        let parameters = FRIParameters::new(16, 8, 2, 8);
+        let domain = Radix2EvaluationDomain::<Fr>::new(128).unwrap();
+        let evaluations = random_evaluations(63, &domain, rng);
+        assert!(!prove_and_verify(&parameters, &domain, &evaluations));
+
+        // Random evaluations are far from any polynomial of low degree.
+        let evaluations: Vec<Fr> = (0..128).map(|_| Fr::rand(rng)).collect();
+        assert!(!prove_and_verify(&parameters, &domain, &evaluations));
+    }
+
+    #[test]
+    fn tampered_proof_rejected_test() {
+        use algebra::bls12_377::Fr;
+        let rng = &mut XorShiftRng::seed_from_u64(1231275789u64);
+        let crh_parameters = H::setup(rng).unwrap();
+        let parameters = FRIParameters::new(16, 4, 4, 4);
+        let domain = Radix2EvaluationDomain::<Fr>::new(64).unwrap();
+        let evaluations = random_evaluations(15, &domain, rng);
+        let proof = TestFRI::prove(&parameters, &crh_parameters, &domain, &evaluations).unwrap();
+        assert!(TestFRI::verify(&parameters, &crh_parameters, &domain, &proof).unwrap());
+
+        let mut tampered = proof.clone();
+        tampered.query_openings[0][1].values[0] += &Fr::from(1u64);
+        assert!(!TestFRI::verify(&parameters, &crh_parameters, &domain, &tampered).unwrap());
+
+        let mut tampered = proof.clone();
+        tampered.final_polynomial[0] += &Fr::from(1u64);
+        assert!(!TestFRI::verify(&parameters, &crh_parameters, &domain, &tampered).unwrap());
+
+        let mut tampered = proof.clone();
+        tampered.layer_roots.swap(0, 1);
+        assert!(!TestFRI::verify(&parameters, &crh_parameters, &domain, &tampered).unwrap());
+
+        let mut tampered = proof;
+        tampered.query_openings[1].pop();
+        assert!(!TestFRI::verify(&parameters, &crh_parameters, &domain, &tampered).unwrap());
+    }
+
+    #[test]
+    fn invalid_parameters_test() {
+        use algebra::bls12_377::Fr;
+        let rng = &mut XorShiftRng::seed_from_u64(1231275789u64);
+        let crh_parameters = H::setup(rng).unwrap();
+        let domain = Radix2EvaluationDomain::<Fr>::new(64).unwrap();
+        let evaluations = random_evaluations(15, &domain, rng);
+        for parameters in &[
+            FRIParameters::new(16, 4, 1, 4),
+            FRIParameters::new(16, 8, 2, 4),
+            FRIParameters::new(1, 4, 2, 4),
+        ] {
+            assert!(TestFRI::prove(parameters, &crh_parameters, &domain, &evaluations).is_err());
+        }
+        let parameters = FRIParameters::new(16, 4, 2, 4);
+        assert!(TestFRI::prove(&parameters, &crh_parameters, &domain, &evaluations[1..]).is_err());
+        // The domain does not fit into the Merkle tree.
+        let domain = Radix2EvaluationDomain::<Fr>::new(512).unwrap();
+        let evaluations = random_evaluations(15, &domain, rng);
+        assert!(TestFRI::prove(&parameters, &crh_parameters, &domain, &evaluations).is_err());
+    }
+
+    #[test]
+    fn proof_serialization_test() {
+        use algebra::bls12_377::Fr;
+        let rng = &mut XorShiftRng::seed_from_u64(1231275789u64);
+        let crh_parameters = H::setup(rng).unwrap();
+        let parameters = FRIParameters::new(16, 4, 2, 4);
+        let domain = Radix2EvaluationDomain::<Fr>::new(64).unwrap();
+        let evaluations = random_evaluations(15, &domain, rng);
+        let proof = TestFRI::prove(&parameters, &crh_parameters, &domain, &evaluations).unwrap();
+
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), proof.serialized_size());
+        let deserialized = FRIProof::deserialize(&bytes[..]).unwrap();
+        assert!(TestFRI::verify(&parameters, &crh_parameters, &domain, &deserialized).unwrap());
+    }
+}
@@ -99,6 +99,44 @@ where
             should_enforce,
         )
     }
+
+    /// Returns the values of the nodes of the path returned by `value_gen`,
+    /// or `P::HEIGHT - 1` missing values if `cs` is in setup mode and does
+    /// not compute assignments, so that `value_gen` is not invoked.
+    #[allow(clippy::type_complexity)]
+    fn path_values<FN, T, CS>(
+        cs: &CS,
+        value_gen: FN,
+    ) -> Result<
+        Vec<(
+            Result<<P::H as FixedLengthCRH>::Output, SynthesisError>,
+            Result<<P::H as FixedLengthCRH>::Output, SynthesisError>,
+        )>,
+        SynthesisError,
+    >
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<MerkleTreePath<P>>,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        match cs.compute_assignment(value_gen) {
+            Ok(path) => Ok(path
+                .borrow()
+                .path
+                .iter()
+                .map(|(l, r)| (Ok(l.clone()), Ok(r.clone())))
+                .collect()),
+            Err(SynthesisError::AssignmentMissing) if cs.is_in_setup_mode() => Ok((1..P::HEIGHT)
+                .map(|_| {
+                    (
+                        Err(SynthesisError::AssignmentMissing),
+                        Err(SynthesisError::AssignmentMissing),
+                    )
+                })
+                .collect()),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 pub(crate) fn hash_inner_node_gadget<H, HG, ConstraintF, CS>(
@@ -159,15 +197,11 @@ where
         T: Borrow<MerkleTreePath<P>>,
     {
         let mut path = Vec::new();
-        for (i, &(ref l, ref r)) in value_gen()?.borrow().path.iter().enumerate() {
+        for (i, (l, r)) in Self::path_values(&cs, value_gen)?.into_iter().enumerate() {
             let l_hash =
-                HGadget::OutputGadget::alloc(&mut cs.ns(|| format!("l_child_{}", i)), || {
-                    Ok(l.clone())
-                })?;
+                HGadget::OutputGadget::alloc(&mut cs.ns(|| format!("l_child_{}", i)), || l)?;
             let r_hash =
-                HGadget::OutputGadget::alloc(&mut cs.ns(|| format!("r_child_{}", i)), || {
-                    Ok(r.clone())
-                })?;
+                HGadget::OutputGadget::alloc(&mut cs.ns(|| format!("r_child_{}", i)), || r)?;
             path.push((l_hash, r_hash));
         }
         Ok(MerkleTreePathGadget { path })
@@ -182,15 +216,11 @@ where
         T: Borrow<MerkleTreePath<P>>,
     {
         let mut path = Vec::new();
-        for (i, &(ref l, ref r)) in value_gen()?.borrow().path.iter().enumerate() {
-            let l_hash = HGadget::OutputGadget::alloc_input(
-                &mut cs.ns(|| format!("l_child_{}", i)),
-                || Ok(l.clone()),
-            )?;
-            let r_hash = HGadget::OutputGadget::alloc_input(
-                &mut cs.ns(|| format!("r_child_{}", i)),
-                || Ok(r.clone()),
-            )?;
+        for (i, (l, r)) in Self::path_values(&cs, value_gen)?.into_iter().enumerate() {
+            let l_hash =
+                HGadget::OutputGadget::alloc_input(&mut cs.ns(|| format!("l_child_{}", i)), || l)?;
+            let r_hash =
+                HGadget::OutputGadget::alloc_input(&mut cs.ns(|| format!("r_child_{}", i)), || r)?;
             path.push((l_hash, r_hash));
         }
 
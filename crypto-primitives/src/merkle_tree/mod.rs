@@ -1,5 +1,9 @@
 use crate::{crh::FixedLengthCRH, Error, Vec};
-use algebra_core::{bytes::ToBytes, io::Cursor};
+use algebra_core::{
+    bytes::ToBytes,
+    io::{Cursor, Read, Result as IoResult, Write},
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+};
 use core::fmt;
 
 #[cfg(feature = "r1cs")]
@@ -79,6 +83,72 @@ impl<P: MerkleTreeConfig> MerkleTreePath<P> {
             Ok(false)
         }
     }
+
+    /// Like `verify`, but additionally checks that `leaf` is located at
+    /// position `index` in the tree.
+    pub fn verify_with_index<L: ToBytes>(
+        &self,
+        parameters: &<P::H as FixedLengthCRH>::Parameters,
+        root_hash: &<P::H as FixedLengthCRH>::Output,
+        index: usize,
+        leaf: &L,
+    ) -> Result<bool, Error> {
+        if !self.verify(parameters, root_hash, leaf)? {
+            return Ok(false);
+        }
+        // The bits of `index` determine whether the current node is the left or
+        // the right child at each level. Padding levels always use the left child.
+        let mut buffer = [0u8; 128];
+        let mut prev = hash_leaf::<P::H, L>(parameters, leaf, &mut buffer)?;
+        let mut index = index;
+        for &(ref left, ref right) in &self.path {
+            let expected = if index % 2 == 0 { left } else { right };
+            if &prev != expected {
+                return Ok(false);
+            }
+            prev = hash_inner_node::<P::H>(parameters, left, right, &mut buffer)?;
+            index >>= 1;
+        }
+        Ok(index == 0)
+    }
+}
+
+impl<P: MerkleTreeConfig> ToBytes for MerkleTreePath<P> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        (self.path.len() as u64).write(&mut writer)?;
+        for (hash, sibling_hash) in &self.path {
+            hash.write(&mut writer)?;
+            sibling_hash.write(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<P: MerkleTreeConfig> CanonicalSerialize for MerkleTreePath<P>
+where
+    MerkleTreeDigest<P>: CanonicalSerialize,
+{
+    #[inline]
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.path.serialize(writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.path.serialized_size()
+    }
+}
+
+impl<P: MerkleTreeConfig> CanonicalDeserialize for MerkleTreePath<P>
+where
+    MerkleTreeDigest<P>: CanonicalDeserialize,
+{
+    #[inline]
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        let path = Vec::deserialize(reader)?;
+        Ok(Self { path })
+    }
 }
 
 pub struct MerkleHashTree<P: MerkleTreeConfig> {
@@ -430,6 +500,45 @@ mod test {
         generate_merkle_tree(&[[1u8; 8]]);
     }
 
+    #[test]
+    fn index_is_checked_test() {
+        let mut rng = XorShiftRng::seed_from_u64(9174123u64);
+        let leaves: Vec<_> = (0..20u8).map(|i| [i; 8]).collect();
+        let crh_parameters = H::setup(&mut rng).unwrap();
+        let tree = JubJubMerkleTree::new(crh_parameters.clone(), &leaves).unwrap();
+        let root = tree.root();
+        for (i, leaf) in leaves.iter().enumerate() {
+            let proof = tree.generate_proof(i, &leaf).unwrap();
+            assert!(proof
+                .verify_with_index(&crh_parameters, &root, i, &leaf)
+                .unwrap());
+            assert!(!proof
+                .verify_with_index(&crh_parameters, &root, i ^ 1, &leaf)
+                .unwrap());
+            assert!(!proof
+                .verify_with_index(&crh_parameters, &root, i + 128, &leaf)
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn path_serialization_test() {
+        let mut rng = XorShiftRng::seed_from_u64(9174123u64);
+        let leaves: Vec<_> = (0..5u8).map(|i| [i; 8]).collect();
+        let crh_parameters = H::setup(&mut rng).unwrap();
+        let tree = JubJubMerkleTree::new(crh_parameters.clone(), &leaves).unwrap();
+        let proof = tree.generate_proof(3, &leaves[3]).unwrap();
+
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), proof.serialized_size());
+        let deserialized =
+            MerkleTreePath::<JubJubMerkleTreeParams>::deserialize(&bytes[..]).unwrap();
+        assert!(deserialized
+            .verify_with_index(&crh_parameters, &tree.root(), 3, &leaves[3])
+            .unwrap());
+    }
+
     fn bad_merkle_tree_verify<L: ToBytes + Clone + Eq>(leaves: &[L]) -> () {
         let mut rng = XorShiftRng::seed_from_u64(13423423u64);
 
@@ -499,13 +499,26 @@ where
         FN: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Proof<PairingE>>,
     {
-        value_gen().and_then(|proof| {
-            let Proof { a, b, c } = proof.borrow().clone();
-            let a = P::G1Gadget::alloc_checked(cs.ns(|| "a"), || Ok(a.into_projective()))?;
-            let b = P::G2Gadget::alloc_checked(cs.ns(|| "b"), || Ok(b.into_projective()))?;
-            let c = P::G1Gadget::alloc_checked(cs.ns(|| "c"), || Ok(c.into_projective()))?;
-            Ok(Self { a, b, c })
-        })
+        let (a, b, c) = match cs.compute_assignment(value_gen) {
+            Ok(proof) => {
+                let Proof { a, b, c } = proof.borrow().clone();
+                (
+                    Ok(a.into_projective()),
+                    Ok(b.into_projective()),
+                    Ok(c.into_projective()),
+                )
+            },
+            Err(SynthesisError::AssignmentMissing) if cs.is_in_setup_mode() => (
+                Err(SynthesisError::AssignmentMissing),
+                Err(SynthesisError::AssignmentMissing),
+                Err(SynthesisError::AssignmentMissing),
+            ),
+            Err(e) => return Err(e),
+        };
+        let a = P::G1Gadget::alloc_checked(cs.ns(|| "a"), || a)?;
+        let b = P::G2Gadget::alloc_checked(cs.ns(|| "b"), || b)?;
+        let c = P::G1Gadget::alloc_checked(cs.ns(|| "c"), || c)?;
+        Ok(Self { a, b, c })
     }
 
     #[inline]
@@ -517,15 +530,28 @@ where
         FN: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Proof<PairingE>>,
     {
-        value_gen().and_then(|proof| {
-            let Proof { a, b, c } = proof.borrow().clone();
-            // We don't need to check here because the prime order check can be performed
-            // in plain.
-            let a = P::G1Gadget::alloc_input(cs.ns(|| "a"), || Ok(a.into_projective()))?;
-            let b = P::G2Gadget::alloc_input(cs.ns(|| "b"), || Ok(b.into_projective()))?;
-            let c = P::G1Gadget::alloc_input(cs.ns(|| "c"), || Ok(c.into_projective()))?;
-            Ok(Self { a, b, c })
-        })
+        let (a, b, c) = match cs.compute_assignment(value_gen) {
+            Ok(proof) => {
+                let Proof { a, b, c } = proof.borrow().clone();
+                (
+                    Ok(a.into_projective()),
+                    Ok(b.into_projective()),
+                    Ok(c.into_projective()),
+                )
+            },
+            Err(SynthesisError::AssignmentMissing) if cs.is_in_setup_mode() => (
+                Err(SynthesisError::AssignmentMissing),
+                Err(SynthesisError::AssignmentMissing),
+                Err(SynthesisError::AssignmentMissing),
+            ),
+            Err(e) => return Err(e),
+        };
+        // We don't need to check here because the prime order check can be performed
+        // in plain.
+        let a = P::G1Gadget::alloc_input(cs.ns(|| "a"), || a)?;
+        let b = P::G2Gadget::alloc_input(cs.ns(|| "b"), || b)?;
+        let c = P::G1Gadget::alloc_input(cs.ns(|| "c"), || c)?;
+        Ok(Self { a, b, c })
     }
 }
 
@@ -837,6 +863,64 @@ mod test_recursive {
         }
     }
 
+    struct VkCircuit {
+        params: Parameters<MNT6_298>,
+        proof: Option<Proof<MNT6_298>>,
+    }
+
+    impl ConstraintSynthesizer<MNT6Fq> for VkCircuit {
+        fn generate_constraints<CS: ConstraintSystem<MNT6Fq>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let proof = self.proof;
+            TestVkGadget1::alloc_input(cs.ns(|| "Vk"), || Ok(&self.params.vk))?;
+            TestProofGadget1::alloc(cs.ns(|| "Proof"), || {
+                proof.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn groth16_vk_gadget_setup_test() {
+        let num_inputs = 5;
+        let num_constraints = num_inputs;
+        let rng = &mut test_rng();
+        let inputs: Vec<Option<MNT4Fq>> = (0..num_inputs).map(|_| Some(rng.gen())).collect();
+
+        let inner_params = {
+            let c = Bench::<MNT4Fq> {
+                inputs: vec![None; num_inputs],
+                num_constraints,
+            };
+            generate_random_parameters(c, rng).unwrap()
+        };
+        let inner_proof = {
+            let c = Bench {
+                inputs,
+                num_constraints,
+            };
+            create_random_proof(c, &inner_params, rng).unwrap()
+        };
+
+        // The verifying key shapes the circuit, so it must be allocated during
+        // setup even though the proof is not known yet.
+        let params = {
+            let c = VkCircuit {
+                params: inner_params.clone(),
+                proof: None,
+            };
+            generate_random_parameters::<MNT4_298, _, _>(c, rng).unwrap()
+        };
+
+        let c = VkCircuit {
+            params: inner_params,
+            proof: Some(inner_proof),
+        };
+        create_random_proof(c, &params, rng).unwrap();
+    }
+
     #[test]
     fn groth16_recursive_verifier_test() {
         let num_inputs = 5;
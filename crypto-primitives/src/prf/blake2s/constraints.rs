@@ -476,7 +476,7 @@ impl<ConstraintF: PrimeField> AllocGadget<[u8; 32], ConstraintF> for Blake2sOutp
         T: Borrow<[u8; 32]>,
     {
         let zeros = [0u8; 32];
-        let value = match value_gen() {
+        let value = match cs.compute_assignment(value_gen) {
             Ok(val) => *(val.borrow()),
             Err(_) => zeros,
         };
@@ -495,7 +495,7 @@ impl<ConstraintF: PrimeField> AllocGadget<[u8; 32], ConstraintF> for Blake2sOutp
         T: Borrow<[u8; 32]>,
     {
         let zeros = [0u8; 32];
-        let value = match value_gen() {
+        let value = match cs.compute_assignment(value_gen) {
             Ok(val) => *(val.borrow()),
             Err(_) => zeros,
         };
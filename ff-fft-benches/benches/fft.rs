@@ -5,7 +5,7 @@ extern crate criterion;
 
 use algebra::{mnt4_753::Fr as MNT4Fr, mnt6_753::Fr as MNT6Fr, FftField, UniformRand};
 use criterion::Criterion;
-use ff_fft::{EvaluationDomain, MixedRadixEvaluationDomain, Radix2EvaluationDomain};
+use ff_fft::{EvaluationDomain, FFTPlan, MixedRadixEvaluationDomain, Radix2EvaluationDomain};
 
 fn bench_groth16_ffts<F: FftField, D: EvaluationDomain<F>>(
     c: &mut Criterion,
@@ -51,10 +51,49 @@ fn bench_groth16_ffts<F: FftField, D: EvaluationDomain<F>>(
     });
 }
 
+fn bench_groth16_ffts_with_plan<F: FftField, D: EvaluationDomain<F>>(
+    c: &mut Criterion,
+    num_coeffs: usize,
+    name: &'static str,
+) {
+    // Per benchmark setup
+    let rng = &mut rand::thread_rng();
+
+    // We expect the num_coeffs input to be a compatible size for the domain.
+    let domain = D::new(num_coeffs).unwrap();
+    assert_eq!(num_coeffs, domain.size());
+
+    c.bench_function(name, move |bencher| {
+        // Per sample setup
+        let mut abc = (0..3)
+            .map(|_| (0..num_coeffs).map(|_| F::rand(rng)).collect())
+            .collect::<Vec<Vec<F>>>();
+
+        bencher.iter(|| {
+            // Emulate the FFT operations Groth16 performs in a call to `witness_map`,
+            // including the precomputation of the plan.
+            let plan = FFTPlan::new(domain);
+            plan.ifft_batch_in_place(&mut abc);
+            plan.coset_fft_batch_in_place(&mut abc);
+
+            let mut ab = domain.mul_polynomials_in_evaluation_domain(&abc[0], &abc[1]);
+
+            domain.divide_by_vanishing_poly_on_coset_in_place(&mut ab);
+
+            plan.coset_ifft_in_place(&mut ab);
+        })
+    });
+}
+
 fn bench_groth16_ffts_radix2(c: &mut Criterion) {
     // Choose 2^16 = 65,536 coefficients for the radix-2 FFT.
     // This comes closest to the 51,200 coefficients chosen in the mixed-radix FFT.
     bench_groth16_ffts::<MNT4Fr, Radix2EvaluationDomain<MNT4Fr>>(c, 1 << 16, "radix-2 FFT");
+    bench_groth16_ffts_with_plan::<MNT4Fr, Radix2EvaluationDomain<MNT4Fr>>(
+        c,
+        1 << 16,
+        "radix-2 FFT with plan",
+    );
 }
 
 fn bench_groth16_ffts_mixed_radix(c: &mut Criterion) {
@@ -0,0 +1,831 @@
+//! This module contains an `ECFFTDomain` for performing polynomial
+//! arithmetic over fields that do not have large smooth multiplicative
+//! subgroups, such as the base fields of BLS12-381 or secp256k1.
+//!
+//! Instead of a multiplicative subgroup, the domain is the set `L` of the
+//! x-coordinates of a coset `Q + <G>` of a subgroup of order `2^k` of an
+//! elliptic curve over the field. A chain of 2-isogenies maps `L` two-to-one
+//! onto smaller and smaller sets, which replaces the squaring map of the
+//! classical FFT. This is the elliptic curve FFT (ECFFT) of
+//! [Ben-Sasson, Carmon, Kopparty and Levit][bckl21].
+//!
+//! The basic operation is `EXTEND`, which computes the evaluations of a
+//! polynomial over one half of a domain from its evaluations over the other
+//! half in time O(n log n). On top of it, conversions between coefficients and
+//! evaluations take time O(n log^2 n), and so does polynomial multiplication.
+//!
+//! [bckl21]: https://arxiv.org/abs/2107.08473
+
+use crate::{DensePolynomial, Vec};
+use algebra_core::{
+    batch_inversion,
+    bytes::ToBytes,
+    io::{Read, Result as IoResult, Write},
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+    Field, SquareRootField,
+};
+use rand::Rng;
+
+/// A point in affine coordinates, where `None` is the point at infinity.
+type Point<F> = Option<(F, F)>;
+
+/// The number of offsets tried for every curve found by
+/// `ECFFTParameters::sample`.
+const MAX_OFFSET_TRIES: usize = 16;
+
+/// The elliptic curve data that defines the largest `ECFFTDomain` over `F`:
+/// the curve `y^2 = x^3 + coeff_a * x + coeff_b`, a point `generator` of order
+/// `2^log_size` on it, and a point `offset` that shifts the subgroup generated
+/// by `generator` to a coset whose x-coordinates are distinct.
+///
+/// Finding such a curve is expensive for large `log_size`, so parameters
+/// should be sampled once and then stored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ECFFTParameters<F: Field> {
+    /// The coefficient of `x` in the curve equation.
+    pub coeff_a: F,
+    /// The constant coefficient in the curve equation.
+    pub coeff_b: F,
+    /// A point of order `2^log_size`.
+    pub generator: (F, F),
+    /// The point that shifts the subgroup generated by `generator`.
+    pub offset: (F, F),
+    /// `log_2` of the order of `generator`.
+    pub log_size: u32,
+}
+
+impl<F: Field> ECFFTParameters<F> {
+    /// Returns whether `point` lies on the curve.
+    fn is_on_curve(&self, (x, y): (F, F)) -> bool {
+        y.square() == x.square() * &x + &(self.coeff_a * &x) + &self.coeff_b
+    }
+
+    /// Returns the x-coordinates of `offset + j * generator'` for
+    /// `0 <= j < 2^log_size`, where `generator'` generates the subgroup of
+    /// order `2^log_size` of the group generated by `generator`, or `None` if
+    /// the parameters are invalid or the x-coordinates are not distinct and
+    /// non-zero.
+    fn coset(&self, log_size: u32) -> Option<Vec<F>> {
+        if log_size > self.log_size
+            || !self.is_on_curve(self.generator)
+            || !self.is_on_curve(self.offset)
+        {
+            return None;
+        }
+        let generator = self.double_repeatedly(Some(self.generator), self.log_size - log_size);
+        // The generator must have order exactly `2^log_size`.
+        let half = self.double_repeatedly(generator, log_size.saturating_sub(1));
+        if (log_size > 0 && half.is_none()) || self.double_repeatedly(half, 1).is_some() {
+            return None;
+        }
+
+        let mut point = Some(self.offset);
+        let mut elements = Vec::with_capacity(1 << log_size);
+        for _ in 0..1usize << log_size {
+            elements.push(point?.0);
+            point = self.add(point, generator);
+        }
+        let mut sorted = elements.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() != elements.len() || elements.iter().any(|x| x.is_zero()) {
+            return None;
+        }
+        Some(elements)
+    }
+
+    fn add(&self, p: Point<F>, q: Point<F>) -> Point<F> {
+        let ((x1, y1), (x2, y2)) = match (p, q) {
+            (None, q) => return q,
+            (p, None) => return p,
+            (Some(p), Some(q)) => (p, q),
+        };
+        let lambda = if x1 != x2 {
+            (y2 - &y1) / &(x2 - &x1)
+        } else if (y1 + &y2).is_zero() {
+            return None;
+        } else {
+            let x1_squared = x1.square();
+            (x1_squared.double() + &x1_squared + &self.coeff_a) / &y1.double()
+        };
+        let x3 = lambda.square() - &x1 - &x2;
+        let y3 = lambda * &(x1 - &x3) - &y1;
+        Some((x3, y3))
+    }
+
+    fn double_repeatedly(&self, mut point: Point<F>, times: u32) -> Point<F> {
+        for _ in 0..times {
+            point = self.add(point, point);
+        }
+        point
+    }
+}
+
+impl<F: Field> ToBytes for ECFFTParameters<F> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.coeff_a.write(&mut writer)?;
+        self.coeff_b.write(&mut writer)?;
+        self.generator.0.write(&mut writer)?;
+        self.generator.1.write(&mut writer)?;
+        self.offset.0.write(&mut writer)?;
+        self.offset.1.write(&mut writer)?;
+        self.log_size.write(&mut writer)
+    }
+}
+
+/// Domains themselves are not serialized, since they are cheap to rebuild
+/// with `ECFFTDomain::new` from their parameters, which are not.
+impl<F: Field> CanonicalSerialize for ECFFTParameters<F> {
+    #[inline]
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.coeff_a.serialize(&mut writer)?;
+        self.coeff_b.serialize(&mut writer)?;
+        self.generator.serialize(&mut writer)?;
+        self.offset.serialize(&mut writer)?;
+        self.log_size.serialize(&mut writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.coeff_a.serialized_size()
+            + self.coeff_b.serialized_size()
+            + self.generator.serialized_size()
+            + self.offset.serialized_size()
+            + self.log_size.serialized_size()
+    }
+}
+
+impl<F: Field> CanonicalDeserialize for ECFFTParameters<F> {
+    /// Fails unless both points lie on the curve and the generator has order
+    /// exactly `2^log_size`. Whether the x-coordinates of the coset are
+    /// distinct is only checked when a domain is constructed.
+    #[inline]
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let parameters = Self {
+            coeff_a: F::deserialize(&mut reader)?,
+            coeff_b: F::deserialize(&mut reader)?,
+            generator: <(F, F)>::deserialize(&mut reader)?,
+            offset: <(F, F)>::deserialize(&mut reader)?,
+            log_size: u32::deserialize(&mut reader)?,
+        };
+        if parameters.log_size == 0
+            || parameters.log_size >= 8 * core::mem::size_of::<usize>() as u32
+            || !parameters.is_on_curve(parameters.generator)
+            || !parameters.is_on_curve(parameters.offset)
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        let half =
+            parameters.double_repeatedly(Some(parameters.generator), parameters.log_size - 1);
+        if half.is_none() || parameters.double_repeatedly(half, 1).is_some() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(parameters)
+    }
+}
+
+impl<F: SquareRootField> ECFFTParameters<F> {
+    /// Samples parameters for domains of size up to `2^log_size`.
+    ///
+    /// This searches random curves `y^2 = (x - e_1)(x - e_2)(x - e_3)` for a
+    /// point of order `2^log_size`, by repeatedly halving a point of order 2.
+    /// The expected number of curves that are tried grows like
+    /// `2^log_size`.
+    pub fn sample<R: Rng>(log_size: u32, rng: &mut R) -> Self {
+        assert!(log_size > 0, "the generator must have order at least 2");
+        loop {
+            let e1 = F::rand(rng);
+            let e2 = F::rand(rng);
+            let e3 = -(e1 + &e2);
+            if e1 == e2 || e1 == e3 || e2 == e3 {
+                continue;
+            }
+            let roots = [e1, e2, e3];
+            let mut parameters = Self {
+                coeff_a: e1 * &e2 + &(e1 * &e3) + &(e2 * &e3),
+                coeff_b: -(e1 * &e2 * &e3),
+                generator: (F::zero(), F::zero()),
+                offset: (F::zero(), F::zero()),
+                log_size,
+            };
+            for &root in &roots {
+                let start = (root, F::zero());
+                let generator = match parameters.halve_repeatedly(&roots, start, log_size - 1) {
+                    Some(generator) => generator,
+                    None => continue,
+                };
+                parameters.generator = generator;
+                for _ in 0..MAX_OFFSET_TRIES {
+                    let x = F::rand(rng);
+                    let y =
+                        match (x.square() * &x + &(parameters.coeff_a * &x) + &parameters.coeff_b)
+                            .sqrt()
+                        {
+                            Some(y) => y,
+                            None => continue,
+                        };
+                    parameters.offset = (x, y);
+                    if parameters.coset(log_size).is_some() {
+                        return parameters;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns a point `R` with `2^times * R = point`, if there is one.
+    fn halve_repeatedly(&self, roots: &[F; 3], point: (F, F), times: u32) -> Option<(F, F)> {
+        if times == 0 {
+            return Some(point);
+        }
+        self.halves(roots, point)
+            .into_iter()
+            .find_map(|half| self.halve_repeatedly(roots, half, times - 1))
+    }
+
+    /// Returns all the points `R` with `2 * R = point`.
+    ///
+    /// A point `(x, y)` is divisible by 2 if and only if all of `x - e_i` are
+    /// squares, and then the x-coordinates of its halves are
+    /// `x + r_1 r_2 + r_1 r_3 + r_2 r_3` for the choices of square roots
+    /// `r_i` of `x - e_i`.
+    fn halves(&self, roots: &[F; 3], (x, y): (F, F)) -> Vec<(F, F)> {
+        let mut square_roots = [F::zero(); 3];
+        for (r, root) in square_roots.iter_mut().zip(roots) {
+            match (x - root).sqrt() {
+                Some(sqrt) => *r = sqrt,
+                None => return Vec::new(),
+            }
+        }
+        let [r1, r2, r3] = square_roots;
+        let mut halves = Vec::with_capacity(4);
+        for &(r2, r3) in &[(r2, r3), (r2, -r3), (-r2, r3), (-r2, -r3)] {
+            let half_x = x + &(r1 * &r2) + &(r1 * &r3) + &(r2 * &r3);
+            let half_y = match roots.iter().map(|root| half_x - root).product::<F>().sqrt() {
+                Some(half_y) => half_y,
+                None => continue,
+            };
+            for &half_y in &[half_y, -half_y] {
+                let half = Some((half_x, half_y));
+                if self.add(half, half) == Some((x, y)) {
+                    halves.push((half_x, half_y));
+                    break;
+                }
+            }
+        }
+        halves
+    }
+}
+
+/// A level of the isogeny chain: the set `L_i`, ordered so that the 2-isogeny
+/// `psi_i(x) = x + residue / (x - pole)` maps the `j`-th element of `L_i` to
+/// the `(j mod |L_i| / 2)`-th element of `L_{i + 1}`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Level<F: Field> {
+    elements: Vec<F>,
+    pole: F,
+}
+
+/// Precomputed values for `extend` from or to a set `L_i[offset::stride]` of
+/// size `m`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ExtensionPrecomputation<F: Field> {
+    /// The evaluations of `v_i(X)^{m/2 - 1}` over the set.
+    v_pow: Vec<F>,
+    /// The inverses of `v_pow`.
+    v_pow_inv: Vec<F>,
+    /// The inverses of the differences between the `r`-th and the
+    /// `(r + m/2)`-th elements of the set, which have the same image under
+    /// `psi_i`.
+    difference_inv: Vec<F>,
+}
+
+impl<F: Field> ExtensionPrecomputation<F> {
+    /// Computes the precomputations for the sets `L_i[offset::stride]` at all
+    /// levels `i` at which they have at least two elements.
+    fn new(levels: &[Level<F>], stride: usize, offset: usize) -> Vec<Self> {
+        levels
+            .iter()
+            .take_while(|level| level.elements.len() >= 2 * stride)
+            .map(|Level { elements, pole }| {
+                let set: Vec<F> = elements[offset..].iter().step_by(stride).copied().collect();
+                let half = set.len() / 2;
+                let v_pow: Vec<F> = set
+                    .iter()
+                    .map(|x| (*x - pole).pow([half as u64 - 1]))
+                    .collect();
+                let mut v_pow_inv = v_pow.clone();
+                batch_inversion(&mut v_pow_inv);
+                let mut difference_inv: Vec<F> = set[..half]
+                    .iter()
+                    .zip(&set[half..])
+                    .map(|(x0, x1)| *x0 - x1)
+                    .collect();
+                batch_inversion(&mut difference_inv);
+                Self {
+                    v_pow,
+                    v_pow_inv,
+                    difference_inv,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Precomputed values for converting between evaluations over
+/// `L_0[0::stride]` and coefficients. Within this set of size `m`, `S` denotes
+/// the elements at even positions, `S'` those at odd positions, and `Z_S` the
+/// vanishing polynomial of `S`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct StridePrecomputation<F: Field> {
+    /// The evaluations of `X^{m/2}` over the set.
+    x_half_pow: Vec<F>,
+    /// The inverses of the evaluations of `X^{m/2}` over `S`.
+    x_half_pow_inv: Vec<F>,
+    /// The inverses of the evaluations of `Z_S` over `S'`.
+    vanishing_inv: Vec<F>,
+    /// The evaluations of `Z_S^2 mod X^{m/2}` over the set.
+    vanishing_square: Vec<F>,
+}
+
+/// Defines a domain of size `2^k` over which polynomial arithmetic can be
+/// performed with ECFFTs. Works for any field for which `ECFFTParameters`
+/// with `log_size >= k` are known.
+///
+/// Evaluations over the domain are ordered like `elements()`. The elements at
+/// even positions form a subdomain of half the size, over which
+/// `low_degree_extension` is defined.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ECFFTDomain<F: Field> {
+    elements: Vec<F>,
+    levels: Vec<Level<F>>,
+    /// The precomputations for `extend` over sets of stride `2^{j + 1}`, with
+    /// offset 0 and with offset `2^j`.
+    extensions: Vec<[Vec<ExtensionPrecomputation<F>>; 2]>,
+    strides: Vec<StridePrecomputation<F>>,
+}
+
+impl<F: Field> ECFFTDomain<F> {
+    /// Construct a domain that is large enough for evaluations of a polynomial
+    /// having `num_coeffs` coefficients, or return `None` if `parameters` do
+    /// not support a domain of that size.
+    pub fn new(parameters: &ECFFTParameters<F>, num_coeffs: usize) -> Option<Self> {
+        let size = num_coeffs.checked_next_power_of_two()?;
+        let log_size = size.trailing_zeros();
+        let elements = parameters.coset(log_size)?;
+
+        // The kernel of the `i`-th isogeny is generated by the image of
+        // `2^{log_size - i - 1} * generator'`, which has order `2^{i + 1}`.
+        let generator = parameters
+            .double_repeatedly(Some(parameters.generator), parameters.log_size - log_size);
+        let mut kernels: Vec<F> = (0..log_size)
+            .map(|i| parameters.double_repeatedly(generator, log_size - i - 1))
+            .map(|point| point.map(|(x, _)| x))
+            .collect::<Option<_>>()?;
+
+        let mut levels = Vec::with_capacity(log_size as usize);
+        let mut coeff_a = parameters.coeff_a;
+        let mut current = elements.clone();
+        for i in 0..log_size as usize {
+            // Velu's formulas for the 2-isogeny with kernel `(pole, 0)`.
+            let pole = kernels[i];
+            let residue = pole.square().double() + &pole.square() + &coeff_a;
+            coeff_a -= &(residue.double().double() + &residue);
+            let psi = |x: F| Some(x + &(residue * &(x - &pole).inverse()?));
+            for kernel in &mut kernels[i + 1..] {
+                *kernel = psi(*kernel)?;
+            }
+            let next = current[..current.len() / 2]
+                .iter()
+                .map(|x| psi(*x))
+                .collect::<Option<Vec<_>>>()?;
+            debug_assert!(current[current.len() / 2..]
+                .iter()
+                .zip(&next)
+                .all(|(x, y)| psi(*x) == Some(*y)));
+            levels.push(Level {
+                elements: current,
+                pole,
+            });
+            current = next;
+        }
+
+        let extensions = (1..=log_size as usize)
+            .map(|log_stride| {
+                let stride = 1 << log_stride;
+                [
+                    ExtensionPrecomputation::new(&levels, stride, 0),
+                    ExtensionPrecomputation::new(&levels, stride, stride / 2),
+                ]
+            })
+            .collect();
+        let mut domain = Self {
+            elements,
+            levels,
+            extensions,
+            strides: Vec::with_capacity(log_size as usize),
+        };
+        for log_stride in 0..log_size {
+            let stride = 1 << log_stride;
+            let x_half_pow: Vec<F> = domain
+                .elements
+                .iter()
+                .step_by(stride)
+                .map(|x| x.pow([(size / stride / 2) as u64]))
+                .collect();
+            let mut x_half_pow_inv: Vec<F> = x_half_pow.iter().step_by(2).copied().collect();
+            batch_inversion(&mut x_half_pow_inv);
+            domain.strides.push(StridePrecomputation {
+                x_half_pow,
+                x_half_pow_inv,
+                vanishing_inv: Vec::new(),
+                vanishing_square: Vec::new(),
+            });
+        }
+        // Computing the vanishing polynomials for a stride requires converting
+        // evaluations to coefficients for twice that stride.
+        for log_stride in (0..log_size as usize).rev() {
+            let (vanishing_inv, vanishing_square) = domain.vanishing_precomputation(log_stride);
+            domain.strides[log_stride].vanishing_inv = vanishing_inv;
+            domain.strides[log_stride].vanishing_square = vanishing_square;
+        }
+        Some(domain)
+    }
+
+    /// Return the size of `self`.
+    pub fn size(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Return the elements of `self`.
+    pub fn elements(&self) -> &[F] {
+        &self.elements
+    }
+
+    /// Evaluates `polynomial` over `self`. Polynomials of degree at least the
+    /// size of `self` are split into chunks of that size.
+    pub fn evaluate(&self, polynomial: &DensePolynomial<F>) -> Vec<F> {
+        let size = self.size();
+        let mut chunks = polynomial.coeffs.chunks(size).rev();
+        let mut evaluations = match chunks.next() {
+            Some(chunk) => self.enter(0, chunk),
+            None => return vec![F::zero(); size],
+        };
+        let x_pow: Vec<F> = self.elements.iter().map(|x| x.pow([size as u64])).collect();
+        for chunk in chunks {
+            for ((e, chunk_eval), x_pow) in
+                evaluations.iter_mut().zip(self.enter(0, chunk)).zip(&x_pow)
+            {
+                *e = *e * x_pow + &chunk_eval;
+            }
+        }
+        evaluations
+    }
+
+    /// Returns the unique polynomial of degree less than the size of `self`
+    /// with the given `evaluations` over `self`.
+    pub fn interpolate(&self, evaluations: &[F]) -> DensePolynomial<F> {
+        assert_eq!(evaluations.len(), self.size());
+        DensePolynomial::from_coefficients_vec(self.exit(0, evaluations))
+    }
+
+    /// Given the `evaluations` of a polynomial of degree less than half the
+    /// size of `self` over the elements at even positions of `self`, returns
+    /// its evaluations over all of `self`.
+    pub fn low_degree_extension(&self, evaluations: &[F]) -> Vec<F> {
+        assert_eq!(2 * evaluations.len(), self.size());
+        let extension = self.extend(0, 1, 0, 1, evaluations);
+        interleave(evaluations, &extension)
+    }
+
+    /// Multiplies `a` and `b`, or returns `None` if the product has at least
+    /// as many coefficients as the size of `self`.
+    pub(crate) fn mul(&self, a: &[F], b: &[F]) -> Option<Vec<F>> {
+        if a.len() + b.len() > self.size() + 1 {
+            return None;
+        }
+        Some(self.mul_at_stride(0, a, b))
+    }
+
+    fn mul_at_stride(&self, log_stride: usize, a: &[F], b: &[F]) -> Vec<F> {
+        let mut evaluations = self.enter(log_stride, a);
+        for (a, b) in evaluations.iter_mut().zip(self.enter(log_stride, b)) {
+            *a *= &b;
+        }
+        self.exit(log_stride, &evaluations)
+    }
+
+    /// Given the evaluations of a polynomial `P` of degree less than `m` over
+    /// a set `L_level[from * stride / 2::stride]` of size `m`, where
+    /// `stride = 2^log_stride`, computes its evaluations over
+    /// `L_level[to * stride / 2::stride]`. Both `from` and `to` are 0 or 1.
+    ///
+    /// `P` can be written as `(P_0(psi(X)) + X P_1(psi(X))) v(X)^{m/2 - 1}`,
+    /// where `v(X) = X - pole` is the denominator of the isogeny `psi`, and
+    /// `P_0` and `P_1` have degree less than `m / 2`. For each pair of
+    /// elements with the same image under `psi`, the evaluations of `P_0` and
+    /// `P_1` at that image are obtained by solving a 2x2 linear system. Both
+    /// halves are recursively extended over the image of the target set, from
+    /// which the evaluations of `P` over the target set follow.
+    fn extend(
+        &self,
+        level: usize,
+        log_stride: usize,
+        from: usize,
+        to: usize,
+        evaluations: &[F],
+    ) -> Vec<F> {
+        let m = evaluations.len();
+        if m == 1 {
+            return evaluations.to_vec();
+        }
+        let half = m / 2;
+        let stride = 1 << log_stride;
+        let elements = &self.levels[level].elements;
+        let (from_offset, to_offset) = (from * stride / 2, to * stride / 2);
+        let source = &self.extensions[log_stride - 1][from][level];
+        let target = &self.extensions[log_stride - 1][to][level];
+
+        let mut p0 = Vec::with_capacity(half);
+        let mut p1 = Vec::with_capacity(half);
+        for r in 0..half {
+            let e0 = evaluations[r] * &source.v_pow_inv[r];
+            let e1 = evaluations[r + half] * &source.v_pow_inv[r + half];
+            let slope = (e0 - &e1) * &source.difference_inv[r];
+            p0.push(e0 - &(elements[from_offset + stride * r] * &slope));
+            p1.push(slope);
+        }
+
+        let q0 = self.extend(level + 1, log_stride, from, to, &p0);
+        let q1 = self.extend(level + 1, log_stride, from, to, &p1);
+        (0..m)
+            .map(|r| {
+                let t = elements[to_offset + stride * r];
+                (q0[r % half] + &(t * &q1[r % half])) * &target.v_pow[r]
+            })
+            .collect()
+    }
+
+    /// Evaluates the polynomial with coefficients `coeffs` over
+    /// `L_0[0::stride]`, where `stride = 2^log_stride`, by splitting it into
+    /// `lo + X^{m/2} hi`, evaluating both halves over the elements at even
+    /// positions, and extending them to the elements at odd positions.
+    fn enter(&self, log_stride: usize, coeffs: &[F]) -> Vec<F> {
+        let m = self.size() >> log_stride;
+        debug_assert!(coeffs.len() <= m);
+        if m == 1 {
+            return vec![coeffs.first().copied().unwrap_or_else(F::zero)];
+        }
+        let (lo, hi) = coeffs.split_at(coeffs.len().min(m / 2));
+        let lo_even = self.enter(log_stride + 1, lo);
+        let hi_even = self.enter(log_stride + 1, hi);
+        let lo_odd = self.extend(0, log_stride + 1, 0, 1, &lo_even);
+        let hi_odd = self.extend(0, log_stride + 1, 0, 1, &hi_even);
+        interleave(&lo_even, &lo_odd)
+            .into_iter()
+            .zip(interleave(&hi_even, &hi_odd))
+            .zip(&self.strides[log_stride].x_half_pow)
+            .map(|((lo, hi), x_half_pow)| lo + &(hi * x_half_pow))
+            .collect()
+    }
+
+    /// Computes the coefficients of the polynomial `P` of degree less than `m`
+    /// with the given evaluations over `L_0[0::stride]`, where
+    /// `stride = 2^log_stride`.
+    ///
+    /// Writing `P = lo + X^{m/2} hi`, the evaluations of `lo = P mod X^{m/2}`
+    /// over the elements `S` at even positions are computed by modular
+    /// reduction, which determines those of `hi`. Both halves are then
+    /// recursively converted to coefficients.
+    fn exit(&self, log_stride: usize, evaluations: &[F]) -> Vec<F> {
+        let m = evaluations.len();
+        debug_assert_eq!(m, self.size() >> log_stride);
+        if m == 1 {
+            return evaluations.to_vec();
+        }
+        let precomputation = &self.strides[log_stride];
+        let (even, odd) = deinterleave(evaluations);
+
+        // Since `REDC(P) = P Z_S^{-1} mod X^{m/2}` has degree less than
+        // `m / 2`, `REDC(REDC(P) (Z_S^2 mod X^{m/2}))` is `P mod X^{m/2}`.
+        let (t_even, t_odd) = self.redc(log_stride, &even, &odd);
+        let (c_even, c_odd) = deinterleave(&precomputation.vanishing_square);
+        let t_even: Vec<F> = t_even.iter().zip(c_even).map(|(t, c)| *t * &c).collect();
+        let t_odd: Vec<F> = t_odd.iter().zip(c_odd).map(|(t, c)| *t * &c).collect();
+        let (lo_even, _) = self.redc(log_stride, &t_even, &t_odd);
+
+        let hi_even: Vec<F> = even
+            .iter()
+            .zip(&lo_even)
+            .zip(&precomputation.x_half_pow_inv)
+            .map(|((p, lo), x_half_pow_inv)| (*p - lo) * x_half_pow_inv)
+            .collect();
+        let mut coeffs = self.exit(log_stride + 1, &lo_even);
+        coeffs.extend(self.exit(log_stride + 1, &hi_even));
+        coeffs
+    }
+
+    /// Montgomery reduction modulo `X^{m/2}` with respect to `Z_S`: given the
+    /// evaluations of `P` of degree less than `m` over `S` and `S'`, computes
+    /// those of `P Z_S^{-1} mod X^{m/2}`.
+    ///
+    /// With `A = -P / X^{m/2}` over `S`, `P + A X^{m/2}` vanishes over `S`, and
+    /// `(P + A X^{m/2}) / Z_S` is the result. It is computed over `S'` and
+    /// then extended back to `S`.
+    fn redc(&self, log_stride: usize, even: &[F], odd: &[F]) -> (Vec<F>, Vec<F>) {
+        let precomputation = &self.strides[log_stride];
+        let a_even: Vec<F> = even
+            .iter()
+            .zip(&precomputation.x_half_pow_inv)
+            .map(|(p, x_half_pow_inv)| -(*p * x_half_pow_inv))
+            .collect();
+        let a_odd = self.extend(0, log_stride + 1, 0, 1, &a_even);
+        let result_odd: Vec<F> = odd
+            .iter()
+            .zip(a_odd)
+            .zip(precomputation.x_half_pow.iter().skip(1).step_by(2))
+            .zip(&precomputation.vanishing_inv)
+            .map(|(((p, a), x_half_pow), vanishing_inv)| (a * x_half_pow + p) * vanishing_inv)
+            .collect();
+        let result_even = self.extend(0, log_stride + 1, 1, 0, &result_odd);
+        (result_even, result_odd)
+    }
+
+    /// Computes the inverses of the evaluations of `Z_S` over `S'`, and the
+    /// evaluations of `Z_S^2 mod X^{m/2}` over `L_0[0::stride]`.
+    ///
+    /// Writing `Z_S = X^{m/2} + W`, `W` has degree less than `m / 2` and equals
+    /// `-X^{m/2}` over `S`, which determines it, and
+    /// `Z_S^2 mod X^{m/2} = W^2 mod X^{m/2}`.
+    fn vanishing_precomputation(&self, log_stride: usize) -> (Vec<F>, Vec<F>) {
+        let m = self.size() >> log_stride;
+        let (x_half_pow_even, x_half_pow_odd) = deinterleave(&self.strides[log_stride].x_half_pow);
+        let w_even: Vec<F> = x_half_pow_even.iter().map(|x| -*x).collect();
+        let w_odd = self.extend(0, log_stride + 1, 0, 1, &w_even);
+        let mut vanishing_inv: Vec<F> = w_odd
+            .iter()
+            .zip(&x_half_pow_odd)
+            .map(|(w, x)| *w + x)
+            .collect();
+        batch_inversion(&mut vanishing_inv);
+
+        // With `W = W_lo + X^{m/4} W_hi`, `W^2 mod X^{m/2}` is
+        // `W_lo^2 + 2 X^{m/4} (W_lo W_hi mod X^{m/4})`, and both products fit
+        // into the domain of size `m / 2`.
+        let w = self.exit(log_stride + 1, &w_even);
+        let vanishing_square = if m == 2 {
+            vec![w[0].square()]
+        } else {
+            let (w_lo, w_hi) = w.split_at(m / 4);
+            let mut square = self.mul_at_stride(log_stride + 1, w_lo, w_lo);
+            let cross = self.mul_at_stride(log_stride + 1, w_lo, w_hi);
+            for (s, c) in square[m / 4..].iter_mut().zip(cross) {
+                *s += &c.double();
+            }
+            square
+        };
+        (vanishing_inv, self.enter(log_stride, &vanishing_square))
+    }
+}
+
+/// Returns the vector whose elements at even positions are `even` and whose
+/// elements at odd positions are `odd`.
+fn interleave<F: Field>(even: &[F], odd: &[F]) -> Vec<F> {
+    let mut result = Vec::with_capacity(even.len() + odd.len());
+    for (e, o) in even.iter().zip(odd) {
+        result.push(*e);
+        result.push(*o);
+    }
+    result
+}
+
+/// Splits `v` into its elements at even positions and at odd positions.
+fn deinterleave<F: Field>(v: &[F]) -> (Vec<F>, Vec<F>) {
+    let even = v.iter().step_by(2).copied().collect();
+    let odd = v.iter().skip(1).step_by(2).copied().collect();
+    (even, odd)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DensePolynomial, ECFFTDomain, ECFFTParameters};
+    use algebra::bls12_381::Fq;
+    use algebra_core::{test_rng, CanonicalDeserialize, CanonicalSerialize, ToBytes, Zero};
+
+    const LOG_SIZE: u32 = 5;
+
+    fn parameters() -> ECFFTParameters<Fq> {
+        ECFFTParameters::sample(LOG_SIZE, &mut test_rng())
+    }
+
+    #[test]
+    fn sampled_parameters() {
+        let parameters = parameters();
+        let domain = ECFFTDomain::new(&parameters, 1 << LOG_SIZE).unwrap();
+        assert_eq!(domain.size(), 1 << LOG_SIZE);
+        assert!(ECFFTDomain::new(&parameters, (1 << LOG_SIZE) + 1).is_none());
+
+        let mut invalid = parameters;
+        invalid.offset.1 += &invalid.offset.0;
+        assert!(ECFFTDomain::new(&invalid, 4).is_none());
+        let mut invalid = parameters;
+        invalid.log_size += 1;
+        assert!(ECFFTDomain::new(&invalid, 4).is_none());
+    }
+
+    #[test]
+    fn serialize_parameters() {
+        let parameters = parameters();
+        let mut bytes = Vec::new();
+        parameters.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), parameters.serialized_size());
+        assert_eq!(
+            ECFFTParameters::deserialize(&bytes[..]).unwrap(),
+            parameters
+        );
+        let mut to_bytes = Vec::new();
+        parameters.write(&mut to_bytes).unwrap();
+        assert_eq!(to_bytes.len(), bytes.len());
+
+        let mut invalid = parameters;
+        invalid.offset.1 += &invalid.offset.0;
+        let mut bytes = Vec::new();
+        invalid.serialize(&mut bytes).unwrap();
+        assert!(ECFFTParameters::<Fq>::deserialize(&bytes[..]).is_err());
+        let mut invalid = parameters;
+        invalid.log_size += 1;
+        let mut bytes = Vec::new();
+        invalid.serialize(&mut bytes).unwrap();
+        assert!(ECFFTParameters::<Fq>::deserialize(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn evaluate_and_interpolate() {
+        let parameters = parameters();
+        let rng = &mut test_rng();
+        for log_size in 0..=LOG_SIZE {
+            let domain = ECFFTDomain::new(&parameters, 1 << log_size).unwrap();
+            for degree in 0..domain.size() {
+                let polynomial = DensePolynomial::<Fq>::rand(degree, rng);
+                let evaluations = domain.evaluate(&polynomial);
+                for (x, evaluation) in domain.elements().iter().zip(&evaluations) {
+                    assert_eq!(polynomial.evaluate(*x), *evaluation);
+                }
+                assert_eq!(domain.interpolate(&evaluations), polynomial);
+            }
+        }
+    }
+
+    #[test]
+    fn evaluate_high_degree() {
+        let parameters = parameters();
+        let rng = &mut test_rng();
+        let domain = ECFFTDomain::new(&parameters, 8).unwrap();
+        let polynomial = DensePolynomial::<Fq>::rand(29, rng);
+        let evaluations = domain.evaluate(&polynomial);
+        for (x, evaluation) in domain.elements().iter().zip(&evaluations) {
+            assert_eq!(polynomial.evaluate(*x), *evaluation);
+        }
+        assert!(domain
+            .evaluate(&DensePolynomial::zero())
+            .iter()
+            .all(Zero::is_zero));
+    }
+
+    #[test]
+    fn low_degree_extension() {
+        let parameters = parameters();
+        let rng = &mut test_rng();
+        let domain = ECFFTDomain::new(&parameters, 1 << LOG_SIZE).unwrap();
+        let polynomial = DensePolynomial::<Fq>::rand(domain.size() / 2 - 1, rng);
+        let evaluations: Vec<Fq> = domain
+            .elements()
+            .iter()
+            .step_by(2)
+            .map(|x| polynomial.evaluate(*x))
+            .collect();
+        assert_eq!(
+            domain.low_degree_extension(&evaluations),
+            domain.evaluate(&polynomial)
+        );
+    }
+
+    #[test]
+    fn mul_polynomials() {
+        let parameters = parameters();
+        let rng = &mut test_rng();
+        let domain = ECFFTDomain::new(&parameters, 1 << LOG_SIZE).unwrap();
+        for (a_degree, b_degree) in &[(0, 0), (3, 7), (15, 16), (30, 1)] {
+            let a = DensePolynomial::<Fq>::rand(*a_degree, rng);
+            let b = DensePolynomial::<Fq>::rand(*b_degree, rng);
+            assert_eq!(a.mul_with_ecfft(&b, &domain), Some(a.naive_mul(&b)));
+        }
+        let a = DensePolynomial::<Fq>::rand(16, rng);
+        assert_eq!(a.mul_with_ecfft(&a, &domain), None);
+        assert_eq!(
+            a.mul_with_ecfft(&DensePolynomial::zero(), &domain),
+            Some(DensePolynomial::zero())
+        );
+    }
+}
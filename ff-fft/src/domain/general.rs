@@ -11,7 +11,12 @@ use crate::domain::{
     DomainCoeff, EvaluationDomain, MixedRadixEvaluationDomain, Radix2EvaluationDomain,
 };
 use crate::Vec;
-use algebra_core::{FftField, FftParameters};
+use algebra_core::{
+    bytes::ToBytes,
+    io::{Read, Result as IoResult, Write},
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+    FftField, FftParameters,
+};
 
 /// Defines a domain over which finite field (I)FFTs can be performed.
 /// Generally tries to build a radix-2 domain and falls back to a mixed-radix
@@ -48,6 +53,28 @@ impl<F: FftField> EvaluationDomain<F> for GeneralEvaluationDomain<F> {
         None
     }
 
+    fn get_coset(&self, offset: F) -> Option<Self> {
+        Some(match self {
+            GeneralEvaluationDomain::Radix2(domain) => {
+                GeneralEvaluationDomain::Radix2(domain.get_coset(offset)?)
+            },
+            GeneralEvaluationDomain::MixedRadix(domain) => {
+                GeneralEvaluationDomain::MixedRadix(domain.get_coset(offset)?)
+            },
+        })
+    }
+
+    fn get_subdomain(&self, k: usize) -> Option<Self> {
+        Some(match self {
+            GeneralEvaluationDomain::Radix2(domain) => {
+                GeneralEvaluationDomain::Radix2(domain.get_subdomain(k)?)
+            },
+            GeneralEvaluationDomain::MixedRadix(domain) => {
+                GeneralEvaluationDomain::MixedRadix(domain.get_subdomain(k)?)
+            },
+        })
+    }
+
     fn compute_size_of_domain(num_coeffs: usize) -> Option<usize> {
         let domain_size = Radix2EvaluationDomain::<F>::compute_size_of_domain(num_coeffs);
         if let Some(domain_size) = domain_size {
@@ -71,6 +98,46 @@ impl<F: FftField> EvaluationDomain<F> for GeneralEvaluationDomain<F> {
         }
     }
 
+    #[inline]
+    fn group_gen(&self) -> F {
+        match self {
+            GeneralEvaluationDomain::Radix2(domain) => domain.group_gen,
+            GeneralEvaluationDomain::MixedRadix(domain) => domain.group_gen,
+        }
+    }
+
+    #[inline]
+    fn group_gen_inv(&self) -> F {
+        match self {
+            GeneralEvaluationDomain::Radix2(domain) => domain.group_gen_inv,
+            GeneralEvaluationDomain::MixedRadix(domain) => domain.group_gen_inv,
+        }
+    }
+
+    #[inline]
+    fn coset_offset(&self) -> F {
+        match self {
+            GeneralEvaluationDomain::Radix2(domain) => domain.offset,
+            GeneralEvaluationDomain::MixedRadix(domain) => domain.offset,
+        }
+    }
+
+    #[inline]
+    fn coset_offset_inv(&self) -> F {
+        match self {
+            GeneralEvaluationDomain::Radix2(domain) => domain.offset_inv,
+            GeneralEvaluationDomain::MixedRadix(domain) => domain.offset_inv,
+        }
+    }
+
+    #[inline]
+    fn coset_offset_pow_size(&self) -> F {
+        match self {
+            GeneralEvaluationDomain::Radix2(domain) => domain.offset_pow_size,
+            GeneralEvaluationDomain::MixedRadix(domain) => domain.offset_pow_size,
+        }
+    }
+
     #[inline]
     fn fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>) {
         match self {
@@ -146,6 +213,63 @@ impl<F: FftField> EvaluationDomain<F> for GeneralEvaluationDomain<F> {
     }
 }
 
+impl<F: FftField> ToBytes for GeneralEvaluationDomain<F> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        match self {
+            GeneralEvaluationDomain::Radix2(domain) => {
+                0u8.write(&mut writer)?;
+                domain.write(&mut writer)
+            },
+            GeneralEvaluationDomain::MixedRadix(domain) => {
+                1u8.write(&mut writer)?;
+                domain.write(&mut writer)
+            },
+        }
+    }
+}
+
+/// The variant is serialized as a one-byte tag, `0` for radix-2 and `1` for
+/// mixed-radix domains, followed by the domain itself.
+impl<F: FftField> CanonicalSerialize for GeneralEvaluationDomain<F> {
+    #[inline]
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        match self {
+            GeneralEvaluationDomain::Radix2(domain) => {
+                0u8.serialize(&mut writer)?;
+                domain.serialize(&mut writer)
+            },
+            GeneralEvaluationDomain::MixedRadix(domain) => {
+                1u8.serialize(&mut writer)?;
+                domain.serialize(&mut writer)
+            },
+        }
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        1 + match self {
+            GeneralEvaluationDomain::Radix2(domain) => domain.serialized_size(),
+            GeneralEvaluationDomain::MixedRadix(domain) => domain.serialized_size(),
+        }
+    }
+}
+
+impl<F: FftField> CanonicalDeserialize for GeneralEvaluationDomain<F> {
+    #[inline]
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        match u8::deserialize(&mut reader)? {
+            0 => Ok(GeneralEvaluationDomain::Radix2(
+                Radix2EvaluationDomain::deserialize(&mut reader)?,
+            )),
+            1 => Ok(GeneralEvaluationDomain::MixedRadix(
+                MixedRadixEvaluationDomain::deserialize(&mut reader)?,
+            )),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
 /// A generalized version of an iterator over the elements of a domain.
 pub enum GeneralElements<F: FftField> {
     /// A basic iterator over the elements of a domain (currently, the only one in use).
@@ -15,7 +15,13 @@ use crate::domain::{
     DomainCoeff, EvaluationDomain,
 };
 use crate::Vec;
-use algebra_core::{fields::utils::k_adicity, FftField, FftParameters};
+use algebra_core::{
+    bytes::ToBytes,
+    fields::utils::k_adicity,
+    io::{Read, Result as IoResult, Write},
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+    FftField, FftParameters,
+};
 use core::cmp::min;
 use core::fmt;
 #[cfg(feature = "parallel")]
@@ -24,6 +30,7 @@ use rayon::prelude::*;
 /// Defines a domain over which finite field (I)FFTs can be performed. Works
 /// only for fields that have a multiplicative subgroup of size that is
 /// a power-of-2 and another small subgroup over a different base defined.
+/// The domain is either such a subgroup or a coset of it.
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
 pub struct MixedRadixEvaluationDomain<F: FftField> {
     /// The size of the domain.
@@ -40,15 +47,29 @@ pub struct MixedRadixEvaluationDomain<F: FftField> {
     pub group_gen_inv: F,
     /// Multiplicative generator of the finite field.
     pub generator_inv: F,
+    /// Offset of the coset, which is one for the subgroup itself.
+    pub offset: F,
+    /// Inverse of the offset.
+    pub offset_inv: F,
+    /// `offset^size`.
+    pub offset_pow_size: F,
 }
 
 impl<F: FftField> fmt::Debug for MixedRadixEvaluationDomain<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Mixed-radix multiplicative subgroup of size {}",
-            self.size
-        )
+        if self.offset.is_one() {
+            write!(
+                f,
+                "Mixed-radix multiplicative subgroup of size {}",
+                self.size
+            )
+        } else {
+            write!(
+                f,
+                "Mixed-radix multiplicative coset of size {} with offset {}",
+                self.size, self.offset
+            )
+        }
     }
 }
 
@@ -94,6 +115,36 @@ impl<F: FftField> EvaluationDomain<F> for MixedRadixEvaluationDomain<F> {
             group_gen,
             group_gen_inv: group_gen.inverse()?,
             generator_inv: F::multiplicative_generator().inverse()?,
+            offset: F::one(),
+            offset_inv: F::one(),
+            offset_pow_size: F::one(),
+        })
+    }
+
+    fn get_coset(&self, offset: F) -> Option<Self> {
+        Some(MixedRadixEvaluationDomain {
+            offset,
+            offset_inv: offset.inverse()?,
+            offset_pow_size: offset.pow([self.size]),
+            ..*self
+        })
+    }
+
+    fn get_subdomain(&self, k: usize) -> Option<Self> {
+        if k == 0 || self.size() % k != 0 {
+            return None;
+        }
+        let size = self.size / k as u64;
+        let size_as_field_element = F::from(size);
+        Some(MixedRadixEvaluationDomain {
+            size,
+            log_size_of_group: k_adicity(2, size as usize),
+            size_as_field_element,
+            size_inv: size_as_field_element.inverse()?,
+            group_gen: self.group_gen.pow([k as u64]),
+            group_gen_inv: self.group_gen_inv.pow([k as u64]),
+            offset_pow_size: self.offset.pow([size]),
+            ..*self
         })
     }
 
@@ -122,8 +173,36 @@ impl<F: FftField> EvaluationDomain<F> for MixedRadixEvaluationDomain<F> {
         self.size as usize
     }
 
+    #[inline]
+    fn group_gen(&self) -> F {
+        self.group_gen
+    }
+
+    #[inline]
+    fn group_gen_inv(&self) -> F {
+        self.group_gen_inv
+    }
+
+    #[inline]
+    fn coset_offset(&self) -> F {
+        self.offset
+    }
+
+    #[inline]
+    fn coset_offset_inv(&self) -> F {
+        self.offset_inv
+    }
+
+    #[inline]
+    fn coset_offset_pow_size(&self) -> F {
+        self.offset_pow_size
+    }
+
     #[inline]
     fn fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>) {
+        if !self.offset.is_one() {
+            Self::distribute_powers(coeffs, self.offset);
+        }
         coeffs.resize(self.size(), T::zero());
         best_fft(
             coeffs,
@@ -142,7 +221,11 @@ impl<F: FftField> EvaluationDomain<F> for MixedRadixEvaluationDomain<F> {
             self.log_size_of_group,
             serial_mixed_radix_fft::<T, F>,
         );
-        cfg_iter_mut!(evals).for_each(|val| *val *= self.size_inv);
+        if self.offset.is_one() {
+            cfg_iter_mut!(evals).for_each(|val| *val *= self.size_inv);
+        } else {
+            Self::distribute_powers_and_mul_by_const(evals, self.offset_inv, self.size_inv);
+        }
     }
 
     #[inline]
@@ -152,6 +235,9 @@ impl<F: FftField> EvaluationDomain<F> for MixedRadixEvaluationDomain<F> {
     }
 
     fn evaluate_all_lagrange_coefficients(&self, tau: F) -> Vec<F> {
+        // The Lagrange polynomials of `offset * H` at `tau` are those of `H` at
+        // `tau / offset`.
+        let tau = tau * &self.offset_inv;
         // Evaluate all Lagrange polynomials
         let size = self.size as usize;
         let t_size = tau.pow(&[self.size]);
@@ -192,21 +278,21 @@ impl<F: FftField> EvaluationDomain<F> for MixedRadixEvaluationDomain<F> {
     }
 
     fn vanishing_polynomial(&self) -> crate::SparsePolynomial<F> {
-        let coeffs = vec![(0, -F::one()), (self.size(), F::one())];
+        let coeffs = vec![(0, -self.offset_pow_size), (self.size(), F::one())];
         crate::SparsePolynomial::from_coefficients_vec(coeffs)
     }
 
     /// This evaluates the vanishing polynomial for this domain at tau.
     /// For multiplicative subgroups, this polynomial is `z(X) = X^self.size -
-    /// 1`.
+    /// 1`, and for their cosets it is `z(X) = X^self.size - offset^self.size`.
     fn evaluate_vanishing_polynomial(&self, tau: F) -> F {
-        tau.pow(&[self.size]) - &F::one()
+        tau.pow(&[self.size]) - &self.offset_pow_size
     }
 
     /// Return an iterator over the elements of the domain.
     fn elements(&self) -> Elements<F> {
         Elements {
-            cur_elem: F::one(),
+            cur_elem: self.offset,
             cur_pow: 0,
             size: self.size,
             group_gen: self.group_gen,
@@ -214,6 +300,54 @@ impl<F: FftField> EvaluationDomain<F> for MixedRadixEvaluationDomain<F> {
     }
 }
 
+impl<F: FftField> ToBytes for MixedRadixEvaluationDomain<F> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.size.write(&mut writer)?;
+        self.offset.write(&mut writer)
+    }
+}
+
+/// A domain is serialized as its size and offset, from which everything else
+/// is recomputed on deserialization.
+impl<F: FftField> CanonicalSerialize for MixedRadixEvaluationDomain<F> {
+    #[inline]
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.size.serialize(&mut writer)?;
+        self.offset.serialize(&mut writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.size.serialized_size() + self.offset.serialized_size()
+    }
+}
+
+impl<F: FftField> CanonicalDeserialize for MixedRadixEvaluationDomain<F> {
+    /// Fails unless the field has a mixed-radix subgroup of exactly the
+    /// serialized size and the offset is nonzero.
+    #[inline]
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let size = u64::deserialize(&mut reader)?;
+        let offset = F::deserialize(&mut reader)?;
+        // Check that `size = 2^a * q^b` for exponents within the adicities of
+        // the field, since `new` would otherwise round it up.
+        let q = F::FftParams::SMALL_SUBGROUP_BASE.ok_or(SerializationError::InvalidData)? as usize;
+        let two_adicity = k_adicity(2, size as usize);
+        let q_adicity = k_adicity(q, size as usize >> two_adicity);
+        if two_adicity > F::FftParams::TWO_ADICITY
+            || Some(q_adicity) > F::FftParams::SMALL_SUBGROUP_BASE_ADICITY
+            || (q.pow(q_adicity) << two_adicity) as u64 != size
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        Self::new(size as usize)
+            .filter(|domain| domain.size == size)
+            .and_then(|domain| domain.get_coset(offset))
+            .ok_or(SerializationError::InvalidData)
+    }
+}
+
 fn mixed_radix_fft_permute(
     two_adicity: u32,
     q_adicity: u32,
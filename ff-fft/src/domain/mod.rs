@@ -14,13 +14,17 @@ use rand::Rng;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+pub mod ecfft;
 pub mod general;
 pub mod mixed_radix;
+pub mod plan;
 pub mod radix2;
 pub(crate) mod utils;
 
+pub use ecfft::{ECFFTDomain, ECFFTParameters};
 pub use general::GeneralEvaluationDomain;
 pub use mixed_radix::MixedRadixEvaluationDomain;
+pub use plan::FFTPlan;
 pub use radix2::Radix2EvaluationDomain;
 
 /// Defines a domain over which finite field (I)FFTs can be performed. The
@@ -46,6 +50,23 @@ pub trait EvaluationDomain<F: FftField>:
     /// having `num_coeffs` coefficients.
     fn new(num_coeffs: usize) -> Option<Self>;
 
+    /// Construct a coset domain `offset * H`, where `H` is the subgroup that
+    /// `Self::new(num_coeffs)` would return.
+    fn new_coset(num_coeffs: usize, offset: F) -> Option<Self> {
+        Self::new(num_coeffs)?.get_coset(offset)
+    }
+
+    /// Return the coset `offset * H` of the subgroup `H` underlying `self`.
+    /// The offset of `self` is discarded, so that `get_coset(F::one())`
+    /// returns the subgroup itself. Returns `None` if `offset` is zero.
+    fn get_coset(&self, offset: F) -> Option<Self>;
+
+    /// Return the domain of size `self.size() / k` that is generated by
+    /// `self.group_gen()^k` and has the same offset as `self`. Its elements
+    /// are the elements of `self` whose index is a multiple of `k`.
+    /// Returns `None` if `k` is zero or does not divide `self.size()`.
+    fn get_subdomain(&self, k: usize) -> Option<Self>;
+
     /// Return the size of a domain that is large enough for evaluations of a
     /// polynomial having `num_coeffs` coefficients.
     fn compute_size_of_domain(num_coeffs: usize) -> Option<usize>;
@@ -58,6 +79,26 @@ pub trait EvaluationDomain<F: FftField>:
         F::from(self.size() as u64)
     }
 
+    /// Return the generator of the subgroup underlying `self`.
+    fn group_gen(&self) -> F;
+
+    /// Return the inverse of `self.group_gen()`.
+    fn group_gen_inv(&self) -> F;
+
+    /// Return the offset of `self`, which is one if `self` is a subgroup.
+    fn coset_offset(&self) -> F;
+
+    /// Return the inverse of `self.coset_offset()`.
+    fn coset_offset_inv(&self) -> F;
+
+    /// Return `self.coset_offset()^self.size()`.
+    fn coset_offset_pow_size(&self) -> F;
+
+    /// Return whether `self` is a proper coset, rather than a subgroup.
+    fn is_coset(&self) -> bool {
+        !self.coset_offset().is_one()
+    }
+
     /// Compute a FFT.
     #[inline]
     fn fft<T: DomainCoeff<F>>(&self, coeffs: &[T]) -> Vec<T> {
@@ -66,7 +107,8 @@ pub trait EvaluationDomain<F: FftField>:
         coeffs
     }
 
-    /// Compute a FFT, modifying the vector in place.
+    /// Compute a FFT, modifying the vector in place. If `self` is a coset, this
+    /// evaluates the polynomial over the elements of the coset.
     fn fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>);
 
     /// Compute a IFFT.
@@ -77,9 +119,36 @@ pub trait EvaluationDomain<F: FftField>:
         evals
     }
 
-    /// Compute a IFFT, modifying the vector in place.
+    /// Compute a IFFT, modifying the vector in place. If `self` is a coset, this
+    /// interpolates evaluations over the elements of the coset.
     fn ifft_in_place<T: DomainCoeff<F>>(&self, evals: &mut Vec<T>);
 
+    /// Given the first `self.size()` powers `{ \tau^i G }` of a secret `\tau`
+    /// in the monomial basis, computes `{ L_i(\tau) G }`, where `L_i` is the
+    /// `i`-th Lagrange polynomial of `self`.
+    ///
+    /// Since `L_i(\tau) = n^{-1} \sum_j \omega^{-ij} \tau^j`, this is exactly an
+    /// IFFT of the powers, which takes O(n log n) group operations instead of
+    /// the O(n) scalar multiplications required when starting from
+    /// `evaluate_all_lagrange_coefficients`.
+    ///
+    /// For a coset `g * H`, `L_i(\tau)` equals the `i`-th Lagrange polynomial
+    /// of `H` at `g^{-1} \tau`, so the powers are first scaled by powers of
+    /// `g^{-1}`.
+    fn monomial_to_lagrange_basis<T: DomainCoeff<F>>(&self, powers_of_tau: &[T]) -> Vec<T> {
+        assert!(
+            powers_of_tau.len() >= self.size(),
+            "not enough powers to compute the Lagrange basis"
+        );
+        if !self.is_coset() {
+            return self.ifft(&powers_of_tau[..self.size()]);
+        }
+        let mut powers = powers_of_tau[..self.size()].to_vec();
+        Self::distribute_powers(&mut powers, self.coset_offset_inv());
+        self.get_coset(F::one()).unwrap().ifft_in_place(&mut powers);
+        powers
+    }
+
     /// Multiply the `i`-th element of `coeffs` with the `i`-th power of `g`.
     fn distribute_powers<T: DomainCoeff<F>>(coeffs: &mut [T], g: F) {
         let mut pow = F::one();
@@ -89,7 +158,17 @@ pub trait EvaluationDomain<F: FftField>:
         })
     }
 
-    /// Compute a FFT over a coset of the domain.
+    /// Multiply the `i`-th element of `coeffs` with `c * g^i`.
+    fn distribute_powers_and_mul_by_const<T: DomainCoeff<F>>(coeffs: &mut [T], g: F, c: F) {
+        let mut pow = c;
+        coeffs.iter_mut().for_each(|coeff| {
+            *coeff *= pow;
+            pow *= &g
+        })
+    }
+
+    /// Compute a FFT over a coset of the domain, namely `self` shifted by
+    /// `F::multiplicative_generator()`.
     #[inline]
     fn coset_fft<T: DomainCoeff<F>>(&self, coeffs: &[T]) -> Vec<T> {
         let mut coeffs = coeffs.to_vec();
@@ -131,6 +210,26 @@ pub trait EvaluationDomain<F: FftField>:
     /// This evaluates the vanishing polynomial for this domain at tau.
     fn evaluate_vanishing_polynomial(&self, tau: F) -> F;
 
+    /// Evaluate the vanishing polynomial of `self` over all the elements of
+    /// `other`, in the order given by `other.elements()`.
+    ///
+    /// The vanishing polynomial of `g * H` is `X^n - g^n`, and the `n`-th
+    /// powers of the elements of `other` form a geometric sequence, so this
+    /// takes O(|other|) field multiplications.
+    fn evaluate_vanishing_polynomial_over_domain(&self, other: &Self) -> Vec<F> {
+        let n = [self.size() as u64];
+        let step = other.group_gen().pow(n);
+        let mut cur = other.coset_offset().pow(n);
+        let offset_pow_size = self.coset_offset_pow_size();
+        (0..other.size())
+            .map(|_| {
+                let eval = cur - &offset_pow_size;
+                cur *= &step;
+                eval
+            })
+            .collect()
+    }
+
     /// Return an iterator over the elements of the domain.
     fn elements(&self) -> Self::Elements;
 
@@ -139,7 +238,7 @@ pub trait EvaluationDomain<F: FftField>:
     /// a coset.
     fn divide_by_vanishing_poly_on_coset_in_place(&self, evals: &mut [F]) {
         let i = self
-            .evaluate_vanishing_polynomial(F::multiplicative_generator())
+            .evaluate_vanishing_polynomial(F::multiplicative_generator() * &self.coset_offset())
             .inverse()
             .unwrap();
 
@@ -173,6 +272,39 @@ pub trait EvaluationDomain<F: FftField>:
         }
     }
 
+    /// Given the evaluations of a polynomial over `self`, return its
+    /// evaluations over the `k` cosets of `self.get_subdomain(k)` that
+    /// partition `self`. The `t`-th vector holds the evaluations over the
+    /// subdomain shifted by `self.group_gen()^t`, i.e. the evaluations at the
+    /// indices that are congruent to `t` modulo `k`.
+    fn split_evaluations_by_subdomain<T: Copy>(&self, evals: &[T], k: usize) -> Vec<Vec<T>> {
+        assert_eq!(evals.len(), self.size(), "wrong number of evaluations");
+        assert!(
+            k > 0 && self.size() % k == 0,
+            "k must divide the domain size"
+        );
+        (0..k)
+            .map(|t| evals[t..].iter().step_by(k).copied().collect())
+            .collect()
+    }
+
+    /// The inverse of `split_evaluations_by_subdomain`: interleaves the
+    /// evaluations over the cosets of a subdomain into evaluations over
+    /// `self`.
+    fn merge_evaluations_from_subdomain<T: Copy>(&self, evals: &[Vec<T>]) -> Vec<T> {
+        let k = evals.len();
+        assert!(
+            k > 0 && self.size() % k == 0,
+            "k must divide the domain size"
+        );
+        let sub_size = self.size() / k;
+        assert!(
+            evals.iter().all(|e| e.len() == sub_size),
+            "wrong number of evaluations"
+        );
+        (0..self.size()).map(|i| evals[i % k][i / k]).collect()
+    }
+
     /// Perform O(n) multiplication of two polynomials that are presented by
     /// their evaluations in the domain.
     /// Returns the evaluations of the product over the domain.
@@ -193,6 +325,10 @@ pub trait EvaluationDomain<F: FftField>:
 }
 
 /// Types that can be FFT-ed must implement this trait.
+///
+/// Besides field elements, this is implemented for the projective points of
+/// every curve model in `algebra-core`, so that one can FFT over vectors of
+/// group elements with their scalar field as `F`.
 pub trait DomainCoeff<F: FftField>:
     Copy
     + Send
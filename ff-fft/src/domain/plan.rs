@@ -0,0 +1,464 @@
+//! This module defines `FFTPlan`, which precomputes the twiddle factors of an
+//! `EvaluationDomain` so that they can be shared by many FFTs over the same
+//! domain, as in the Groth16 prover.
+//!
+//! For domains whose size is a power of two, the plan runs its own FFT: after
+//! the bit-reversal permutation, the butterflies of two consecutive layers are
+//! merged into a single radix-4 pass, and the first layers are processed one
+//! cache-sized block at a time, so that each block stays in cache while all of
+//! its layers are computed. Other domains fall back to the FFTs of the domain.
+
+use crate::domain::{utils::bitreverse, DomainCoeff, EvaluationDomain, GeneralEvaluationDomain};
+use crate::Vec;
+use algebra_core::FftField;
+use core::fmt;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The base-2 logarithm of the number of elements in a block of the first
+/// layers of the FFT.
+const LOG_BLOCK_SIZE: u32 = 10;
+
+/// The number of butterflies that are performed by a single task when a
+/// layer of the FFT is split across threads.
+const TASK_SIZE: usize = 1 << 9;
+
+/// An FFT plan for the domain `D`, which caches the powers of the generator of
+/// the domain and of its inverse.
+#[derive(Clone)]
+pub struct FFTPlan<F: FftField, D: EvaluationDomain<F> = GeneralEvaluationDomain<F>> {
+    domain: D,
+    log_size: u32,
+    /// `omega^i` for `0 <= i < n/2`, where `omega` generates the domain. It is
+    /// empty if `n` is not a power of two.
+    twiddles: Vec<F>,
+    /// `omega^{-i}` for `0 <= i < n/2`.
+    inv_twiddles: Vec<F>,
+}
+
+impl<F: FftField, D: EvaluationDomain<F>> fmt::Debug for FFTPlan<F, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FFT plan over {:?}", self.domain)
+    }
+}
+
+impl<F: FftField, D: EvaluationDomain<F>> FFTPlan<F, D> {
+    /// Precomputes the twiddle factors of `domain`.
+    pub fn new(domain: D) -> Self {
+        let size = domain.size();
+        let (twiddles, inv_twiddles) = if size.is_power_of_two() {
+            (
+                powers(domain.group_gen(), size / 2),
+                powers(domain.group_gen_inv(), size / 2),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        Self {
+            domain,
+            log_size: size.trailing_zeros(),
+            twiddles,
+            inv_twiddles,
+        }
+    }
+
+    /// Returns the domain of `self`.
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    /// Returns whether `self` runs its own FFT, rather than the one of its
+    /// domain.
+    fn is_radix2(&self) -> bool {
+        self.domain.size().is_power_of_two()
+    }
+
+    /// Compute a FFT.
+    pub fn fft<T: DomainCoeff<F>>(&self, coeffs: &[T]) -> Vec<T> {
+        let mut coeffs = coeffs.to_vec();
+        self.fft_in_place(&mut coeffs);
+        coeffs
+    }
+
+    /// Compute a FFT, modifying the vector in place. This agrees with
+    /// `self.domain().fft_in_place(coeffs)`.
+    pub fn fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>) {
+        if self.is_radix2() {
+            self.forward(coeffs, self.domain.coset_offset());
+        } else {
+            self.domain.fft_in_place(coeffs);
+        }
+    }
+
+    /// Compute a IFFT.
+    pub fn ifft<T: DomainCoeff<F>>(&self, evals: &[T]) -> Vec<T> {
+        let mut evals = evals.to_vec();
+        self.ifft_in_place(&mut evals);
+        evals
+    }
+
+    /// Compute a IFFT, modifying the vector in place. This agrees with
+    /// `self.domain().ifft_in_place(evals)`.
+    pub fn ifft_in_place<T: DomainCoeff<F>>(&self, evals: &mut Vec<T>) {
+        if self.is_radix2() {
+            self.inverse(evals, self.domain.coset_offset_inv());
+        } else {
+            self.domain.ifft_in_place(evals);
+        }
+    }
+
+    /// Compute a FFT over a coset of the domain, modifying the vector in
+    /// place. This agrees with `self.domain().coset_fft_in_place(coeffs)`.
+    pub fn coset_fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>) {
+        if self.is_radix2() {
+            self.forward(coeffs, self.coset_offset());
+        } else {
+            self.domain.coset_fft_in_place(coeffs);
+        }
+    }
+
+    /// Compute a IFFT over a coset of the domain, modifying the vector in
+    /// place. This agrees with `self.domain().coset_ifft_in_place(evals)`.
+    pub fn coset_ifft_in_place<T: DomainCoeff<F>>(&self, evals: &mut Vec<T>) {
+        if self.is_radix2() {
+            self.inverse(evals, self.coset_offset().inverse().unwrap());
+        } else {
+            self.domain.coset_ifft_in_place(evals);
+        }
+    }
+
+    /// Compute the FFTs of many vectors, in place.
+    pub fn fft_batch_in_place<T: DomainCoeff<F>>(&self, vectors: &mut [Vec<T>]) {
+        if self.is_radix2() {
+            self.forward_batch(vectors, self.domain.coset_offset());
+        } else {
+            vectors.iter_mut().for_each(|v| self.domain.fft_in_place(v));
+        }
+    }
+
+    /// Compute the IFFTs of many vectors, in place.
+    pub fn ifft_batch_in_place<T: DomainCoeff<F>>(&self, vectors: &mut [Vec<T>]) {
+        if self.is_radix2() {
+            self.inverse_batch(vectors, self.domain.coset_offset_inv());
+        } else {
+            vectors
+                .iter_mut()
+                .for_each(|v| self.domain.ifft_in_place(v));
+        }
+    }
+
+    /// Compute the FFTs of many vectors over a coset of the domain, in place.
+    pub fn coset_fft_batch_in_place<T: DomainCoeff<F>>(&self, vectors: &mut [Vec<T>]) {
+        if self.is_radix2() {
+            self.forward_batch(vectors, self.coset_offset());
+        } else {
+            vectors
+                .iter_mut()
+                .for_each(|v| self.domain.coset_fft_in_place(v));
+        }
+    }
+
+    /// Compute the IFFTs of many vectors over a coset of the domain, in place.
+    pub fn coset_ifft_batch_in_place<T: DomainCoeff<F>>(&self, vectors: &mut [Vec<T>]) {
+        if self.is_radix2() {
+            self.inverse_batch(vectors, self.coset_offset().inverse().unwrap());
+        } else {
+            vectors
+                .iter_mut()
+                .for_each(|v| self.domain.coset_ifft_in_place(v));
+        }
+    }
+
+    /// The offset of the coset used by `coset_fft_in_place`.
+    fn coset_offset(&self) -> F {
+        self.domain.coset_offset() * &F::multiplicative_generator()
+    }
+
+    /// Evaluates `coeffs` over `offset * <omega>`.
+    fn forward<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>, offset: F) {
+        forward(coeffs, &self.twiddles, self.log_size, offset);
+    }
+
+    fn forward_batch<T: DomainCoeff<F>>(&self, vectors: &mut [Vec<T>], offset: F) {
+        let (twiddles, log_size) = (&self.twiddles, self.log_size);
+        cfg_iter_mut!(vectors).for_each(|v| forward(v, twiddles, log_size, offset));
+    }
+
+    /// Interpolates `evals` over `offset_inv^{-1} * <omega>`.
+    fn inverse<T: DomainCoeff<F>>(&self, evals: &mut Vec<T>, offset_inv: F) {
+        let size_inv = self.domain.size_as_field_element().inverse().unwrap();
+        inverse(
+            evals,
+            &self.inv_twiddles,
+            self.log_size,
+            size_inv,
+            offset_inv,
+        );
+    }
+
+    fn inverse_batch<T: DomainCoeff<F>>(&self, vectors: &mut [Vec<T>], offset_inv: F) {
+        let size_inv = self.domain.size_as_field_element().inverse().unwrap();
+        let (inv_twiddles, log_size) = (&self.inv_twiddles, self.log_size);
+        cfg_iter_mut!(vectors)
+            .for_each(|v| inverse(v, inv_twiddles, log_size, size_inv, offset_inv));
+    }
+}
+
+fn forward<T: DomainCoeff<F>, F: FftField>(
+    coeffs: &mut Vec<T>,
+    twiddles: &[F],
+    log_n: u32,
+    offset: F,
+) {
+    coeffs.resize(1 << log_n, T::zero());
+    if !offset.is_one() {
+        scale_by_powers(coeffs, F::one(), offset);
+    }
+    radix2_fft(coeffs, twiddles, log_n);
+}
+
+fn inverse<T: DomainCoeff<F>, F: FftField>(
+    evals: &mut Vec<T>,
+    inv_twiddles: &[F],
+    log_n: u32,
+    size_inv: F,
+    offset_inv: F,
+) {
+    evals.resize(1 << log_n, T::zero());
+    radix2_fft(evals, inv_twiddles, log_n);
+    scale_by_powers(evals, size_inv, offset_inv);
+}
+
+/// Returns `[1, g, ..., g^{len - 1}]`.
+fn powers<F: FftField>(g: F, len: usize) -> Vec<F> {
+    let mut result = vec![F::one(); len];
+    scale_by_powers(&mut result, F::one(), g);
+    result
+}
+
+/// Sets `a[i]` to `a[i] * c * g^i`.
+fn scale_by_powers<T: DomainCoeff<F>, F: FftField>(a: &mut [T], c: F, g: F) {
+    cfg_chunks_mut!(a, TASK_SIZE)
+        .enumerate()
+        .for_each(|(i, chunk)| {
+            let mut pow = c * &g.pow([(i * TASK_SIZE) as u64]);
+            chunk.iter_mut().for_each(|x| {
+                *x *= pow;
+                pow *= &g;
+            });
+        });
+}
+
+/// Computes an in-place FFT of `a`, which has size `2^log_n`, where
+/// `twiddles[i] = omega^i` for `0 <= i < 2^{log_n - 1}`.
+fn radix2_fft<T: DomainCoeff<F>, F: FftField>(a: &mut [T], twiddles: &[F], log_n: u32) {
+    let n = a.len();
+    assert_eq!(n, 1 << log_n);
+    assert_eq!(twiddles.len(), n / 2);
+
+    for i in 0..n {
+        let ri = bitreverse(i as u32, log_n) as usize;
+        if i < ri {
+            a.swap(i, ri);
+        }
+    }
+
+    // If the number of layers is odd, the first one is done on its own, which
+    // only involves `omega^0 = 1`.
+    let mut m = 1;
+    if log_n % 2 == 1 {
+        cfg_chunks_mut!(a, 2).for_each(|pair| {
+            let t = pair[1];
+            pair[1] = pair[0];
+            pair[1] -= t;
+            pair[0] += t;
+        });
+        m = 2;
+    }
+
+    // The layers whose butterflies stay within a block are done block by block.
+    let block_size = core::cmp::min(n, 1 << LOG_BLOCK_SIZE);
+    let mut block_m = m;
+    while 4 * block_m <= block_size {
+        block_m *= 4;
+    }
+    if block_m > m {
+        cfg_chunks_mut!(a, block_size).for_each(|block| {
+            let mut m = m;
+            while m < block_m {
+                block.chunks_mut(4 * m).for_each(|chunk| {
+                    let (a0, a1, a2, a3) = split_in_quarters(chunk);
+                    radix4_butterflies(a0, a1, a2, a3, 0, n / (4 * m), twiddles);
+                });
+                m *= 4;
+            }
+        });
+        m = block_m;
+    }
+
+    // The remaining layers are split across threads within each chunk.
+    while m < n {
+        let stride = n / (4 * m);
+        a.chunks_mut(4 * m).for_each(|chunk| {
+            let (a0, a1, a2, a3) = split_in_quarters(chunk);
+            cfg_chunks_mut!(a0, TASK_SIZE)
+                .zip(cfg_chunks_mut!(a1, TASK_SIZE))
+                .zip(cfg_chunks_mut!(a2, TASK_SIZE))
+                .zip(cfg_chunks_mut!(a3, TASK_SIZE))
+                .enumerate()
+                .for_each(|(i, (((a0, a1), a2), a3))| {
+                    radix4_butterflies(a0, a1, a2, a3, i * TASK_SIZE, stride, twiddles)
+                });
+        });
+        m *= 4;
+    }
+}
+
+fn split_in_quarters<T>(chunk: &mut [T]) -> (&mut [T], &mut [T], &mut [T], &mut [T]) {
+    let m = chunk.len() / 4;
+    let (a0, rest) = chunk.split_at_mut(m);
+    let (a1, rest) = rest.split_at_mut(m);
+    let (a2, a3) = rest.split_at_mut(m);
+    (a0, a1, a2, a3)
+}
+
+/// Performs the butterflies of two consecutive layers, with half-sizes `m`
+/// and `2m`, on the chunk `a0 || a1 || a2 || a3` of size `4m`, where
+/// `stride = n / 4m`. The slices may be subslices of the quarters of that
+/// chunk, starting at index `j_start`.
+///
+/// The first layer combines `(a0[j], a1[j])` and `(a2[j], a3[j])` with
+/// `omega_{2m}^j`, and the second layer combines `(a0[j], a2[j])` with
+/// `omega_{4m}^j` and `(a1[j], a3[j])` with `omega_{4m}^{j + m}`.
+fn radix4_butterflies<T: DomainCoeff<F>, F: FftField>(
+    a0: &mut [T],
+    a1: &mut [T],
+    a2: &mut [T],
+    a3: &mut [T],
+    j_start: usize,
+    stride: usize,
+    twiddles: &[F],
+) {
+    // `m` is recovered from the size of the domain, `n = 2 * twiddles.len()`.
+    let m = twiddles.len() / (2 * stride);
+    for (j, (((x0, x1), x2), x3)) in a0
+        .iter_mut()
+        .zip(a1.iter_mut())
+        .zip(a2.iter_mut())
+        .zip(a3.iter_mut())
+        .enumerate()
+    {
+        let j = j_start + j;
+        let w1 = twiddles[2 * j * stride];
+        let w2 = twiddles[j * stride];
+        let w3 = twiddles[(j + m) * stride];
+
+        let (mut y0, mut y1, mut y2, mut y3) = (*x0, *x0, *x2, *x2);
+        let mut t = *x1;
+        t *= w1;
+        y0 += t;
+        y1 -= t;
+        let mut t = *x3;
+        t *= w1;
+        y2 += t;
+        y3 -= t;
+
+        y2 *= w2;
+        y3 *= w3;
+        *x0 = y0;
+        *x0 += y2;
+        *x2 = y0;
+        *x2 -= y2;
+        *x1 = y1;
+        *x1 += y3;
+        *x3 = y1;
+        *x3 -= y3;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::*;
+    use algebra::{
+        bls12_381::{Fr, G1Projective},
+        mnt6_753::Fr as MNT6Fr,
+    };
+    use algebra_core::{test_rng, FftField, UniformRand, Zero};
+    use core::fmt;
+
+    fn check_plan<F: FftField, T: DomainCoeff<F> + UniformRand + fmt::Debug + Eq>(
+        domain: impl EvaluationDomain<F>,
+    ) {
+        let rng = &mut test_rng();
+        let plan = FFTPlan::new(domain);
+        let v = (0..domain.size()).map(|_| T::rand(rng)).collect::<Vec<_>>();
+
+        assert_eq!(plan.fft(&v), domain.fft(&v));
+        assert_eq!(plan.ifft(&v), domain.ifft(&v));
+        assert_eq!(plan.ifft(&plan.fft(&v)), v);
+
+        let mut expected = v.clone();
+        domain.coset_fft_in_place(&mut expected);
+        let mut result = v.clone();
+        plan.coset_fft_in_place(&mut result);
+        assert_eq!(result, expected);
+
+        domain.coset_ifft_in_place(&mut expected);
+        plan.coset_ifft_in_place(&mut result);
+        assert_eq!(result, expected);
+        assert_eq!(result, v);
+    }
+
+    #[test]
+    fn plan_agrees_with_domain() {
+        let rng = &mut test_rng();
+        // Sizes below and above the block size, with both parities of `log_n`.
+        for log_n in 0..13 {
+            let domain = Radix2EvaluationDomain::<Fr>::new(1 << log_n).unwrap();
+            check_plan::<Fr, Fr>(domain);
+            check_plan::<Fr, Fr>(domain.get_coset(Fr::rand(rng)).unwrap());
+        }
+        for log_n in 0..6 {
+            let domain = GeneralEvaluationDomain::<Fr>::new(1 << log_n).unwrap();
+            check_plan::<Fr, G1Projective>(domain);
+        }
+    }
+
+    #[test]
+    fn mixed_radix_plan() {
+        // A mixed-radix domain of size 2^3 * 5 falls back to its own FFT,
+        // while one of size 2^4 uses the plan.
+        for &size in &[40, 16] {
+            let domain = MixedRadixEvaluationDomain::<MNT6Fr>::new(size).unwrap();
+            assert_eq!(domain.size(), size);
+            check_plan::<MNT6Fr, MNT6Fr>(domain);
+        }
+    }
+
+    #[test]
+    fn batch_ffts() {
+        let rng = &mut test_rng();
+        let domain = GeneralEvaluationDomain::<Fr>::new(1 << 8).unwrap();
+        let plan = FFTPlan::new(domain);
+        let vectors = (0..4)
+            .map(|i| (0..i * 80).map(|_| Fr::rand(rng)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut batch = vectors.clone();
+        plan.fft_batch_in_place(&mut batch);
+        for (v, result) in vectors.iter().zip(&batch) {
+            assert_eq!(result, &domain.fft(v));
+        }
+        plan.ifft_batch_in_place(&mut batch);
+        plan.coset_fft_batch_in_place(&mut batch);
+        for (v, result) in vectors.iter().zip(&batch) {
+            assert_eq!(result, &domain.coset_fft(v));
+        }
+        plan.coset_ifft_batch_in_place(&mut batch);
+        for (v, result) in vectors.iter().zip(&batch) {
+            let mut v = v.clone();
+            v.resize(domain.size(), Fr::zero());
+            assert_eq!(result, &v);
+        }
+    }
+}
@@ -9,14 +9,19 @@ use crate::domain::{
     DomainCoeff, EvaluationDomain,
 };
 use crate::Vec;
-use algebra_core::{FftField, FftParameters};
+use algebra_core::{
+    bytes::ToBytes,
+    io::{Read, Result as IoResult, Write},
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+    FftField, FftParameters,
+};
 use core::fmt;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
 /// Defines a domain over which finite field (I)FFTs can be performed. Works
 /// only for fields that have a large multiplicative subgroup of size that is
-/// a power-of-2.
+/// a power-of-2. The domain is either such a subgroup or a coset of it.
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
 pub struct Radix2EvaluationDomain<F: FftField> {
     /// The size of the domain.
@@ -33,11 +38,25 @@ pub struct Radix2EvaluationDomain<F: FftField> {
     pub group_gen_inv: F,
     /// Multiplicative generator of the finite field.
     pub generator_inv: F,
+    /// Offset of the coset, which is one for the subgroup itself.
+    pub offset: F,
+    /// Inverse of the offset.
+    pub offset_inv: F,
+    /// `offset^size`.
+    pub offset_pow_size: F,
 }
 
 impl<F: FftField> fmt::Debug for Radix2EvaluationDomain<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Radix-2 multiplicative subgroup of size {}", self.size)
+        if self.offset.is_one() {
+            write!(f, "Radix-2 multiplicative subgroup of size {}", self.size)
+        } else {
+            write!(
+                f,
+                "Radix-2 multiplicative coset of size {} with offset {}",
+                self.size, self.offset
+            )
+        }
     }
 }
 
@@ -72,6 +91,36 @@ impl<F: FftField> EvaluationDomain<F> for Radix2EvaluationDomain<F> {
             group_gen,
             group_gen_inv: group_gen.inverse()?,
             generator_inv: F::multiplicative_generator().inverse()?,
+            offset: F::one(),
+            offset_inv: F::one(),
+            offset_pow_size: F::one(),
+        })
+    }
+
+    fn get_coset(&self, offset: F) -> Option<Self> {
+        Some(Radix2EvaluationDomain {
+            offset,
+            offset_inv: offset.inverse()?,
+            offset_pow_size: offset.pow([self.size]),
+            ..*self
+        })
+    }
+
+    fn get_subdomain(&self, k: usize) -> Option<Self> {
+        if k == 0 || self.size() % k != 0 {
+            return None;
+        }
+        let size = self.size / k as u64;
+        let size_as_field_element = F::from(size);
+        Some(Radix2EvaluationDomain {
+            size,
+            log_size_of_group: size.trailing_zeros(),
+            size_as_field_element,
+            size_inv: size_as_field_element.inverse()?,
+            group_gen: self.group_gen.pow([k as u64]),
+            group_gen_inv: self.group_gen_inv.pow([k as u64]),
+            offset_pow_size: self.offset.pow([size]),
+            ..*self
         })
     }
 
@@ -89,8 +138,36 @@ impl<F: FftField> EvaluationDomain<F> for Radix2EvaluationDomain<F> {
         self.size as usize
     }
 
+    #[inline]
+    fn group_gen(&self) -> F {
+        self.group_gen
+    }
+
+    #[inline]
+    fn group_gen_inv(&self) -> F {
+        self.group_gen_inv
+    }
+
+    #[inline]
+    fn coset_offset(&self) -> F {
+        self.offset
+    }
+
+    #[inline]
+    fn coset_offset_inv(&self) -> F {
+        self.offset_inv
+    }
+
+    #[inline]
+    fn coset_offset_pow_size(&self) -> F {
+        self.offset_pow_size
+    }
+
     #[inline]
     fn fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>) {
+        if !self.offset.is_one() {
+            Self::distribute_powers(coeffs, self.offset);
+        }
         coeffs.resize(self.size(), T::zero());
         best_fft(
             coeffs,
@@ -109,7 +186,11 @@ impl<F: FftField> EvaluationDomain<F> for Radix2EvaluationDomain<F> {
             self.log_size_of_group,
             serial_radix2_fft::<T, F>,
         );
-        cfg_iter_mut!(evals).for_each(|val| *val *= self.size_inv);
+        if self.offset.is_one() {
+            cfg_iter_mut!(evals).for_each(|val| *val *= self.size_inv);
+        } else {
+            Self::distribute_powers_and_mul_by_const(evals, self.offset_inv, self.size_inv);
+        }
     }
 
     #[inline]
@@ -119,6 +200,9 @@ impl<F: FftField> EvaluationDomain<F> for Radix2EvaluationDomain<F> {
     }
 
     fn evaluate_all_lagrange_coefficients(&self, tau: F) -> Vec<F> {
+        // The Lagrange polynomials of `offset * H` at `tau` are those of `H` at
+        // `tau / offset`.
+        let tau = tau * &self.offset_inv;
         // Evaluate all Lagrange polynomials
         let size = self.size as usize;
         let t_size = tau.pow(&[self.size]);
@@ -159,21 +243,21 @@ impl<F: FftField> EvaluationDomain<F> for Radix2EvaluationDomain<F> {
     }
 
     fn vanishing_polynomial(&self) -> crate::SparsePolynomial<F> {
-        let coeffs = vec![(0, -F::one()), (self.size(), F::one())];
+        let coeffs = vec![(0, -self.offset_pow_size), (self.size(), F::one())];
         crate::SparsePolynomial::from_coefficients_vec(coeffs)
     }
 
     /// This evaluates the vanishing polynomial for this domain at tau.
     /// For multiplicative subgroups, this polynomial is `z(X) = X^self.size -
-    /// 1`.
+    /// 1`, and for their cosets it is `z(X) = X^self.size - offset^self.size`.
     fn evaluate_vanishing_polynomial(&self, tau: F) -> F {
-        tau.pow(&[self.size]) - &F::one()
+        tau.pow(&[self.size]) - &self.offset_pow_size
     }
 
     /// Return an iterator over the elements of the domain.
     fn elements(&self) -> Elements<F> {
         Elements {
-            cur_elem: F::one(),
+            cur_elem: self.offset,
             cur_pow: 0,
             size: self.size,
             group_gen: self.group_gen,
@@ -181,6 +265,45 @@ impl<F: FftField> EvaluationDomain<F> for Radix2EvaluationDomain<F> {
     }
 }
 
+impl<F: FftField> ToBytes for Radix2EvaluationDomain<F> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.size.write(&mut writer)?;
+        self.offset.write(&mut writer)
+    }
+}
+
+/// A domain is serialized as its size and offset, from which everything else
+/// is recomputed on deserialization.
+impl<F: FftField> CanonicalSerialize for Radix2EvaluationDomain<F> {
+    #[inline]
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.size.serialize(&mut writer)?;
+        self.offset.serialize(&mut writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.size.serialized_size() + self.offset.serialized_size()
+    }
+}
+
+impl<F: FftField> CanonicalDeserialize for Radix2EvaluationDomain<F> {
+    /// Fails unless the field has a radix-2 subgroup of exactly the serialized
+    /// size and the offset is nonzero.
+    #[inline]
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let size = u64::deserialize(&mut reader)?;
+        let offset = F::deserialize(&mut reader)?;
+        if !size.is_power_of_two() {
+            return Err(SerializationError::InvalidData);
+        }
+        Self::new(size as usize)
+            .and_then(|domain| domain.get_coset(offset))
+            .ok_or(SerializationError::InvalidData)
+    }
+}
+
 pub(crate) fn serial_radix2_fft<T: DomainCoeff<F>, F: FftField>(a: &mut [T], omega: F, log_n: u32) {
     let n = a.len() as u32;
     assert_eq!(n, 1 << log_n);
@@ -307,8 +430,35 @@ mod tests {
             }
         }
 
+        fn test_group_consistency<E: PairingEngine, R: Rng>(rng: &mut R, max_coeffs: u32) {
+            for log_d in 0..max_coeffs {
+                let d = 1 << log_d;
+
+                let mut v1 = (0..d)
+                    .map(|_| E::G1Projective::rand(rng))
+                    .collect::<Vec<_>>();
+                let mut v2 = v1.clone();
+
+                let domain = Radix2EvaluationDomain::<E::Fr>::new(v1.len()).unwrap();
+
+                for log_cpus in log_d..min(log_d + 1, 3) {
+                    parallel_fft::<E::G1Projective, E::Fr>(
+                        &mut v1,
+                        domain.group_gen,
+                        log_d,
+                        log_cpus,
+                        serial_radix2_fft::<E::G1Projective, E::Fr>,
+                    );
+                    serial_radix2_fft::<E::G1Projective, E::Fr>(&mut v2, domain.group_gen, log_d);
+
+                    assert_eq!(v1, v2);
+                }
+            }
+        }
+
         let rng = &mut test_rng();
 
         test_consistency::<Bls12_381, _>(rng, 10);
+        test_group_consistency::<Bls12_381, _>(rng, 6);
     }
 }
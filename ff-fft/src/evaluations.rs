@@ -1,7 +1,12 @@
 //! A polynomial represented in evaluations form.
 
 use crate::{DensePolynomial, EvaluationDomain, GeneralEvaluationDomain, Vec};
-use algebra_core::FftField;
+use algebra_core::{
+    bytes::ToBytes,
+    io::{Read, Result as IoResult, Write},
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+    FftField,
+};
 use core::ops::{Add, AddAssign, Div, DivAssign, Index, Mul, MulAssign, Sub, SubAssign};
 
 /// Stores a polynomial in evaluation form.
@@ -19,6 +24,23 @@ impl<F: FftField, D: EvaluationDomain<F>> Evaluations<F, D> {
         Self { evals, domain }
     }
 
+    /// Return the domain, possibly a coset, over which `self` is given.
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    /// Return the evaluations over `self.domain().get_subdomain(k)`, i.e.
+    /// every `k`-th evaluation. Panics if `k` does not divide the size of the
+    /// domain.
+    pub fn restrict_to_subdomain(&self, k: usize) -> Self {
+        let domain = self
+            .domain
+            .get_subdomain(k)
+            .expect("k must divide the domain size");
+        let evals = self.evals.iter().step_by(k).copied().collect();
+        Self { evals, domain }
+    }
+
     /// Interpolate a polynomial from a list of evaluations
     pub fn interpolate_by_ref(&self) -> DensePolynomial<F> {
         DensePolynomial::from_coefficients_vec(self.domain.ifft(&self.evals))
@@ -40,6 +62,44 @@ impl<F: FftField, D: EvaluationDomain<F>> Index<usize> for Evaluations<F, D> {
     }
 }
 
+impl<F: FftField, D: EvaluationDomain<F> + ToBytes> ToBytes for Evaluations<F, D> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.evals.write(&mut writer)?;
+        self.domain.write(&mut writer)
+    }
+}
+
+impl<F: FftField, D: EvaluationDomain<F> + CanonicalSerialize> CanonicalSerialize
+    for Evaluations<F, D>
+{
+    #[inline]
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.evals.serialize(&mut writer)?;
+        self.domain.serialize(&mut writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.evals.serialized_size() + self.domain.serialized_size()
+    }
+}
+
+impl<F: FftField, D: EvaluationDomain<F> + CanonicalDeserialize> CanonicalDeserialize
+    for Evaluations<F, D>
+{
+    /// Fails unless there is exactly one evaluation per element of the domain.
+    #[inline]
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let evals = Vec::<F>::deserialize(&mut reader)?;
+        let domain = D::deserialize(&mut reader)?;
+        if evals.len() != domain.size() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(Self { evals, domain })
+    }
+}
+
 impl<'a, 'b, F: FftField, D: EvaluationDomain<F>> Mul<&'a Evaluations<F, D>>
     for &'b Evaluations<F, D>
 {
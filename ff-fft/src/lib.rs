@@ -14,10 +14,10 @@
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-pub(crate) use alloc::{borrow::Cow, collections::BTreeMap, vec::Vec};
+pub(crate) use alloc::{borrow::Cow, boxed::Box, collections::BTreeMap, vec::Vec};
 
 #[cfg(feature = "std")]
-pub(crate) use std::{borrow::Cow, collections::BTreeMap, vec::Vec};
+pub(crate) use std::{borrow::Cow, boxed::Box, collections::BTreeMap, vec::Vec};
 
 /// Creates parallel iterator over refs if `parallel` feature is enabled.
 #[macro_export]
@@ -97,10 +97,14 @@ pub mod evaluations;
 pub mod polynomial;
 
 pub use domain::{
-    EvaluationDomain, GeneralEvaluationDomain, MixedRadixEvaluationDomain, Radix2EvaluationDomain,
+    ECFFTDomain, ECFFTParameters, EvaluationDomain, FFTPlan, GeneralEvaluationDomain,
+    MixedRadixEvaluationDomain, Radix2EvaluationDomain,
 };
 pub use evaluations::Evaluations;
-pub use polynomial::{DenseOrSparsePolynomial, DensePolynomial, SparsePolynomial};
+pub use polynomial::{
+    DenseMultilinearExtension, DenseOrSparsePolynomial, DensePolynomial,
+    SparseMultivariatePolynomial, SparsePolynomial, SparseTerm, SubproductTree,
+};
 
 #[cfg(test)]
 mod test;
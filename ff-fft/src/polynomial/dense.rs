@@ -3,11 +3,16 @@
 use crate::{GeneralEvaluationDomain, Vec};
 use core::{
     fmt,
-    ops::{Add, AddAssign, Deref, DerefMut, Div, Mul, Neg, Sub, SubAssign},
+    ops::{Add, AddAssign, Deref, DerefMut, Div, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 
-use crate::{DenseOrSparsePolynomial, EvaluationDomain, Evaluations};
-use algebra_core::{FftField, Field};
+use crate::{DenseOrSparsePolynomial, ECFFTDomain, EvaluationDomain, Evaluations, SubproductTree};
+use algebra_core::{
+    bytes::ToBytes,
+    io::{Read, Result as IoResult, Write},
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+    FftField, Field,
+};
 use rand::Rng;
 
 #[cfg(feature = "parallel")]
@@ -126,6 +131,61 @@ impl<F: Field> DensePolynomial<F> {
         }
     }
 
+    /// Multiply `self` by `other` with Karatsuba's algorithm, which costs
+    /// `O(n^1.59)` field operations and works over any field. Over
+    /// FFT-friendly fields, the `Mul` implementation is faster for large
+    /// inputs.
+    pub fn karatsuba_mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            DensePolynomial::zero()
+        } else {
+            DensePolynomial::from_coefficients_vec(karatsuba_mul(&self.coeffs, &other.coeffs))
+        }
+    }
+
+    /// Multiply `self` by `other` with ECFFTs over `domain`, which works over
+    /// fields without large smooth multiplicative subgroups. Returns `None` if
+    /// `domain` is too small for the product.
+    pub fn mul_with_ecfft(&self, other: &Self, domain: &ECFFTDomain<F>) -> Option<Self> {
+        if self.is_zero() || other.is_zero() {
+            Some(DensePolynomial::zero())
+        } else {
+            domain
+                .mul(&self.coeffs, &other.coeffs)
+                .map(Self::from_coefficients_vec)
+        }
+    }
+
+    /// Returns the composition `self(other(X))`, computed with Horner's rule.
+    pub fn compose(&self, other: &Self) -> Self {
+        let mut result = DensePolynomial::zero();
+        for coeff in self.coeffs.iter().rev() {
+            result = result.karatsuba_mul(other);
+            if result.coeffs.is_empty() {
+                result.coeffs.push(*coeff);
+            } else {
+                result.coeffs[0] += coeff;
+            }
+            result.truncate_leading_zeros();
+        }
+        result
+    }
+
+    /// Returns the formal derivative of `self`.
+    pub fn derivative(&self) -> Self {
+        let mut index = F::zero();
+        let coeffs = self
+            .coeffs
+            .iter()
+            .skip(1)
+            .map(|coeff| {
+                index += &F::one();
+                *coeff * &index
+            })
+            .collect();
+        DensePolynomial::from_coefficients_vec(coeffs)
+    }
+
     /// Outputs a polynomial of degree `d` where each coefficient is sampled
     /// uniformly at random from the field `F`.
     pub fn rand<R: Rng>(d: usize, rng: &mut R) -> Self {
@@ -137,15 +197,116 @@ impl<F: Field> DensePolynomial<F> {
     }
 }
 
+/// Below this degree, division falls back to schoolbook long division, which
+/// is faster than Newton iteration for small inputs.
+const FAST_DIVISION_THRESHOLD: usize = 64;
+
 impl<F: FftField> DensePolynomial<F> {
+    /// Divide `self` by `divisor`, and return the quotient and remainder.
+    ///
+    /// This reduces division to multiplication by the inverse of the reversed
+    /// divisor modulo `X^{deg(self) - deg(divisor) + 1}`, which is computed
+    /// via Newton iteration. The total cost is `O(n log n)` field operations,
+    /// where `n` is the degree of `self`.
+    pub fn fast_divide_with_q_and_r(
+        &self,
+        divisor: &Self,
+    ) -> Option<(DensePolynomial<F>, DensePolynomial<F>)> {
+        if self.is_zero() {
+            return Some((DensePolynomial::zero(), DensePolynomial::zero()));
+        } else if divisor.is_zero() {
+            panic!("Dividing by zero polynomial")
+        } else if self.degree() < divisor.degree() {
+            return Some((DensePolynomial::zero(), self.clone()));
+        }
+
+        let quotient_len = self.degree() - divisor.degree() + 1;
+        if divisor.degree() < FAST_DIVISION_THRESHOLD || quotient_len < FAST_DIVISION_THRESHOLD {
+            let dividend: DenseOrSparsePolynomial<F> = self.into();
+            return dividend.divide_with_q_and_r(&divisor.into());
+        }
+
+        // If `a = b * q + r`, then `rev(a) = rev(b) * rev(q) + X^{n - m + 1} * rev(r)`,
+        // and hence `rev(q) = rev(a) * rev(b)^{-1} mod X^{n - m + 1}`.
+        let rev_divisor =
+            DensePolynomial::from_coefficients_vec(divisor.coeffs.iter().rev().cloned().collect());
+        let rev_divisor_inv = rev_divisor.inverse_mod_x_n(quotient_len)?;
+        let rev_dividend = DensePolynomial::from_coefficients_vec(
+            self.coeffs
+                .iter()
+                .rev()
+                .take(quotient_len)
+                .cloned()
+                .collect(),
+        );
+        let mut rev_quotient = (&rev_dividend * &rev_divisor_inv).coeffs;
+        rev_quotient.resize(quotient_len, F::zero());
+        rev_quotient.reverse();
+
+        let quotient = DensePolynomial::from_coefficients_vec(rev_quotient);
+        let remainder = self - &(divisor * &quotient);
+        Some((quotient, remainder))
+    }
+
+    /// Returns the inverse of `self` modulo `X^n`, or `None` if the constant
+    /// term of `self` is zero.
+    ///
+    /// Uses Newton iteration `g <- g * (2 - self * g)`, which doubles the
+    /// number of correct coefficients in each step.
+    pub fn inverse_mod_x_n(&self, n: usize) -> Option<DensePolynomial<F>> {
+        let mut inverse =
+            DensePolynomial::from_coefficients_vec(vec![self.coeffs.first()?.inverse()?]);
+        let mut precision = 1;
+        while precision < n {
+            precision = core::cmp::min(2 * precision, n);
+            let truncated = DensePolynomial::from_coefficients_slice(
+                &self.coeffs[..core::cmp::min(precision, self.coeffs.len())],
+            );
+            let mut correction = &truncated * &inverse;
+            correction.coeffs.truncate(precision);
+            correction.coeffs.iter_mut().for_each(|c| *c = -*c);
+            correction.coeffs[0] += &F::one().double();
+            inverse = &inverse * &correction;
+            inverse.coeffs.truncate(precision);
+            inverse.truncate_leading_zeros();
+        }
+        Some(inverse)
+    }
+
+    /// Evaluates `self` at each of the given `points`.
+    ///
+    /// For many points this uses a subproduct tree, for a total cost of
+    /// `O(n log^2 n)` field operations.
+    pub fn evaluate_at_points(&self, points: &[F]) -> Vec<F> {
+        if points.len() <= FAST_DIVISION_THRESHOLD {
+            points.iter().map(|point| self.evaluate(*point)).collect()
+        } else {
+            SubproductTree::new(points).evaluate(self)
+        }
+    }
+
+    /// Returns the unique polynomial of degree less than `points.len()` that
+    /// evaluates to `evals[i]` at `points[i]`, or `None` if the points are
+    /// not distinct.
+    ///
+    /// This uses a subproduct tree, for a total cost of `O(n log^2 n)` field
+    /// operations.
+    pub fn interpolate(points: &[F], evals: &[F]) -> Option<DensePolynomial<F>> {
+        if points.is_empty() {
+            return Some(DensePolynomial::zero());
+        }
+        SubproductTree::new(points).interpolate(evals)
+    }
+
     /// Multiply `self` by the vanishing polynomial for the domain `domain`.
     /// Returns the result of the multiplication.
     pub fn mul_by_vanishing_poly<D: EvaluationDomain<F>>(&self, domain: D) -> DensePolynomial<F> {
         let mut shifted = vec![F::zero(); domain.size()];
         shifted.extend_from_slice(&self.coeffs);
+        let offset_pow_size = domain.coset_offset_pow_size();
         cfg_iter_mut!(shifted)
             .zip(&self.coeffs)
-            .for_each(|(s, c)| *s -= c);
+            .for_each(|(s, c)| *s -= &(*c * &offset_pow_size));
         DensePolynomial::from_coefficients_vec(shifted)
     }
 
@@ -346,12 +507,128 @@ impl<'a, 'b, F: FftField> Mul<&'a DensePolynomial<F>> for &'b DensePolynomial<F>
     }
 }
 
+/// Below this number of coefficients, Karatsuba multiplication falls back to
+/// schoolbook multiplication.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Returns the coefficients of the product of the non-empty coefficient
+/// vectors `a` and `b`.
+fn karatsuba_mul<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    let mut result = vec![F::zero(); a.len() + b.len() - 1];
+    if a.len() < KARATSUBA_THRESHOLD || b.len() < KARATSUBA_THRESHOLD {
+        for (i, a_coeff) in a.iter().enumerate() {
+            for (j, b_coeff) in b.iter().enumerate() {
+                result[i + j] += &(*a_coeff * b_coeff);
+            }
+        }
+        return result;
+    }
+
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let half = long.len() / 2;
+    if short.len() <= half {
+        // Unbalanced inputs: multiply `short` by chunks of `long` of the same
+        // length instead.
+        for (i, chunk) in long.chunks(short.len()).enumerate() {
+            add_assign_at(&mut result, i * short.len(), &karatsuba_mul(short, chunk));
+        }
+        return result;
+    }
+
+    // With `a = a_0 + X^h a_1` and `b = b_0 + X^h b_1`, the middle term
+    // `a_0 b_1 + a_1 b_0` equals `(a_0 + a_1)(b_0 + b_1) - a_0 b_0 - a_1 b_1`.
+    let (a_0, a_1) = a.split_at(half);
+    let (b_0, b_1) = b.split_at(half);
+    let low = karatsuba_mul(a_0, b_0);
+    let high = karatsuba_mul(a_1, b_1);
+    let mut middle = karatsuba_mul(&add_coeffs(a_0, a_1), &add_coeffs(b_0, b_1));
+    middle.iter_mut().zip(&low).for_each(|(m, l)| *m -= l);
+    middle.iter_mut().zip(&high).for_each(|(m, h)| *m -= h);
+    add_assign_at(&mut result, 0, &low);
+    add_assign_at(&mut result, half, &middle);
+    add_assign_at(&mut result, 2 * half, &high);
+    result
+}
+
+fn add_coeffs<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let mut result = long.to_vec();
+    result.iter_mut().zip(short).for_each(|(r, s)| *r += s);
+    result
+}
+
+/// Adds `other` to `result`, shifted by `shift` coefficients. Coefficients of
+/// `other` that do not fit in `result` must be zero.
+fn add_assign_at<F: Field>(result: &mut [F], shift: usize, other: &[F]) {
+    result[shift..]
+        .iter_mut()
+        .zip(other)
+        .for_each(|(r, o)| *r += o);
+}
+
+impl<'a, F: Field> Mul<F> for &'a DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+
+    #[inline]
+    fn mul(self, elem: F) -> DensePolynomial<F> {
+        let mut result = self.clone();
+        result *= elem;
+        result
+    }
+}
+
+impl<F: Field> MulAssign<F> for DensePolynomial<F> {
+    #[inline]
+    fn mul_assign(&mut self, elem: F) {
+        if elem.is_zero() {
+            self.coeffs.clear();
+        } else {
+            cfg_iter_mut!(self.coeffs).for_each(|c| *c *= &elem);
+        }
+    }
+}
+
+impl<F: Field> ToBytes for DensePolynomial<F> {
+    #[inline]
+    fn write<W: Write>(&self, writer: W) -> IoResult<()> {
+        self.coeffs.write(writer)
+    }
+}
+
+impl<F: Field> CanonicalSerialize for DensePolynomial<F> {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.coeffs.serialize(writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.coeffs.serialized_size()
+    }
+}
+
+impl<F: Field> CanonicalDeserialize for DensePolynomial<F> {
+    /// Fails if the leading coefficient is zero, since such a coefficient
+    /// vector is not the canonical representation of any polynomial.
+    #[inline]
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        let coeffs = Vec::<F>::deserialize(reader)?;
+        if coeffs.last().map_or(false, |c| c.is_zero()) {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(Self { coeffs })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::polynomial::*;
     use crate::{EvaluationDomain, GeneralEvaluationDomain};
-    use algebra::bls12_381::fr::Fr;
-    use algebra_core::{test_rng, Field, One, UniformRand, Zero};
+    use algebra::bls12_381::{fq2::Fq2, fr::Fr};
+    use algebra_core::{
+        test_rng, CanonicalDeserialize, CanonicalSerialize, Field, One, SerializationError,
+        UniformRand, Zero,
+    };
 
     #[test]
     fn double_polynomials_random() {
@@ -448,6 +725,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fast_divide_polynomials_random() {
+        let rng = &mut test_rng();
+
+        for a_degree in [0, 10, 63, 64, 100, 257, 600].iter() {
+            for b_degree in [0, 1, 40, 64, 65, 200, 511].iter() {
+                let dividend = DensePolynomial::<Fr>::rand(*a_degree, rng);
+                let divisor = DensePolynomial::<Fr>::rand(*b_degree, rng);
+                let (quotient, remainder) = dividend.fast_divide_with_q_and_r(&divisor).unwrap();
+                let expected = DenseOrSparsePolynomial::divide_with_q_and_r(
+                    &(&dividend).into(),
+                    &(&divisor).into(),
+                )
+                .unwrap();
+                assert_eq!((quotient, remainder), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_mod_x_n_random() {
+        let rng = &mut test_rng();
+
+        for degree in [0, 1, 5, 100].iter() {
+            let p = DensePolynomial::<Fr>::rand(*degree, rng);
+            for n in [1, 2, 3, 64, 127, 300].iter() {
+                let inverse = p.inverse_mod_x_n(*n).unwrap();
+                let mut product = (&p * &inverse).coeffs;
+                product.resize(*n, Fr::zero());
+                let mut expected = vec![Fr::zero(); *n];
+                expected[0] = Fr::one();
+                assert_eq!(product, expected);
+            }
+        }
+        let p = DensePolynomial::from_coefficients_slice(&[Fr::zero(), Fr::one()]);
+        assert!(p.inverse_mod_x_n(4).is_none());
+    }
+
     #[test]
     fn evaluate_polynomials() {
         let rng = &mut test_rng();
@@ -469,11 +784,116 @@ mod tests {
             for b_degree in 0..70 {
                 let a = DensePolynomial::<Fr>::rand(a_degree, rng);
                 let b = DensePolynomial::<Fr>::rand(b_degree, rng);
-                assert_eq!(&a * &b, a.naive_mul(&b))
+                assert_eq!(&a * &b, a.naive_mul(&b));
+                assert_eq!(a.karatsuba_mul(&b), a.naive_mul(&b));
             }
         }
     }
 
+    #[test]
+    fn mul_polynomials_large() {
+        let rng = &mut test_rng();
+        let degrees = [0, 1, 31, 32, 100, 257, 600];
+        for a_degree in degrees.iter() {
+            for b_degree in degrees.iter() {
+                let a = DensePolynomial::<Fr>::rand(*a_degree, rng);
+                let b = DensePolynomial::<Fr>::rand(*b_degree, rng);
+                let expected = a.naive_mul(&b);
+                assert_eq!(&a * &b, expected);
+                assert_eq!(a.karatsuba_mul(&b), expected);
+
+                // `Fq2` has no FFT-friendly subgroups.
+                let a = DensePolynomial::<Fq2>::rand(*a_degree, rng);
+                let b = DensePolynomial::<Fq2>::rand(*b_degree, rng);
+                assert_eq!(a.karatsuba_mul(&b), a.naive_mul(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn mul_polynomials_by_scalar() {
+        let rng = &mut test_rng();
+        for degree in 0..10 {
+            let p = DensePolynomial::<Fr>::rand(degree, rng);
+            let f = Fr::rand(rng);
+            let expected =
+                DensePolynomial::from_coefficients_vec(p.coeffs.iter().map(|c| f * c).collect());
+            assert_eq!(&p * f, expected);
+            let mut q = p.clone();
+            q *= f;
+            assert_eq!(q, expected);
+            assert!((&p * Fr::zero()).coeffs.is_empty());
+        }
+    }
+
+    #[test]
+    fn compose_polynomials() {
+        let rng = &mut test_rng();
+        for p_degree in 0..8 {
+            for q_degree in 0..8 {
+                let p = DensePolynomial::<Fr>::rand(p_degree, rng);
+                let q = DensePolynomial::<Fr>::rand(q_degree, rng);
+                let composition = p.compose(&q);
+                assert_eq!(composition.degree(), p_degree * q_degree);
+                let point = Fr::rand(rng);
+                assert_eq!(composition.evaluate(point), p.evaluate(q.evaluate(point)));
+            }
+        }
+        let p = DensePolynomial::<Fr>::rand(5, rng);
+        let constant = DensePolynomial::from_coefficients_slice(&p.coeffs[..1]);
+        assert_eq!(p.compose(&DensePolynomial::zero()), constant);
+        assert!(DensePolynomial::zero().compose(&p).is_zero());
+    }
+
+    #[test]
+    fn derivative_of_polynomials() {
+        let rng = &mut test_rng();
+        let x_cubed = DensePolynomial::from_coefficients_vec(vec![
+            Fr::zero(),
+            Fr::zero(),
+            Fr::zero(),
+            Fr::one(),
+        ]);
+        let expected =
+            DensePolynomial::from_coefficients_vec(vec![Fr::zero(), Fr::zero(), Fr::from(3u64)]);
+        assert_eq!(x_cubed.derivative(), expected);
+        assert!(DensePolynomial::<Fr>::rand(0, rng).derivative().is_zero());
+
+        for degree in 0..10 {
+            let p = DensePolynomial::<Fr>::rand(degree, rng);
+            let q = DensePolynomial::<Fr>::rand(degree + 3, rng);
+            assert_eq!(
+                (&p * &q).derivative(),
+                &(&p.derivative() * &q) + &(&p * &q.derivative())
+            );
+        }
+    }
+
+    #[test]
+    fn serialize_polynomials() {
+        let rng = &mut test_rng();
+        for degree in 0..10 {
+            let p = DensePolynomial::<Fr>::rand(degree, rng);
+            let mut bytes = Vec::new();
+            p.serialize(&mut bytes).unwrap();
+            assert_eq!(bytes.len(), p.serialized_size());
+            assert_eq!(DensePolynomial::deserialize(&bytes[..]).unwrap(), p);
+        }
+        let mut bytes = Vec::new();
+        DensePolynomial::<Fr>::zero().serialize(&mut bytes).unwrap();
+        assert!(DensePolynomial::<Fr>::deserialize(&bytes[..])
+            .unwrap()
+            .is_zero());
+
+        // A vanishing leading coefficient is not canonical.
+        let mut bytes = Vec::new();
+        vec![Fr::one(), Fr::zero()].serialize(&mut bytes).unwrap();
+        match DensePolynomial::<Fr>::deserialize(&bytes[..]) {
+            Err(SerializationError::InvalidData) => {},
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
     #[test]
     fn mul_by_vanishing_poly() {
         let rng = &mut test_rng();
@@ -484,6 +904,11 @@ mod tests {
                 let ans1 = p.mul_by_vanishing_poly(domain);
                 let ans2 = &p * &domain.vanishing_polynomial().into();
                 assert_eq!(ans1, ans2);
+
+                let coset = domain.get_coset(Fr::rand(rng)).unwrap();
+                let ans1 = p.mul_by_vanishing_poly(coset);
+                let ans2 = &p * &coset.vanishing_polynomial().into();
+                assert_eq!(ans1, ans2);
             }
         }
     }
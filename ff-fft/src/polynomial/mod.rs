@@ -6,10 +6,16 @@ use core::convert::TryInto;
 use DenseOrSparsePolynomial::*;
 
 mod dense;
+mod multilinear;
+mod multivariate;
 mod sparse;
+mod subproduct_tree;
 
 pub use dense::DensePolynomial;
+pub use multilinear::DenseMultilinearExtension;
+pub use multivariate::{SparseMultivariatePolynomial, SparseTerm};
 pub use sparse::SparsePolynomial;
+pub use subproduct_tree::SubproductTree;
 
 /// Represents either a sparse polynomial or a dense one.
 #[derive(Clone)]
@@ -0,0 +1,352 @@
+//! A multilinear polynomial represented by its evaluations over the boolean
+//! hypercube.
+
+use crate::{SparseMultivariatePolynomial, SparseTerm, Vec};
+use algebra_core::Field;
+use core::{
+    fmt,
+    ops::{Add, AddAssign, Index, Mul, Neg, Sub, SubAssign},
+};
+use rand::Rng;
+
+/// Stores a multilinear polynomial in `num_vars` variables by its
+/// evaluations over `{0, 1}^num_vars`.
+///
+/// The evaluation at the point `(b_0, ..., b_{n - 1})` is stored at index
+/// `\sum_i b_i 2^i` of `self.evaluations`, so that the first variable
+/// corresponds to the least significant bit of the index.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct DenseMultilinearExtension<F: Field> {
+    /// The evaluations of the polynomial over the boolean hypercube.
+    pub evaluations: Vec<F>,
+    /// The number of variables of the polynomial.
+    pub num_vars: usize,
+}
+
+impl<F: Field> fmt::Debug for DenseMultilinearExtension<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "DenseMultilinearExtension(")?;
+        for (i, eval) in self.evaluations.iter().enumerate() {
+            if i == 0 {
+                write!(f, "{:?}", eval)?;
+            } else {
+                write!(f, ", {:?}", eval)?;
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+impl<F: Field> Index<usize> for DenseMultilinearExtension<F> {
+    type Output = F;
+
+    /// Returns the evaluation at the boolean point whose bits are `index`.
+    fn index(&self, index: usize) -> &F {
+        &self.evaluations[index]
+    }
+}
+
+impl<F: Field> DenseMultilinearExtension<F> {
+    /// Returns the zero polynomial in `num_vars` variables.
+    pub fn zero(num_vars: usize) -> Self {
+        Self {
+            evaluations: vec![F::zero(); 1 << num_vars],
+            num_vars,
+        }
+    }
+
+    /// Checks if the given polynomial is zero.
+    pub fn is_zero(&self) -> bool {
+        self.evaluations.iter().all(|eval| eval.is_zero())
+    }
+
+    /// Constructs a new polynomial from its evaluations over the boolean
+    /// hypercube.
+    pub fn from_evaluations_slice(num_vars: usize, evaluations: &[F]) -> Self {
+        Self::from_evaluations_vec(num_vars, evaluations.to_vec())
+    }
+
+    /// Constructs a new polynomial from its evaluations over the boolean
+    /// hypercube.
+    ///
+    /// Panics if `evaluations` does not contain exactly `2^num_vars` entries.
+    pub fn from_evaluations_vec(num_vars: usize, evaluations: Vec<F>) -> Self {
+        assert_eq!(
+            evaluations.len(),
+            1 << num_vars,
+            "the number of evaluations must be 2^num_vars"
+        );
+        Self {
+            evaluations,
+            num_vars,
+        }
+    }
+
+    /// Outputs a polynomial in `num_vars` variables whose evaluations are
+    /// sampled uniformly at random from the field `F`.
+    pub fn rand<R: Rng>(num_vars: usize, rng: &mut R) -> Self {
+        Self::from_evaluations_vec(num_vars, (0..1 << num_vars).map(|_| F::rand(rng)).collect())
+    }
+
+    /// Returns the polynomial in `num_vars - partial_point.len()` variables
+    /// that is obtained by fixing the first `partial_point.len()` variables
+    /// of `self` to `partial_point`.
+    ///
+    /// Panics if `partial_point` has more than `num_vars` entries.
+    pub fn fix_variables(&self, partial_point: &[F]) -> Self {
+        assert!(
+            partial_point.len() <= self.num_vars,
+            "invalid size of partial point"
+        );
+        let mut evaluations = self.evaluations.clone();
+        for (i, r) in partial_point.iter().enumerate() {
+            // Fix the least significant remaining variable, halving the table.
+            let half = 1 << (self.num_vars - i - 1);
+            for b in 0..half {
+                let (low, high) = (evaluations[2 * b], evaluations[2 * b + 1]);
+                evaluations[b] = low + &(*r * &(high - &low));
+            }
+            evaluations.truncate(half);
+        }
+        Self::from_evaluations_vec(self.num_vars - partial_point.len(), evaluations)
+    }
+
+    /// Evaluates `self` at the given `point`, or returns `None` if `point`
+    /// does not have `num_vars` entries.
+    pub fn evaluate(&self, point: &[F]) -> Option<F> {
+        if point.len() == self.num_vars {
+            Some(self.fix_variables(point).evaluations[0])
+        } else {
+            None
+        }
+    }
+
+    /// Converts `self` into a sparse multivariate polynomial, by computing
+    /// the coefficient of each multilinear monomial.
+    pub fn to_sparse(&self) -> SparseMultivariatePolynomial<F> {
+        // The coefficient of `\prod_{i \in S} x_i` is
+        // `\sum_{T \subseteq S} (-1)^{|S \setminus T|} f(T)`, which is computed
+        // one variable at a time.
+        let mut coeffs = self.evaluations.clone();
+        for i in 0..self.num_vars {
+            let bit = 1 << i;
+            for index in 0..coeffs.len() {
+                if index & bit != 0 {
+                    let low = coeffs[index ^ bit];
+                    coeffs[index] -= &low;
+                }
+            }
+        }
+        let terms = coeffs
+            .into_iter()
+            .enumerate()
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .map(|(index, coeff)| {
+                let term = (0..self.num_vars)
+                    .filter(|i| index & (1 << i) != 0)
+                    .map(|i| (i, 1))
+                    .collect();
+                (coeff, SparseTerm::new(term))
+            })
+            .collect();
+        SparseMultivariatePolynomial::from_coefficients_vec(self.num_vars, terms)
+    }
+}
+
+impl<'a, 'b, F: Field> Add<&'a DenseMultilinearExtension<F>> for &'b DenseMultilinearExtension<F> {
+    type Output = DenseMultilinearExtension<F>;
+
+    fn add(self, other: &'a DenseMultilinearExtension<F>) -> DenseMultilinearExtension<F> {
+        let mut result = self.clone();
+        result += other;
+        result
+    }
+}
+
+impl<'a, F: Field> AddAssign<&'a DenseMultilinearExtension<F>> for DenseMultilinearExtension<F> {
+    fn add_assign(&mut self, other: &'a DenseMultilinearExtension<F>) {
+        assert_eq!(
+            self.num_vars, other.num_vars,
+            "mismatched number of variables"
+        );
+        for (a, b) in self.evaluations.iter_mut().zip(&other.evaluations) {
+            *a += b;
+        }
+    }
+}
+
+impl<'a, F: Field> AddAssign<(F, &'a DenseMultilinearExtension<F>)>
+    for DenseMultilinearExtension<F>
+{
+    fn add_assign(&mut self, (f, other): (F, &'a DenseMultilinearExtension<F>)) {
+        assert_eq!(
+            self.num_vars, other.num_vars,
+            "mismatched number of variables"
+        );
+        for (a, b) in self.evaluations.iter_mut().zip(&other.evaluations) {
+            *a += &(f * b);
+        }
+    }
+}
+
+impl<F: Field> Neg for DenseMultilinearExtension<F> {
+    type Output = DenseMultilinearExtension<F>;
+
+    #[inline]
+    fn neg(mut self) -> DenseMultilinearExtension<F> {
+        for eval in &mut self.evaluations {
+            *eval = -*eval;
+        }
+        self
+    }
+}
+
+impl<'a, 'b, F: Field> Sub<&'a DenseMultilinearExtension<F>> for &'b DenseMultilinearExtension<F> {
+    type Output = DenseMultilinearExtension<F>;
+
+    #[inline]
+    fn sub(self, other: &'a DenseMultilinearExtension<F>) -> DenseMultilinearExtension<F> {
+        let mut result = self.clone();
+        result -= other;
+        result
+    }
+}
+
+impl<'a, F: Field> SubAssign<&'a DenseMultilinearExtension<F>> for DenseMultilinearExtension<F> {
+    #[inline]
+    fn sub_assign(&mut self, other: &'a DenseMultilinearExtension<F>) {
+        assert_eq!(
+            self.num_vars, other.num_vars,
+            "mismatched number of variables"
+        );
+        for (a, b) in self.evaluations.iter_mut().zip(&other.evaluations) {
+            *a -= b;
+        }
+    }
+}
+
+/// The product of two multilinear polynomials is in general not multilinear,
+/// so it is returned as a sparse multivariate polynomial.
+impl<'a, 'b, F: Field> Mul<&'a DenseMultilinearExtension<F>> for &'b DenseMultilinearExtension<F> {
+    type Output = SparseMultivariatePolynomial<F>;
+
+    #[inline]
+    fn mul(self, other: &'a DenseMultilinearExtension<F>) -> SparseMultivariatePolynomial<F> {
+        &self.to_sparse() * &other.to_sparse()
+    }
+}
+
+impl<'a, F: Field> From<&'a DenseMultilinearExtension<F>> for SparseMultivariatePolynomial<F> {
+    fn from(other: &'a DenseMultilinearExtension<F>) -> Self {
+        other.to_sparse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DenseMultilinearExtension;
+    use algebra::bls12_381::fr::Fr;
+    use algebra_core::{test_rng, One, UniformRand, Zero};
+
+    /// Evaluates the multilinear extension of `evals` at `point` via the
+    /// Lagrange basis of the boolean hypercube.
+    fn naive_evaluate(evals: &[Fr], point: &[Fr]) -> Fr {
+        let mut result = Fr::zero();
+        for (index, eval) in evals.iter().enumerate() {
+            let mut basis = Fr::one();
+            for (i, x) in point.iter().enumerate() {
+                basis *= &if index & (1 << i) != 0 {
+                    *x
+                } else {
+                    Fr::one() - x
+                };
+            }
+            result += &(basis * eval);
+        }
+        result
+    }
+
+    #[test]
+    fn evaluate_matches_lagrange_interpolation() {
+        let rng = &mut test_rng();
+        for num_vars in 0..8 {
+            let poly = DenseMultilinearExtension::<Fr>::rand(num_vars, rng);
+            let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(rng)).collect();
+            assert_eq!(
+                poly.evaluate(&point),
+                Some(naive_evaluate(&poly.evaluations, &point))
+            );
+            assert_eq!(
+                poly.evaluate(&[]),
+                if num_vars == 0 {
+                    Some(poly.evaluations[0])
+                } else {
+                    None
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn evaluate_on_hypercube() {
+        let rng = &mut test_rng();
+        let poly = DenseMultilinearExtension::<Fr>::rand(4, rng);
+        for index in 0..16 {
+            let point: Vec<Fr> = (0..4)
+                .map(|i| {
+                    if index & (1 << i) != 0 {
+                        Fr::one()
+                    } else {
+                        Fr::zero()
+                    }
+                })
+                .collect();
+            assert_eq!(poly.evaluate(&point), Some(poly[index]));
+        }
+    }
+
+    #[test]
+    fn fix_variables_is_consistent() {
+        let rng = &mut test_rng();
+        let poly = DenseMultilinearExtension::<Fr>::rand(6, rng);
+        let point: Vec<Fr> = (0..6).map(|_| Fr::rand(rng)).collect();
+        for k in 0..=6 {
+            let partial = poly.fix_variables(&point[..k]);
+            assert_eq!(partial.num_vars, 6 - k);
+            assert_eq!(partial.evaluate(&point[k..]), poly.evaluate(&point));
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        let rng = &mut test_rng();
+        let a = DenseMultilinearExtension::<Fr>::rand(5, rng);
+        let b = DenseMultilinearExtension::<Fr>::rand(5, rng);
+        let point: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let (a_eval, b_eval) = (a.evaluate(&point).unwrap(), b.evaluate(&point).unwrap());
+
+        assert_eq!((&a + &b).evaluate(&point), Some(a_eval + &b_eval));
+        assert_eq!((&a - &b).evaluate(&point), Some(a_eval - &b_eval));
+        assert_eq!((-a.clone()).evaluate(&point), Some(-a_eval));
+        let c = Fr::rand(rng);
+        let mut d = a.clone();
+        d += (c, &b);
+        assert_eq!(d.evaluate(&point), Some(a_eval + &(c * &b_eval)));
+        assert!((&a - &a).is_zero());
+
+        let product = &a * &b;
+        assert_eq!(product.evaluate(&point), a_eval * &b_eval);
+    }
+
+    #[test]
+    fn to_sparse_preserves_evaluations() {
+        let rng = &mut test_rng();
+        for num_vars in 0..6 {
+            let poly = DenseMultilinearExtension::<Fr>::rand(num_vars, rng);
+            let sparse = poly.to_sparse();
+            assert!(sparse.degree() <= num_vars);
+            let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(rng)).collect();
+            assert_eq!(Some(sparse.evaluate(&point)), poly.evaluate(&point));
+        }
+    }
+}
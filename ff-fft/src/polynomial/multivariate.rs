@@ -0,0 +1,381 @@
+//! A sparse multivariate polynomial represented as a list of terms.
+
+use crate::{BTreeMap, Vec};
+use algebra_core::Field;
+use core::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+use rand::Rng;
+
+/// Stores a monomial `\prod_i x_{v_i}^{e_i}` as the list of pairs
+/// `(v_i, e_i)`, sorted in increasing order of the variable index `v_i`.
+/// Variables with exponent zero are omitted, so the constant monomial is the
+/// empty list.
+#[derive(Clone, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct SparseTerm(Vec<(usize, usize)>);
+
+impl fmt::Debug for SparseTerm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (i, (var, power)) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, " * ")?;
+            }
+            if *power == 1 {
+                write!(f, "x_{}", var)?;
+            } else {
+                write!(f, "x_{}^{}", var, power)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl core::ops::Deref for SparseTerm {
+    type Target = [(usize, usize)];
+
+    fn deref(&self) -> &[(usize, usize)] {
+        &self.0
+    }
+}
+
+impl SparseTerm {
+    /// Constructs a new term from a list of `(variable, power)` pairs.
+    /// Repeated variables are merged and zero powers are dropped.
+    pub fn new(mut term: Vec<(usize, usize)>) -> Self {
+        term.retain(|(_, power)| *power != 0);
+        term.sort_by(|(v1, _), (v2, _)| v1.cmp(v2));
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(term.len());
+        for (var, power) in term {
+            match merged.last_mut() {
+                Some((last_var, last_power)) if *last_var == var => *last_power += power,
+                _ => merged.push((var, power)),
+            }
+        }
+        SparseTerm(merged)
+    }
+
+    /// Returns the total degree of the term.
+    pub fn degree(&self) -> usize {
+        self.0.iter().map(|(_, power)| power).sum()
+    }
+
+    /// Checks if the term is the constant monomial `1`.
+    pub fn is_constant(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Evaluates the term at the given `point`.
+    ///
+    /// Panics if `point` does not assign a value to every variable of `self`.
+    pub fn evaluate<F: Field>(&self, point: &[F]) -> F {
+        self.0.iter().fold(F::one(), |acc, (var, power)| {
+            acc * &point[*var].pow(&[*power as u64])
+        })
+    }
+}
+
+impl<'a, 'b> Mul<&'a SparseTerm> for &'b SparseTerm {
+    type Output = SparseTerm;
+
+    fn mul(self, other: &'a SparseTerm) -> SparseTerm {
+        SparseTerm::new(self.0.iter().chain(&other.0).cloned().collect())
+    }
+}
+
+/// Stores a sparse multivariate polynomial in `num_vars` variables as a list
+/// of `(coefficient, term)` pairs.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct SparseMultivariatePolynomial<F: Field> {
+    /// The number of variables of the polynomial.
+    pub num_vars: usize,
+    /// The entries in `self.terms` *must* be sorted in increasing order of
+    /// the term, contain no repeated terms, and have non-zero coefficients.
+    terms: Vec<(F, SparseTerm)>,
+}
+
+impl<F: Field> fmt::Debug for SparseMultivariatePolynomial<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (i, (coeff, term)) in self.terms.iter().enumerate() {
+            if i != 0 {
+                write!(f, " + ")?;
+            }
+            if term.is_constant() {
+                write!(f, "\n{:?}", coeff)?;
+            } else {
+                write!(f, "\n{:?} * {:?}", coeff, term)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<F: Field> core::ops::Deref for SparseMultivariatePolynomial<F> {
+    type Target = [(F, SparseTerm)];
+
+    fn deref(&self) -> &[(F, SparseTerm)] {
+        &self.terms
+    }
+}
+
+impl<F: Field> SparseMultivariatePolynomial<F> {
+    /// Returns the zero polynomial in `num_vars` variables.
+    pub fn zero(num_vars: usize) -> Self {
+        Self {
+            num_vars,
+            terms: Vec::new(),
+        }
+    }
+
+    /// Checks if the given polynomial is zero.
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Constructs a new polynomial from a list of `(coefficient, term)`
+    /// pairs. Repeated terms are merged and zero coefficients are dropped.
+    ///
+    /// Panics if a term refers to a variable with index at least `num_vars`.
+    pub fn from_coefficients_slice(num_vars: usize, terms: &[(F, SparseTerm)]) -> Self {
+        Self::from_coefficients_vec(num_vars, terms.to_vec())
+    }
+
+    /// Constructs a new polynomial from a list of `(coefficient, term)`
+    /// pairs. Repeated terms are merged and zero coefficients are dropped.
+    ///
+    /// Panics if a term refers to a variable with index at least `num_vars`.
+    pub fn from_coefficients_vec(num_vars: usize, terms: Vec<(F, SparseTerm)>) -> Self {
+        let mut merged = BTreeMap::new();
+        for (coeff, term) in terms {
+            assert!(
+                term.last().map_or(true, |(var, _)| *var < num_vars),
+                "term refers to a variable that does not exist"
+            );
+            *merged.entry(term).or_insert_with(F::zero) += &coeff;
+        }
+        let terms = merged
+            .into_iter()
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .map(|(term, coeff)| (coeff, term))
+            .collect();
+        Self { num_vars, terms }
+    }
+
+    /// Returns the total degree of the polynomial.
+    pub fn degree(&self) -> usize {
+        self.terms
+            .iter()
+            .map(|(_, term)| term.degree())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Evaluates `self` at the given `point`.
+    ///
+    /// Panics if `point` does not have `num_vars` entries.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars, "invalid size of point");
+        self.terms
+            .iter()
+            .map(|(coeff, term)| *coeff * &term.evaluate(point))
+            .sum()
+    }
+
+    /// Outputs a polynomial in `num_vars` variables of total degree at most
+    /// `d`, where the coefficient of every monomial is sampled uniformly at
+    /// random from the field `F`.
+    pub fn rand<R: Rng>(d: usize, num_vars: usize, rng: &mut R) -> Self {
+        let mut terms = Vec::new();
+        let mut exponents = vec![0usize; num_vars];
+        // Enumerate all exponent vectors of total degree at most `d`.
+        loop {
+            let term = exponents
+                .iter()
+                .enumerate()
+                .map(|(var, power)| (var, *power))
+                .collect();
+            terms.push((F::rand(rng), SparseTerm::new(term)));
+
+            let mut var = 0;
+            loop {
+                if var == num_vars {
+                    return Self::from_coefficients_vec(num_vars, terms);
+                }
+                exponents[var] += 1;
+                if exponents.iter().sum::<usize>() <= d {
+                    break;
+                }
+                exponents[var] = 0;
+                var += 1;
+            }
+        }
+    }
+}
+
+impl<'a, 'b, F: Field> Add<&'a SparseMultivariatePolynomial<F>>
+    for &'b SparseMultivariatePolynomial<F>
+{
+    type Output = SparseMultivariatePolynomial<F>;
+
+    fn add(self, other: &'a SparseMultivariatePolynomial<F>) -> SparseMultivariatePolynomial<F> {
+        let mut result = self.clone();
+        result += other;
+        result
+    }
+}
+
+impl<'a, F: Field> AddAssign<&'a SparseMultivariatePolynomial<F>>
+    for SparseMultivariatePolynomial<F>
+{
+    fn add_assign(&mut self, other: &'a SparseMultivariatePolynomial<F>) {
+        *self += (F::one(), other);
+    }
+}
+
+impl<'a, F: Field> AddAssign<(F, &'a SparseMultivariatePolynomial<F>)>
+    for SparseMultivariatePolynomial<F>
+{
+    fn add_assign(&mut self, (f, other): (F, &'a SparseMultivariatePolynomial<F>)) {
+        let num_vars = core::cmp::max(self.num_vars, other.num_vars);
+        let mut terms = core::mem::take(&mut self.terms);
+        terms.extend(
+            other
+                .terms
+                .iter()
+                .map(|(coeff, term)| (f * coeff, term.clone())),
+        );
+        *self = Self::from_coefficients_vec(num_vars, terms);
+    }
+}
+
+impl<F: Field> Neg for SparseMultivariatePolynomial<F> {
+    type Output = SparseMultivariatePolynomial<F>;
+
+    #[inline]
+    fn neg(mut self) -> SparseMultivariatePolynomial<F> {
+        for (coeff, _) in &mut self.terms {
+            *coeff = -*coeff;
+        }
+        self
+    }
+}
+
+impl<'a, 'b, F: Field> Sub<&'a SparseMultivariatePolynomial<F>>
+    for &'b SparseMultivariatePolynomial<F>
+{
+    type Output = SparseMultivariatePolynomial<F>;
+
+    #[inline]
+    fn sub(self, other: &'a SparseMultivariatePolynomial<F>) -> SparseMultivariatePolynomial<F> {
+        let mut result = self.clone();
+        result -= other;
+        result
+    }
+}
+
+impl<'a, F: Field> SubAssign<&'a SparseMultivariatePolynomial<F>>
+    for SparseMultivariatePolynomial<F>
+{
+    #[inline]
+    fn sub_assign(&mut self, other: &'a SparseMultivariatePolynomial<F>) {
+        *self += (-F::one(), other);
+    }
+}
+
+/// Performs a naive multiplication of every pair of terms.
+impl<'a, 'b, F: Field> Mul<&'a SparseMultivariatePolynomial<F>>
+    for &'b SparseMultivariatePolynomial<F>
+{
+    type Output = SparseMultivariatePolynomial<F>;
+
+    #[inline]
+    fn mul(self, other: &'a SparseMultivariatePolynomial<F>) -> SparseMultivariatePolynomial<F> {
+        let num_vars = core::cmp::max(self.num_vars, other.num_vars);
+        let mut terms = Vec::with_capacity(self.terms.len() * other.terms.len());
+        for (self_coeff, self_term) in &self.terms {
+            for (other_coeff, other_term) in &other.terms {
+                terms.push((*self_coeff * other_coeff, self_term * other_term));
+            }
+        }
+        SparseMultivariatePolynomial::from_coefficients_vec(num_vars, terms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SparseMultivariatePolynomial, SparseTerm};
+    use algebra::bls12_381::fr::Fr;
+    use algebra_core::{test_rng, One, UniformRand, Zero};
+
+    #[test]
+    fn sparse_term_normalization() {
+        let term = SparseTerm::new(vec![(2, 1), (0, 3), (2, 2), (1, 0)]);
+        assert_eq!(&*term, &[(0, 3), (2, 3)]);
+        assert_eq!(term.degree(), 6);
+        assert!(SparseTerm::new(vec![(4, 0)]).is_constant());
+        let product = &term * &SparseTerm::new(vec![(1, 1), (2, 1)]);
+        assert_eq!(&*product, &[(0, 3), (1, 1), (2, 4)]);
+    }
+
+    #[test]
+    fn terms_are_merged() {
+        let two = Fr::one() + &Fr::one();
+        let x0 = SparseTerm::new(vec![(0, 1)]);
+        let poly = SparseMultivariatePolynomial::from_coefficients_vec(
+            2,
+            vec![
+                (Fr::one(), x0.clone()),
+                (Fr::one(), SparseTerm::new(vec![(1, 1)])),
+                (Fr::one(), x0.clone()),
+                (-Fr::one(), SparseTerm::new(vec![(1, 1)])),
+            ],
+        );
+        assert_eq!(poly.len(), 1);
+        assert_eq!(poly[0], (two, x0));
+        assert!((&poly - &poly).is_zero());
+    }
+
+    #[test]
+    fn evaluate_fixed() {
+        // 3 + 2 x_0^2 x_1 + x_2
+        let three = Fr::from(3u64);
+        let two = Fr::from(2u64);
+        let poly = SparseMultivariatePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (three, SparseTerm::new(vec![])),
+                (two, SparseTerm::new(vec![(0, 2), (1, 1)])),
+                (Fr::one(), SparseTerm::new(vec![(2, 1)])),
+            ],
+        );
+        assert_eq!(poly.degree(), 3);
+        let point = [Fr::from(5u64), Fr::from(7u64), Fr::from(11u64)];
+        assert_eq!(poly.evaluate(&point), Fr::from(3u64 + 2 * 25 * 7 + 11));
+    }
+
+    #[test]
+    fn arithmetic_random() {
+        let rng = &mut test_rng();
+        for num_vars in 1..4 {
+            for degree in 0..4 {
+                let a = SparseMultivariatePolynomial::<Fr>::rand(degree, num_vars, rng);
+                let b = SparseMultivariatePolynomial::<Fr>::rand(degree, num_vars, rng);
+                assert!(a.degree() <= degree);
+                let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(rng)).collect();
+                let (a_eval, b_eval) = (a.evaluate(&point), b.evaluate(&point));
+
+                assert_eq!((&a + &b).evaluate(&point), a_eval + &b_eval);
+                assert_eq!((&a - &b).evaluate(&point), a_eval - &b_eval);
+                assert_eq!((-a.clone()).evaluate(&point), -a_eval);
+                assert_eq!((&a * &b).evaluate(&point), a_eval * &b_eval);
+                let c = Fr::rand(rng);
+                let mut d = a.clone();
+                d += (c, &b);
+                assert_eq!(d.evaluate(&point), a_eval + &(c * &b_eval));
+            }
+        }
+        assert!(SparseMultivariatePolynomial::<Fr>::zero(3)
+            .evaluate(&[Fr::one(); 3])
+            .is_zero());
+    }
+}
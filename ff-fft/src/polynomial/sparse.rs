@@ -1,11 +1,16 @@
 //! A sparse polynomial represented in coefficient form.
 
-use core::fmt;
+use core::{fmt, ops::Mul};
 
 use crate::{
     BTreeMap, DenseOrSparsePolynomial, DensePolynomial, EvaluationDomain, Evaluations, Vec,
 };
-use algebra_core::{FftField, Field};
+use algebra_core::{
+    bytes::ToBytes,
+    io::{Read, Result as IoResult, Write},
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+    FftField, Field,
+};
 
 /// Stores a sparse polynomial in coefficient form.
 #[derive(Clone, PartialEq, Eq, Hash, Default)]
@@ -110,6 +115,57 @@ impl<F: Field> SparsePolynomial<F> {
     }
 }
 
+impl<'a, F: Field> Mul<F> for &'a SparsePolynomial<F> {
+    type Output = SparsePolynomial<F>;
+
+    #[inline]
+    fn mul(self, elem: F) -> SparsePolynomial<F> {
+        if elem.is_zero() {
+            SparsePolynomial::zero()
+        } else {
+            let coeffs = self.coeffs.iter().map(|(i, c)| (*i, *c * &elem)).collect();
+            SparsePolynomial { coeffs }
+        }
+    }
+}
+
+impl<F: Field> ToBytes for SparsePolynomial<F> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        for (i, coeff) in &self.coeffs {
+            (*i as u64).write(&mut writer)?;
+            coeff.write(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: Field> CanonicalSerialize for SparsePolynomial<F> {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.coeffs.serialize(writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.coeffs.serialized_size()
+    }
+}
+
+impl<F: Field> CanonicalDeserialize for SparsePolynomial<F> {
+    /// Fails unless the degrees are strictly increasing and the leading
+    /// coefficient is nonzero.
+    #[inline]
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        let coeffs = Vec::<(usize, F)>::deserialize(reader)?;
+        let increasing = coeffs.windows(2).all(|w| w[0].0 < w[1].0);
+        if !increasing || coeffs.last().map_or(false, |(_, c)| c.is_zero()) {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(Self { coeffs })
+    }
+}
+
 impl<F: FftField> SparsePolynomial<F> {
     /// Evaluate `self` over `domain`.
     pub fn evaluate_over_domain_by_ref<D: EvaluationDomain<F>>(
@@ -141,7 +197,10 @@ impl<F: Field> Into<DensePolynomial<F>> for SparsePolynomial<F> {
 mod tests {
     use crate::{DensePolynomial, EvaluationDomain, GeneralEvaluationDomain, SparsePolynomial};
     use algebra::bls12_381::fr::Fr;
-    use algebra_core::One;
+    use algebra_core::{
+        test_rng, CanonicalDeserialize, CanonicalSerialize, One, SerializationError, UniformRand,
+        Zero,
+    };
 
     #[test]
     fn evaluate_over_domain() {
@@ -159,4 +218,39 @@ mod tests {
             assert_eq!(evals2.interpolate(), dense_poly);
         }
     }
+
+    #[test]
+    fn mul_by_scalar() {
+        let rng = &mut test_rng();
+        let f = Fr::rand(rng);
+        let sparse_poly = SparsePolynomial::from_coefficients_vec(vec![(1, Fr::one()), (5, f)]);
+        let dense_poly: DensePolynomial<Fr> = (&sparse_poly * f).into();
+        let expected: DensePolynomial<Fr> = sparse_poly.clone().into();
+        assert_eq!(dense_poly, &expected * f);
+        assert!((&sparse_poly * Fr::zero()).is_zero());
+    }
+
+    #[test]
+    fn serialize_sparse_polynomials() {
+        let rng = &mut test_rng();
+        let sparse_poly =
+            SparsePolynomial::from_coefficients_vec(vec![(0, Fr::rand(rng)), (7, Fr::rand(rng))]);
+        let mut bytes = Vec::new();
+        sparse_poly.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), sparse_poly.serialized_size());
+        assert_eq!(
+            SparsePolynomial::deserialize(&bytes[..]).unwrap(),
+            sparse_poly
+        );
+
+        // Degrees must be strictly increasing.
+        let mut bytes = Vec::new();
+        vec![(7usize, Fr::one()), (0usize, Fr::one())]
+            .serialize(&mut bytes)
+            .unwrap();
+        match SparsePolynomial::<Fr>::deserialize(&bytes[..]) {
+            Err(SerializationError::InvalidData) => {},
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
 }
@@ -0,0 +1,208 @@
+//! A subproduct tree over a set of points, for fast multipoint evaluation and
+//! interpolation.
+
+use crate::{Box, DensePolynomial, Vec};
+use algebra_core::{batch_inversion, FftField};
+
+/// Subtrees over at most this many points are handled directly, since
+/// FFT-based multiplication and division do not pay off for small degrees.
+const LEAF_SIZE: usize = 8;
+
+/// A binary tree whose leaves are the linear factors `X - x_i` for a set of
+/// points `x_i`, and whose internal nodes store the product of the factors
+/// below them.
+///
+/// Building the tree costs `O(n log^2 n)` field operations, after which any
+/// polynomial can be evaluated at all `n` points, or interpolated from its
+/// evaluations at all `n` points, in `O(n log^2 n)` field operations.
+#[derive(Clone, Debug)]
+pub struct SubproductTree<F: FftField> {
+    points: Vec<F>,
+    root: SubproductTreeNode<F>,
+}
+
+#[derive(Clone, Debug)]
+struct SubproductTreeNode<F: FftField> {
+    /// The product of `X - x_i` over all the points `x_i` below this node.
+    product: DensePolynomial<F>,
+    /// The subtrees over the first and second halves of the points.
+    children: Option<(Box<Self>, Box<Self>)>,
+}
+
+impl<F: FftField> SubproductTree<F> {
+    /// Construct the subproduct tree over `points`.
+    ///
+    /// Panics if `points` is empty.
+    pub fn new(points: &[F]) -> Self {
+        assert!(
+            !points.is_empty(),
+            "cannot build a subproduct tree over no points"
+        );
+        Self {
+            points: points.to_vec(),
+            root: SubproductTreeNode::new(points),
+        }
+    }
+
+    /// The points over which `self` was constructed.
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    /// The polynomial that vanishes exactly on the points of `self`.
+    pub fn vanishing_polynomial(&self) -> &DensePolynomial<F> {
+        &self.root.product
+    }
+
+    /// Evaluates `poly` at each of the points of `self`, in order.
+    pub fn evaluate(&self, poly: &DensePolynomial<F>) -> Vec<F> {
+        let mut evals = Vec::with_capacity(self.points.len());
+        self.root.evaluate(poly, &self.points, &mut evals);
+        evals
+    }
+
+    /// Returns the unique polynomial of degree less than the number of points
+    /// of `self` that evaluates to `evals[i]` at the `i`-th point, or `None`
+    /// if the points of `self` are not distinct.
+    ///
+    /// Panics if `evals` does not contain exactly one value per point.
+    pub fn interpolate(&self, evals: &[F]) -> Option<DensePolynomial<F>> {
+        assert_eq!(
+            evals.len(),
+            self.points.len(),
+            "expected one evaluation per point"
+        );
+
+        // The Lagrange basis polynomial for `x_i` is `Z(X) / ((X - x_i) Z'(x_i))`,
+        // and `Z'(x_i)` is zero precisely when `x_i` is a repeated point.
+        let mut weights = self.evaluate(&self.root.product.derivative());
+        if weights.iter().any(|w| w.is_zero()) {
+            return None;
+        }
+        batch_inversion(&mut weights);
+        weights.iter_mut().zip(evals).for_each(|(w, e)| *w *= e);
+
+        Some(self.root.linear_combination(&self.points, &weights))
+    }
+}
+
+impl<F: FftField> SubproductTreeNode<F> {
+    fn new(points: &[F]) -> Self {
+        if points.len() <= LEAF_SIZE {
+            let mut product = DensePolynomial::from_coefficients_vec(vec![F::one()]);
+            for point in points {
+                product = product.naive_mul(&DensePolynomial::from_coefficients_vec(vec![
+                    -*point,
+                    F::one(),
+                ]));
+            }
+            Self {
+                product,
+                children: None,
+            }
+        } else {
+            let (left, right) = points.split_at(points.len() / 2);
+            let left = Self::new(left);
+            let right = Self::new(right);
+            Self {
+                product: &left.product * &right.product,
+                children: Some((Box::new(left), Box::new(right))),
+            }
+        }
+    }
+
+    fn evaluate(&self, poly: &DensePolynomial<F>, points: &[F], evals: &mut Vec<F>) {
+        let remainder;
+        let poly = if poly.degree() >= self.product.degree() {
+            remainder = poly.fast_divide_with_q_and_r(&self.product).unwrap().1;
+            &remainder
+        } else {
+            poly
+        };
+        match &self.children {
+            Some((left, right)) => {
+                let (left_points, right_points) = points.split_at(points.len() / 2);
+                left.evaluate(poly, left_points, evals);
+                right.evaluate(poly, right_points, evals);
+            },
+            None => evals.extend(points.iter().map(|point| poly.evaluate(*point))),
+        }
+    }
+
+    /// Computes `\sum_i weights[i] * product(X) / (X - points[i])`.
+    fn linear_combination(&self, points: &[F], weights: &[F]) -> DensePolynomial<F> {
+        match &self.children {
+            Some((left, right)) => {
+                let mid = points.len() / 2;
+                let left_comb = left.linear_combination(&points[..mid], &weights[..mid]);
+                let right_comb = right.linear_combination(&points[mid..], &weights[mid..]);
+                &(&left_comb * &right.product) + &(&right_comb * &left.product)
+            },
+            None => {
+                let mut result = vec![F::zero(); points.len()];
+                for (point, weight) in points.iter().zip(weights) {
+                    // Synthetic division of `product` by `X - point`.
+                    let mut carry = F::zero();
+                    for (i, coeff) in self.product.coeffs.iter().enumerate().skip(1).rev() {
+                        carry = carry * point + coeff;
+                        result[i - 1] += &(carry * weight);
+                    }
+                }
+                DensePolynomial::from_coefficients_vec(result)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DensePolynomial, SubproductTree};
+    use algebra::bls12_381::Fr;
+    use algebra_core::{test_rng, UniformRand, Zero};
+
+    #[test]
+    fn evaluate_matches_naive_evaluation() {
+        let rng = &mut test_rng();
+        for num_points in [1, 2, 7, 8, 9, 31, 100, 257].iter() {
+            for degree in [0, 5, 64, 300].iter() {
+                let points: Vec<Fr> = (0..*num_points).map(|_| Fr::rand(rng)).collect();
+                let poly = DensePolynomial::rand(*degree, rng);
+                let tree = SubproductTree::new(&points);
+                let expected: Vec<Fr> = points.iter().map(|p| poly.evaluate(*p)).collect();
+                assert_eq!(tree.evaluate(&poly), expected);
+                assert_eq!(poly.evaluate_at_points(&points), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn vanishing_polynomial_vanishes_on_points() {
+        let rng = &mut test_rng();
+        let points: Vec<Fr> = (0..100).map(|_| Fr::rand(rng)).collect();
+        let tree = SubproductTree::new(&points);
+        assert_eq!(tree.vanishing_polynomial().degree(), points.len());
+        assert!(points
+            .iter()
+            .all(|p| tree.vanishing_polynomial().evaluate(*p).is_zero()));
+    }
+
+    #[test]
+    fn interpolate_recovers_polynomial() {
+        let rng = &mut test_rng();
+        for num_points in [1, 2, 8, 9, 50, 129].iter() {
+            let points: Vec<Fr> = (0..*num_points).map(|_| Fr::rand(rng)).collect();
+            let poly = DensePolynomial::rand(num_points - 1, rng);
+            let evals = poly.evaluate_at_points(&points);
+            assert_eq!(DensePolynomial::interpolate(&points, &evals), Some(poly));
+        }
+    }
+
+    #[test]
+    fn interpolate_rejects_duplicate_points() {
+        let rng = &mut test_rng();
+        let mut points: Vec<Fr> = (0..20).map(|_| Fr::rand(rng)).collect();
+        points[13] = points[4];
+        let evals: Vec<Fr> = (0..20).map(|_| Fr::rand(rng)).collect();
+        assert!(DensePolynomial::interpolate(&points, &evals).is_none());
+    }
+}
@@ -1,9 +1,13 @@
-use crate::domain::*;
+use crate::{domain::*, DensePolynomial, Evaluations};
 use algebra::{
-    bls12_381::{Fr, G1Projective},
+    bls12_381::{Fr, G1Projective, G2Projective},
+    ed_on_mnt4_753::{EdwardsProjective, Fr as EdwardsFr},
     mnt6_753::{Fr as MNT6Fr, G1Projective as MNT6G1Projective},
 };
-use algebra_core::{test_rng, PrimeField, UniformRand};
+use algebra_core::{
+    test_rng, CanonicalDeserialize, CanonicalSerialize, Field, One, PrimeField, ProjectiveCurve,
+    UniformRand,
+};
 
 // Test multiplying various (low degree) polynomials together and
 // comparing with naive evaluations.
@@ -53,7 +57,245 @@ fn fft_composition() {
 
     test_fft_composition::<Fr, Fr, _, GeneralEvaluationDomain<Fr>>(rng, 10);
     test_fft_composition::<Fr, G1Projective, _, GeneralEvaluationDomain<Fr>>(rng, 10);
+    test_fft_composition::<Fr, G2Projective, _, GeneralEvaluationDomain<Fr>>(rng, 6);
+    test_fft_composition::<EdwardsFr, EdwardsProjective, _, GeneralEvaluationDomain<EdwardsFr>>(
+        rng, 6,
+    );
     // This will result in a mixed-radix domain being used.
     test_fft_composition::<MNT6Fr, MNT6Fr, _, MixedRadixEvaluationDomain<MNT6Fr>>(rng, 17);
     test_fft_composition::<MNT6Fr, MNT6G1Projective, _, MixedRadixEvaluationDomain<MNT6Fr>>(rng, 5);
 }
+
+#[test]
+fn monomial_to_lagrange_basis() {
+    fn test_monomial_to_lagrange_basis<G: ProjectiveCurve, D: EvaluationDomain<G::ScalarField>>(
+        max_coeffs: usize,
+    ) {
+        let rng = &mut test_rng();
+        for coeffs in 0..max_coeffs {
+            let domain = D::new(1 << coeffs).unwrap();
+            let tau = G::ScalarField::rand(rng);
+            let g = G::rand(rng);
+
+            // Use more powers than needed to check that extra powers are ignored.
+            let powers_of_tau = (0..=domain.size())
+                .map(|i| tau.pow([i as u64]))
+                .collect::<Vec<_>>();
+            let powers_of_tau_g = powers_of_tau.iter().map(|p| g.mul(*p)).collect::<Vec<_>>();
+
+            let lagrange_coeffs = domain.evaluate_all_lagrange_coefficients(tau);
+            assert_eq!(
+                domain.monomial_to_lagrange_basis(&powers_of_tau),
+                lagrange_coeffs
+            );
+
+            let coset = domain.get_coset(G::ScalarField::rand(rng)).unwrap();
+            assert_eq!(
+                coset.monomial_to_lagrange_basis(&powers_of_tau),
+                coset.evaluate_all_lagrange_coefficients(tau)
+            );
+
+            let expected = lagrange_coeffs
+                .iter()
+                .map(|l| g.mul(*l))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                domain.monomial_to_lagrange_basis(&powers_of_tau_g),
+                expected
+            );
+        }
+    }
+
+    test_monomial_to_lagrange_basis::<G1Projective, GeneralEvaluationDomain<Fr>>(8);
+    test_monomial_to_lagrange_basis::<G2Projective, GeneralEvaluationDomain<Fr>>(4);
+    test_monomial_to_lagrange_basis::<MNT6G1Projective, MixedRadixEvaluationDomain<MNT6Fr>>(4);
+}
+
+#[test]
+fn coset_domains() {
+    fn test_coset_domains<F: PrimeField, D: EvaluationDomain<F>>(max_coeffs: usize) {
+        let rng = &mut test_rng();
+        for coeffs in 0..max_coeffs {
+            let offset = F::rand(rng);
+            let domain = D::new_coset(1 << coeffs, offset).unwrap();
+            assert_eq!(
+                domain,
+                D::new(1 << coeffs).unwrap().get_coset(offset).unwrap()
+            );
+            assert_eq!(
+                domain.get_coset(F::one()).unwrap(),
+                D::new(1 << coeffs).unwrap()
+            );
+            assert!(domain.get_coset(F::zero()).is_none());
+
+            let p = DensePolynomial::<F>::rand(domain.size() - 1, rng);
+            let elements = domain.elements().collect::<Vec<_>>();
+            assert_eq!(elements[0], offset);
+
+            // The FFT over a coset evaluates over its elements, and the IFFT inverts it.
+            let evals = domain.fft(&p.coeffs);
+            let expected = elements.iter().map(|e| p.evaluate(*e)).collect::<Vec<_>>();
+            assert_eq!(evals, expected);
+            assert_eq!(
+                DensePolynomial::from_coefficients_vec(domain.ifft(&evals)),
+                p
+            );
+            let coset_evals = domain.coset_fft(&p.coeffs);
+            assert_eq!(domain.coset_ifft(&coset_evals), p.coeffs);
+
+            // The vanishing polynomial vanishes exactly on the coset.
+            let z = domain.vanishing_polynomial();
+            assert!(elements.iter().all(|e| z.evaluate(*e).is_zero()));
+            assert!(!domain.evaluate_vanishing_polynomial(F::one()).is_zero());
+            let larger = D::new_coset(2 << coeffs, F::rand(rng)).unwrap();
+            let expected = larger
+                .elements()
+                .map(|e| domain.evaluate_vanishing_polynomial(e))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                domain.evaluate_vanishing_polynomial_over_domain(&larger),
+                expected
+            );
+
+            // Interpolating with the Lagrange coefficients recovers the polynomial.
+            let tau = F::rand(rng);
+            let lagrange_coeffs = domain.evaluate_all_lagrange_coefficients(tau);
+            let interpolated = lagrange_coeffs
+                .iter()
+                .zip(&evals)
+                .fold(F::zero(), |acc, (l, e)| acc + &(*l * e));
+            assert_eq!(interpolated, p.evaluate(tau));
+            let lagrange_coeffs =
+                domain.evaluate_all_lagrange_coefficients(elements[1 % domain.size()]);
+            assert_eq!(lagrange_coeffs.iter().filter(|l| l.is_one()).count(), 1);
+            assert!(lagrange_coeffs[1 % domain.size()].is_one());
+        }
+    }
+
+    test_coset_domains::<Fr, GeneralEvaluationDomain<Fr>>(8);
+    test_coset_domains::<Fr, Radix2EvaluationDomain<Fr>>(4);
+    test_coset_domains::<MNT6Fr, MixedRadixEvaluationDomain<MNT6Fr>>(4);
+}
+
+#[test]
+fn subdomains() {
+    fn test_subdomains<F: PrimeField, D: EvaluationDomain<F>>(log_size: usize) {
+        let rng = &mut test_rng();
+        let domain = D::new_coset(1 << log_size, F::rand(rng)).unwrap();
+        let p = DensePolynomial::<F>::rand(domain.size() - 1, rng);
+        let evals = domain.fft(&p.coeffs);
+        let elements = domain.elements().collect::<Vec<_>>();
+        assert!(domain.get_subdomain(0).is_none());
+        assert!(domain.get_subdomain(domain.size() + 1).is_none());
+
+        for log_k in 0..=log_size {
+            let k = 1 << log_k;
+            let subdomain = domain.get_subdomain(k).unwrap();
+            assert_eq!(subdomain.size(), domain.size() / k);
+            assert_eq!(subdomain.coset_offset(), domain.coset_offset());
+            let expected = elements.iter().step_by(k).copied().collect::<Vec<_>>();
+            assert_eq!(subdomain.elements().collect::<Vec<_>>(), expected);
+
+            let split = domain.split_evaluations_by_subdomain(&evals, k);
+            assert_eq!(split.len(), k);
+            let mut shift = domain.coset_offset();
+            for (t, sub_evals) in split.iter().enumerate() {
+                // The `t`-th part holds the evaluations over the subdomain shifted
+                // by the `t`-th element of the domain.
+                let coset = subdomain.get_coset(shift).unwrap();
+                assert_eq!(coset.elements().next(), Some(elements[t]));
+                let expected = coset.elements().map(|e| p.evaluate(e)).collect::<Vec<_>>();
+                assert_eq!(sub_evals, &expected);
+                shift *= &domain.group_gen();
+            }
+            assert_eq!(domain.merge_evaluations_from_subdomain(&split), evals);
+
+            let evaluations = Evaluations::from_vec_and_domain(evals.clone(), domain);
+            let restricted = evaluations.restrict_to_subdomain(k);
+            assert_eq!(restricted.domain(), subdomain);
+            assert_eq!(restricted.evals, split[0]);
+        }
+    }
+
+    test_subdomains::<Fr, GeneralEvaluationDomain<Fr>>(6);
+    test_subdomains::<MNT6Fr, MixedRadixEvaluationDomain<MNT6Fr>>(4);
+}
+
+#[test]
+#[should_panic(expected = "domains are unequal")]
+fn evaluations_over_different_cosets() {
+    let rng = &mut test_rng();
+    let domain = GeneralEvaluationDomain::<Fr>::new(8).unwrap();
+    let coset = domain.get_coset(Fr::rand(rng)).unwrap();
+    let p = DensePolynomial::<Fr>::rand(7, rng);
+    let a = p.clone().evaluate_over_domain(domain);
+    let b = p.evaluate_over_domain(coset);
+    let _ = &a + &b;
+}
+
+#[test]
+fn serialize_domains_and_evaluations() {
+    fn test_serialize<
+        F: PrimeField,
+        D: EvaluationDomain<F> + CanonicalSerialize + CanonicalDeserialize,
+    >(
+        log_size: usize,
+    ) {
+        let rng = &mut test_rng();
+        let domain = D::new(1 << log_size).unwrap();
+        let coset = domain.get_coset(F::rand(rng)).unwrap();
+        for domain in [domain, coset, coset.get_subdomain(2).unwrap()].iter() {
+            let mut bytes = Vec::new();
+            domain.serialize(&mut bytes).unwrap();
+            assert_eq!(bytes.len(), domain.serialized_size());
+            assert_eq!(D::deserialize(&bytes[..]).unwrap(), *domain);
+
+            let p = DensePolynomial::<F>::rand(domain.size() - 1, rng);
+            let evaluations = p.evaluate_over_domain(*domain);
+            let mut bytes = Vec::new();
+            evaluations.serialize(&mut bytes).unwrap();
+            assert_eq!(bytes.len(), evaluations.serialized_size());
+            assert_eq!(Evaluations::deserialize(&bytes[..]).unwrap(), evaluations);
+
+            // There must be exactly one evaluation per element of the domain.
+            let mut evals = evaluations.evals;
+            evals.pop();
+            let mut bytes = Vec::new();
+            evals.serialize(&mut bytes).unwrap();
+            domain.serialize(&mut bytes).unwrap();
+            assert!(Evaluations::<F, D>::deserialize(&bytes[..]).is_err());
+        }
+
+        // Domains cannot have zero offsets.
+        let mut bytes = Vec::new();
+        (domain.size() as u64, F::zero())
+            .serialize(&mut bytes)
+            .unwrap();
+        assert!(D::deserialize(&bytes[..]).is_err());
+    }
+
+    test_serialize::<Fr, Radix2EvaluationDomain<Fr>>(5);
+    test_serialize::<Fr, GeneralEvaluationDomain<Fr>>(5);
+    test_serialize::<MNT6Fr, MixedRadixEvaluationDomain<MNT6Fr>>(4);
+
+    // A general domain that falls back to a mixed-radix domain.
+    let domain = GeneralEvaluationDomain::<MNT6Fr>::new(1 << 16).unwrap();
+    assert!(matches!(domain, GeneralEvaluationDomain::MixedRadix(_)));
+    let mut bytes = Vec::new();
+    domain.serialize(&mut bytes).unwrap();
+    assert_eq!(
+        GeneralEvaluationDomain::deserialize(&bytes[..]).unwrap(),
+        domain
+    );
+
+    // Sizes are not rounded up when deserializing.
+    let mut bytes = Vec::new();
+    (3u64, Fr::one()).serialize(&mut bytes).unwrap();
+    assert!(Radix2EvaluationDomain::<Fr>::deserialize(&bytes[..]).is_err());
+    let mut bytes = Vec::new();
+    (7u64, MNT6Fr::one()).serialize(&mut bytes).unwrap();
+    assert!(MixedRadixEvaluationDomain::<MNT6Fr>::deserialize(&bytes[..]).is_err());
+    let mut bytes = Vec::new();
+    (2u8, 4u64, Fr::one()).serialize(&mut bytes).unwrap();
+    assert!(GeneralEvaluationDomain::<Fr>::deserialize(&bytes[..]).is_err());
+}
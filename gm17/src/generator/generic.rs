@@ -4,7 +4,8 @@ use algebra_core::{
 use ff_fft::{cfg_into_iter, cfg_iter, EvaluationDomain};
 
 use r1cs_core::{
-    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination,
+    SynthesisError, Variable,
 };
 use rand::Rng;
 #[cfg(feature = "parallel")]
@@ -33,6 +34,28 @@ where
     generate_parameters::<E, C, D, R>(circuit, alpha, beta, gamma, g, h, rng)
 }
 
+/// Generates a random common reference string for a circuit whose named
+/// linear combinations are inlined, as in
+/// [`ConstraintMatrices::inline_linear_combinations`].
+pub fn generate_random_parameters_inlined<E, C, D, R>(
+    circuit: C,
+    rng: &mut R,
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let alpha = E::Fr::rand(rng);
+    let beta = E::Fr::rand(rng);
+    let gamma = E::Fr::one();
+    let g = E::G1Projective::rand(rng);
+    let h = E::G2Projective::rand(rng);
+
+    generate_parameters_inlined::<E, C, D, R>(circuit, alpha, beta, gamma, g, h, rng)
+}
+
 /// This is our assembly structure that we'll use to synthesize the
 /// circuit into a SAP.
 pub struct KeypairAssembly<E: PairingEngine> {
@@ -142,6 +165,14 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for KeypairAssembly<E> {
     fn num_constraints(&self) -> usize {
         self.num_constraints
     }
+
+    fn supports_parallel_synthesis(&self) -> bool {
+        true
+    }
+
+    fn is_in_setup_mode(&self) -> bool {
+        true
+    }
 }
 
 /// Create parameters for a circuit, given some toxic waste.
@@ -173,10 +204,70 @@ where
     assembly.alloc_input(|| "", || Ok(E::Fr::one()))?;
 
     // Synthesize the circuit.
+    #[cfg(feature = "namespace-paths")]
+    let circuit = r1cs_core::NamespacedCircuit(circuit);
     let synthesis_time = start_timer!(|| "Constraint synthesis");
     circuit.generate_constraints(&mut assembly)?;
     end_timer!(synthesis_time);
 
+    generate_parameters_for_assembly::<E, D, R>(assembly, false, alpha, beta, gamma, g, h, rng)
+}
+
+/// Create parameters for a circuit whose named linear combinations are
+/// inlined, given some toxic waste.
+pub fn generate_parameters_inlined<E, C, D, R>(
+    circuit: C,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    g: E::G1Projective,
+    h: E::G2Projective,
+    rng: &mut R,
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    // Synthesize the circuit.
+    #[cfg(feature = "namespace-paths")]
+    let circuit = r1cs_core::NamespacedCircuit(circuit);
+    let synthesis_time = start_timer!(|| "Constraint synthesis");
+    let mut matrices = ConstraintMatrices::synthesize_setup(circuit)?;
+    end_timer!(synthesis_time);
+
+    let inlining_time = start_timer!(|| "Inline linear combinations");
+    matrices.inline_linear_combinations();
+    end_timer!(inlining_time);
+
+    let assembly = KeypairAssembly {
+        num_inputs: matrices.num_inputs,
+        num_aux: matrices.num_aux,
+        num_constraints: matrices.a.len(),
+        at: matrices.a,
+        bt: matrices.b,
+        ct: matrices.c,
+    };
+    generate_parameters_for_assembly::<E, D, R>(assembly, true, alpha, beta, gamma, g, h, rng)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn generate_parameters_for_assembly<E, D, R>(
+    assembly: KeypairAssembly<E>,
+    inlined: bool,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    g: E::G1Projective,
+    h: E::G2Projective,
+    rng: &mut R,
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
     ///////////////////////////////////////////////////////////////////////////
     let domain_time = start_timer!(|| "Constructing evaluation domain");
 
@@ -336,5 +427,6 @@ where
         g_ab_gamma_z: g_ab_gamma_z.into_affine(),
         g_gamma2_z2: g_gamma2_z2.into_affine(),
         g_gamma2_z_t: g_gamma2_z_t.into_iter().map(Into::into).collect(),
+        inlined,
     })
 }
@@ -126,6 +126,11 @@ pub struct Parameters<E: PairingEngine> {
     pub g_ab_gamma_z: E::G1Affine,
     pub g_gamma2_z2: E::G1Affine,
     pub g_gamma2_z_t: Vec<E::G1Affine>,
+    /// Whether the parameters were generated for the circuit with its named
+    /// linear combinations inlined, by
+    /// [`generate_random_parameters_inlined`], so that proofs must be created
+    /// with [`create_random_proof_inlined`].
+    pub inlined: bool,
 }
 
 /// Preprocessed verification key parameters that enable faster verification
@@ -11,7 +11,8 @@ use crate::{r1cs_to_sap::R1CStoSAP, Parameters, Proof, String, Vec};
 use ff_fft::{cfg_into_iter, EvaluationDomain};
 
 use r1cs_core::{
-    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+    CheckedCircuit, ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, Index,
+    LinearCombination, SynthesisError, Variable, WitnessAssignment,
 };
 
 use smallvec::SmallVec;
@@ -37,11 +38,11 @@ fn eval<E: PairingEngine>(
             Index::Input(i) => {
                 constraints[this_constraint].push((coeff, Index::Input(i)));
                 tmp = input_assignment[i];
-            }
+            },
             Index::Aux(i) => {
                 constraints[this_constraint].push((coeff, Index::Aux(i)));
                 tmp = aux_assignment[i];
-            }
+            },
         }
 
         if coeff.is_one() {
@@ -176,6 +177,10 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for ProvingAssignment<E> {
     fn num_constraints(&self) -> usize {
         self.a.len()
     }
+
+    fn supports_parallel_synthesis(&self) -> bool {
+        true
+    }
 }
 
 pub fn create_random_proof<E, C, D, R>(
@@ -208,8 +213,10 @@ where
     C: ConstraintSynthesizer<E::Fr>,
     D: EvaluationDomain<E::Fr>,
 {
+    check_inlined(params, false)?;
+
     let prover_time = start_timer!(|| "Prover");
-    let mut prover = ProvingAssignment {
+    let mut prover = ProvingAssignment::<E> {
         at: vec![],
         bt: vec![],
         ct: vec![],
@@ -227,36 +234,264 @@ where
     prover.alloc_input(|| "", || Ok(E::Fr::one()))?;
 
     // Synthesize the circuit.
+    #[cfg(feature = "namespace-paths")]
+    let circuit = r1cs_core::NamespacedCircuit(circuit);
     let synthesis_time = start_timer!(|| "Constraint synthesis");
     circuit.generate_constraints(&mut prover)?;
     end_timer!(synthesis_time);
 
+    let proof = prove::<E, D, _>(
+        &prover.at,
+        &prover.bt,
+        &prover.ct,
+        &prover.input_assignment,
+        &prover.aux_assignment,
+        params,
+        d1,
+        d2,
+        r,
+    );
+
+    end_timer!(prover_time);
+
+    proof
+}
+
+pub fn create_random_proof_checked<E, C, D, R>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let d1 = E::Fr::rand(rng);
+    let d2 = E::Fr::rand(rng);
+    let r = E::Fr::rand(rng);
+
+    create_proof_checked::<E, C, D>(circuit, params, d1, d2, r)
+}
+
+/// Creates a proof like [`create_proof`], but checks that the assignment
+/// satisfies `circuit` while synthesizing it, and fails with
+/// `SynthesisError::Unsatisfiable`, with a report of the unsatisfied
+/// constraints attached as context, instead of producing a proof that does
+/// not verify.
+pub fn create_proof_checked<E, C, D>(
+    circuit: C,
+    params: &Parameters<E>,
+    d1: E::Fr,
+    d2: E::Fr,
+    r: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+{
+    create_proof::<E, _, D>(CheckedCircuit(circuit), params, d1, d2, r)
+}
+
+pub fn create_random_proof_inlined<E, C, D, R>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let d1 = E::Fr::rand(rng);
+    let d2 = E::Fr::rand(rng);
+    let r = E::Fr::rand(rng);
+
+    create_proof_inlined::<E, C, D>(circuit, params, d1, d2, r)
+}
+
+/// Creates a proof for `circuit` with its named linear combinations inlined,
+/// from parameters generated by
+/// [`generate_parameters_inlined`](crate::generate_parameters_inlined).
+///
+/// The circuit is synthesized into [`ConstraintMatrices`], which are
+/// inlined in place and proven directly.
+pub fn create_proof_inlined<E, C, D>(
+    circuit: C,
+    params: &Parameters<E>,
+    d1: E::Fr,
+    d2: E::Fr,
+    r: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+{
+    check_inlined(params, true)?;
+
+    let prover_time = start_timer!(|| "Prover");
+
+    // Synthesize the circuit.
+    #[cfg(feature = "namespace-paths")]
+    let circuit = r1cs_core::NamespacedCircuit(circuit);
+    let synthesis_time = start_timer!(|| "Constraint synthesis");
+    let mut matrices = ConstraintMatrices::synthesize(circuit)?;
+    end_timer!(synthesis_time);
+
+    let inlining_time = start_timer!(|| "Inline linear combinations");
+    matrices.inline_linear_combinations();
+    end_timer!(inlining_time);
+
+    let (input_assignment, aux_assignment) =
+        match (&matrices.input_assignment, &matrices.aux_assignment) {
+            (Some(input), Some(aux)) => (input, aux),
+            _ => return Err(SynthesisError::AssignmentMissing),
+        };
+    let proof = prove::<E, D, _>(
+        &matrices.a,
+        &matrices.b,
+        &matrices.c,
+        input_assignment,
+        aux_assignment,
+        params,
+        d1,
+        d2,
+        r,
+    );
+
+    end_timer!(prover_time);
+
+    proof
+}
+
+/// Returns an error unless `params` were generated with the named linear
+/// combinations of the circuit inlined exactly if `inlined` holds.
+fn check_inlined<E: PairingEngine>(
+    params: &Parameters<E>,
+    inlined: bool,
+) -> Result<(), SynthesisError> {
+    if params.inlined == inlined {
+        return Ok(());
+    }
+    let message = if params.inlined {
+        "the parameters were generated with inlined linear combinations"
+    } else {
+        "the parameters were generated without inlining linear combinations"
+    };
+    Err(SynthesisError::MalformedVerifyingKey.context(message))
+}
+
+pub fn create_random_proof_with_matrices<E, D, R>(
+    matrices: &ConstraintMatrices<E::Fr>,
+    witness: &WitnessAssignment<E::Fr>,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let d1 = E::Fr::rand(rng);
+    let d2 = E::Fr::rand(rng);
+    let r = E::Fr::rand(rng);
+
+    create_proof_with_matrices::<E, D>(matrices, witness, params, d1, d2, r)
+}
+
+/// Creates a proof for the constraints in `matrices` from the assignment in
+/// `witness`, which is usually generated with
+/// [`WitnessAssignment::generate`]. `matrices` are computed once, e.g., by
+/// synthesizing the circuit without an assignment into
+/// [`ConstraintMatrices`], and reused for every proof, so that the prover
+/// does not build the constraints again.
+///
+/// The assignment in `matrices`, if any, is ignored. Returns
+/// `SynthesisError::AssignmentMissing` if `witness` does not assign exactly
+/// the variables of `matrices`.
+pub fn create_proof_with_matrices<E, D>(
+    matrices: &ConstraintMatrices<E::Fr>,
+    witness: &WitnessAssignment<E::Fr>,
+    params: &Parameters<E>,
+    d1: E::Fr,
+    d2: E::Fr,
+    r: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    D: EvaluationDomain<E::Fr>,
+{
+    if !witness.fits(matrices) {
+        return Err(SynthesisError::AssignmentMissing);
+    }
+
+    let prover_time = start_timer!(|| "Prover");
+    let proof = prove::<E, D, _>(
+        &matrices.a,
+        &matrices.b,
+        &matrices.c,
+        &witness.input_assignment,
+        &witness.aux_assignment,
+        params,
+        d1,
+        d2,
+        r,
+    );
+    end_timer!(prover_time);
+
+    proof
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prove<E, D, R>(
+    at: &[R],
+    bt: &[R],
+    ct: &[R],
+    input_assignment: &[E::Fr],
+    aux_assignment: &[E::Fr],
+    params: &Parameters<E>,
+    d1: E::Fr,
+    d2: E::Fr,
+    r: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    D: EvaluationDomain<E::Fr>,
+    R: AsRef<[(E::Fr, Index)]> + Sync,
+{
+    let num_inputs = input_assignment.len();
+
     let witness_map_time = start_timer!(|| "R1CS to SAP witness map");
-    let (full_input_assignment, h, _) = R1CStoSAP::witness_map::<E, D>(&prover, &d1, &d2)?;
+    let (full_input_assignment, h, _) =
+        R1CStoSAP::witness_map::<E, D, R>(at, bt, ct, input_assignment, aux_assignment, &d1, &d2)?;
     end_timer!(witness_map_time);
 
-    let input_assignment = full_input_assignment[1..prover.num_inputs]
+    let input_assignment = full_input_assignment[1..num_inputs]
         .iter()
         .map(|s| s.into_repr())
         .collect::<Vec<_>>();
 
-    let aux_assignment = cfg_into_iter!(full_input_assignment[prover.num_inputs..])
+    let aux_assignment = cfg_into_iter!(full_input_assignment[num_inputs..])
         .map(|s| s.into_repr())
         .collect::<Vec<_>>();
     drop(full_input_assignment);
 
-    let h_input = h[0..prover.num_inputs]
+    let h_input = h[0..num_inputs]
         .iter()
         .map(|s| s.into_repr())
         .collect::<Vec<_>>();
-    let h_aux = cfg_into_iter!(h[prover.num_inputs..])
+    let h_aux = cfg_into_iter!(h[num_inputs..])
         .map(|s| s.into_repr())
         .collect::<Vec<_>>();
     drop(h);
 
     // Compute A
     let a_acc_time = start_timer!(|| "Compute A");
-    let (a_inputs_source, a_aux_source) = params.get_a_query(prover.num_inputs)?;
+    let (a_inputs_source, a_aux_source) = params.get_a_query(num_inputs)?;
     let a_inputs_acc = VariableBaseMSM::multi_scalar_mul(a_inputs_source, &input_assignment);
     let a_aux_acc = VariableBaseMSM::multi_scalar_mul(a_aux_source, &aux_assignment);
 
@@ -273,7 +508,7 @@ where
     // Compute B
     let b_acc_time = start_timer!(|| "Compute B");
 
-    let (b_inputs_source, b_aux_source) = params.get_b_query(prover.num_inputs)?;
+    let (b_inputs_source, b_aux_source) = params.get_b_query(num_inputs)?;
     let b_inputs_acc = VariableBaseMSM::multi_scalar_mul(b_inputs_source, &input_assignment);
     let b_aux_acc = VariableBaseMSM::multi_scalar_mul(b_aux_source, &aux_assignment);
 
@@ -300,7 +535,7 @@ where
 
     let c2_acc_time = start_timer!(|| "Compute C2");
 
-    let (c2_inputs_source, c2_aux_source) = params.get_c_query_2(prover.num_inputs)?;
+    let (c2_inputs_source, c2_aux_source) = params.get_c_query_2(num_inputs)?;
     let c2_inputs_acc = VariableBaseMSM::multi_scalar_mul(c2_inputs_source, &input_assignment);
     let c2_aux_acc = VariableBaseMSM::multi_scalar_mul(c2_aux_source, &aux_assignment);
 
@@ -310,7 +545,7 @@ where
     // Compute G
     let g_acc_time = start_timer!(|| "Compute G");
 
-    let (g_inputs_source, g_aux_source) = params.get_g_gamma2_z_t(prover.num_inputs)?;
+    let (g_inputs_source, g_aux_source) = params.get_g_gamma2_z_t(num_inputs)?;
     let g_inputs_acc = VariableBaseMSM::multi_scalar_mul(g_inputs_source, &h_input);
     let g_aux_acc = VariableBaseMSM::multi_scalar_mul(g_aux_source, &h_aux);
 
@@ -337,8 +572,6 @@ where
     g_c.add_assign(&g_acc);
     end_timer!(c_acc_time);
 
-    end_timer!(prover_time);
-
     Ok(Proof {
         a: g_a.into_affine(),
         b: g_b.into_affine(),
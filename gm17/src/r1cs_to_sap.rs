@@ -1,7 +1,7 @@
 use algebra_core::{Field, One, PairingEngine, Zero};
 use ff_fft::{cfg_chunks_mut, cfg_iter, cfg_iter_mut, EvaluationDomain};
 
-use crate::{generator::KeypairAssembly, prover::ProvingAssignment, Vec};
+use crate::{generator::KeypairAssembly, Vec};
 use r1cs_core::{Index, SynthesisError};
 
 use core::ops::{AddAssign, SubAssign};
@@ -97,11 +97,20 @@ impl R1CStoSAP {
     }
 
     #[inline]
-    pub(crate) fn witness_map<E: PairingEngine, D: EvaluationDomain<E::Fr>>(
-        prover: &ProvingAssignment<E>,
+    pub(crate) fn witness_map<E, D, R>(
+        at: &[R],
+        bt: &[R],
+        ct: &[R],
+        input_assignment: &[E::Fr],
+        aux_assignment: &[E::Fr],
         d1: &E::Fr,
         d2: &E::Fr,
-    ) -> Result<(Vec<E::Fr>, Vec<E::Fr>, usize), SynthesisError> {
+    ) -> Result<(Vec<E::Fr>, Vec<E::Fr>, usize), SynthesisError>
+    where
+        E: PairingEngine,
+        D: EvaluationDomain<E::Fr>,
+        R: AsRef<[(E::Fr, Index)]> + Sync,
+    {
         #[inline]
         fn evaluate_constraint<E: PairingEngine>(
             terms: &[(E::Fr, Index)],
@@ -122,18 +131,22 @@ impl R1CStoSAP {
         let zero = E::Fr::zero();
         let one = E::Fr::one();
 
-        let mut full_input_assignment = prover.input_assignment.clone();
-        full_input_assignment.extend(prover.aux_assignment.clone());
+        let num_inputs = input_assignment.len();
+        let num_aux = aux_assignment.len();
+        let num_constraints = at.len();
 
-        let temp = cfg_iter!(prover.at)
-            .zip(&prover.bt)
+        let mut full_input_assignment = input_assignment.to_vec();
+        full_input_assignment.extend_from_slice(aux_assignment);
+
+        let temp = cfg_iter!(at)
+            .zip(bt)
             .map(|(a_i, b_i)| {
                 let mut extra_var: E::Fr =
-                    evaluate_constraint::<E>(&a_i, &full_input_assignment, prover.num_inputs);
+                    evaluate_constraint::<E>(a_i.as_ref(), &full_input_assignment, num_inputs);
                 extra_var.sub_assign(&evaluate_constraint::<E>(
-                    &b_i,
+                    b_i.as_ref(),
                     &full_input_assignment,
-                    prover.num_inputs,
+                    num_inputs,
                 ));
                 extra_var.square_in_place();
                 extra_var
@@ -141,44 +154,44 @@ impl R1CStoSAP {
             .collect::<Vec<_>>();
         full_input_assignment.extend(temp);
 
-        for i in 1..prover.num_inputs {
+        for i in 1..num_inputs {
             let mut extra_var = full_input_assignment[i];
             extra_var.sub_assign(&one);
             extra_var.square_in_place();
             full_input_assignment.push(extra_var);
         }
 
-        let domain = D::new(2 * prover.num_constraints + 2 * (prover.num_inputs - 1) + 1)
+        let domain = D::new(2 * num_constraints + 2 * (num_inputs - 1) + 1)
             .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
         let domain_size = domain.size();
 
-        let extra_constr_offset = 2 * prover.num_constraints;
-        let extra_var_offset = prover.num_inputs + prover.num_aux;
-        let extra_var_offset2 = prover.num_inputs + prover.num_aux + prover.num_constraints - 1;
+        let extra_constr_offset = 2 * num_constraints;
+        let extra_var_offset = num_inputs + num_aux;
+        let extra_var_offset2 = num_inputs + num_aux + num_constraints - 1;
 
         let mut a = vec![zero; domain_size];
-        cfg_chunks_mut!(a[..2 * prover.num_constraints], 2)
-            .zip(&prover.at)
-            .zip(&prover.bt)
+        cfg_chunks_mut!(a[..2 * num_constraints], 2)
+            .zip(at)
+            .zip(bt)
             .for_each(|((chunk, at_i), bt_i)| {
                 chunk[0] =
-                    evaluate_constraint::<E>(&at_i, &full_input_assignment, prover.num_inputs);
+                    evaluate_constraint::<E>(at_i.as_ref(), &full_input_assignment, num_inputs);
                 chunk[0].add_assign(&evaluate_constraint::<E>(
-                    &bt_i,
+                    bt_i.as_ref(),
                     &full_input_assignment,
-                    prover.num_inputs,
+                    num_inputs,
                 ));
 
                 chunk[1] =
-                    evaluate_constraint::<E>(&at_i, &full_input_assignment, prover.num_inputs);
+                    evaluate_constraint::<E>(at_i.as_ref(), &full_input_assignment, num_inputs);
                 chunk[1].sub_assign(&evaluate_constraint::<E>(
-                    &bt_i,
+                    bt_i.as_ref(),
                     &full_input_assignment,
-                    prover.num_inputs,
+                    num_inputs,
                 ));
             });
         a[extra_constr_offset] = one;
-        for i in 1..prover.num_inputs {
+        for i in 1..num_inputs {
             a[extra_constr_offset + 2 * i - 1] = full_input_assignment[i] + &one;
             a[extra_constr_offset + 2 * i] = full_input_assignment[i] - &one;
         }
@@ -199,14 +212,11 @@ impl R1CStoSAP {
         drop(a);
 
         let mut c = vec![zero; domain_size];
-        cfg_chunks_mut!(c[..2 * prover.num_constraints], 2)
+        cfg_chunks_mut!(c[..2 * num_constraints], 2)
             .enumerate()
             .for_each(|(i, chunk)| {
-                let mut tmp: E::Fr = evaluate_constraint::<E>(
-                    &prover.ct[i],
-                    &full_input_assignment,
-                    prover.num_inputs,
-                );
+                let mut tmp: E::Fr =
+                    evaluate_constraint::<E>(ct[i].as_ref(), &full_input_assignment, num_inputs);
                 tmp.double_in_place();
                 tmp.double_in_place();
 
@@ -215,7 +225,7 @@ impl R1CStoSAP {
                 chunk[1] = assignment;
             });
         c[extra_constr_offset] = one;
-        for i in 1..prover.num_inputs {
+        for i in 1..num_inputs {
             let mut tmp = full_input_assignment[i];
             tmp.double_in_place();
             tmp.double_in_place();
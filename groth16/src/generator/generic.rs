@@ -5,7 +5,8 @@ use algebra_core::{
 use ff_fft::{cfg_into_iter, cfg_iter, EvaluationDomain};
 
 use r1cs_core::{
-    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination,
+    SynthesisError, Variable,
 };
 use rand::Rng;
 
@@ -34,6 +35,27 @@ where
     generate_parameters::<E, C, D, R>(circuit, alpha, beta, gamma, delta, rng)
 }
 
+/// Generates a random common reference string for a circuit whose named
+/// linear combinations are inlined, as in
+/// [`ConstraintMatrices::inline_linear_combinations`].
+pub fn generate_random_parameters_inlined<E, C, D, R>(
+    circuit: C,
+    rng: &mut R,
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let alpha = E::Fr::rand(rng);
+    let beta = E::Fr::rand(rng);
+    let gamma = E::Fr::rand(rng);
+    let delta = E::Fr::rand(rng);
+
+    generate_parameters_inlined::<E, C, D, R>(circuit, alpha, beta, gamma, delta, rng)
+}
+
 /// This is our assembly structure that we'll use to synthesize the
 /// circuit into a QAP.
 #[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
@@ -131,6 +153,14 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for KeypairAssembly<E> {
     fn num_constraints(&self) -> usize {
         self.num_constraints
     }
+
+    fn supports_parallel_synthesis(&self) -> bool {
+        true
+    }
+
+    fn is_in_setup_mode(&self) -> bool {
+        true
+    }
 }
 
 /// Create parameters for a circuit, given some toxic waste.
@@ -161,10 +191,67 @@ where
     assembly.alloc_input(|| "", || Ok(E::Fr::one()))?;
 
     // Synthesize the circuit.
+    #[cfg(feature = "namespace-paths")]
+    let circuit = r1cs_core::NamespacedCircuit(circuit);
     let synthesis_time = start_timer!(|| "Constraint synthesis");
     circuit.generate_constraints(&mut assembly)?;
     end_timer!(synthesis_time);
 
+    generate_parameters_for_assembly::<E, D, R>(assembly, false, alpha, beta, gamma, delta, rng)
+}
+
+/// Create parameters for a circuit whose named linear combinations are
+/// inlined, given some toxic waste.
+pub fn generate_parameters_inlined<E, C, D, R>(
+    circuit: C,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    delta: E::Fr,
+    rng: &mut R,
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    // Synthesize the circuit.
+    #[cfg(feature = "namespace-paths")]
+    let circuit = r1cs_core::NamespacedCircuit(circuit);
+    let synthesis_time = start_timer!(|| "Constraint synthesis");
+    let mut matrices = ConstraintMatrices::synthesize_setup(circuit)?;
+    end_timer!(synthesis_time);
+
+    let inlining_time = start_timer!(|| "Inline linear combinations");
+    matrices.inline_linear_combinations();
+    end_timer!(inlining_time);
+
+    let assembly = KeypairAssembly {
+        num_inputs: matrices.num_inputs,
+        num_aux: matrices.num_aux,
+        num_constraints: matrices.a.len(),
+        at: matrices.a,
+        bt: matrices.b,
+        ct: matrices.c,
+    };
+    generate_parameters_for_assembly::<E, D, R>(assembly, true, alpha, beta, gamma, delta, rng)
+}
+
+fn generate_parameters_for_assembly<E, D, R>(
+    assembly: KeypairAssembly<E>,
+    inlined: bool,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    delta: E::Fr,
+    rng: &mut R,
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
     ///////////////////////////////////////////////////////////////////////////
     let domain_time = start_timer!(|| "Constructing evaluation domain");
 
@@ -314,5 +401,6 @@ where
         b_g2_query: b_g2_query.into_iter().map(Into::into).collect(),
         h_query: h_query.into_iter().map(Into::into).collect(),
         l_query: l_query.into_iter().map(Into::into).collect(),
+        inlined,
     })
 }
@@ -25,6 +25,23 @@ where
     )
 }
 
+/// Generates a random common reference string for a circuit whose named
+/// linear combinations are inlined.
+#[inline]
+pub fn generate_random_parameters_inlined<E, C, R>(
+    circuit: C,
+    rng: &mut R,
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    self::generic::generate_random_parameters_inlined::<E, C, GeneralEvaluationDomain<E::Fr>, R>(
+        circuit, rng,
+    )
+}
+
 /// Create parameters for a circuit, given some toxic waste.
 #[inline]
 pub fn generate_parameters<E, C, R>(
@@ -44,3 +61,24 @@ where
         circuit, alpha, beta, gamma, delta, rng,
     )
 }
+
+/// Create parameters for a circuit whose named linear combinations are
+/// inlined, given some toxic waste.
+#[inline]
+pub fn generate_parameters_inlined<E, C, R>(
+    circuit: C,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    delta: E::Fr,
+    rng: &mut R,
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    self::generic::generate_parameters_inlined::<E, C, GeneralEvaluationDomain<E::Fr>, R>(
+        circuit, alpha, beta, gamma, delta, rng,
+    )
+}
@@ -121,6 +121,11 @@ pub struct Parameters<E: PairingEngine> {
     pub b_g2_query: Vec<E::G2Affine>,
     pub h_query: Vec<E::G1Affine>,
     pub l_query: Vec<E::G1Affine>,
+    /// Whether the parameters were generated for the circuit with its named
+    /// linear combinations inlined, by
+    /// [`generate_random_parameters_inlined`], so that proofs must be created
+    /// with [`create_random_proof_inlined`].
+    pub inlined: bool,
 }
 
 /// Preprocessed verification key parameters that enable faster verification
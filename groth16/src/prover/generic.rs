@@ -8,10 +8,11 @@ use algebra_core::{
 use crate::{push_constraints, r1cs_to_qap::R1CStoQAP, Parameters, Proof, String, Vec};
 
 use r1cs_core::{
-    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+    CheckedCircuit, ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, Index,
+    LinearCombination, SynthesisError, Variable, WitnessAssignment,
 };
 
-use ff_fft::{cfg_into_iter, EvaluationDomain};
+use ff_fft::{cfg_into_iter, cfg_iter, EvaluationDomain};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -95,6 +96,10 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for ProvingAssignment<E> {
     fn num_constraints(&self) -> usize {
         self.at.len()
     }
+
+    fn supports_parallel_synthesis(&self) -> bool {
+        true
+    }
 }
 
 pub fn create_random_proof<E, C, D, R>(
@@ -137,8 +142,10 @@ where
     C: ConstraintSynthesizer<E::Fr>,
     D: EvaluationDomain<E::Fr>,
 {
+    check_inlined(params, false)?;
+
     let prover_time = start_timer!(|| "Prover");
-    let mut prover = ProvingAssignment {
+    let mut prover = ProvingAssignment::<E> {
         at: vec![],
         bt: vec![],
         ct: vec![],
@@ -150,20 +157,234 @@ where
     prover.alloc_input(|| "", || Ok(E::Fr::one()))?;
 
     // Synthesize the circuit.
+    #[cfg(feature = "namespace-paths")]
+    let circuit = r1cs_core::NamespacedCircuit(circuit);
     let synthesis_time = start_timer!(|| "Constraint synthesis");
     circuit.generate_constraints(&mut prover)?;
     end_timer!(synthesis_time);
 
+    let proof = prove::<E, D>(
+        &prover.at,
+        &prover.bt,
+        &prover.ct,
+        &prover.input_assignment,
+        &prover.aux_assignment,
+        params,
+        r,
+        s,
+    );
+
+    end_timer!(prover_time);
+
+    proof
+}
+
+pub fn create_random_proof_checked<E, C, D, R>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let r = E::Fr::rand(rng);
+    let s = E::Fr::rand(rng);
+
+    create_proof_checked::<E, C, D>(circuit, params, r, s)
+}
+
+/// Creates a proof like [`create_proof`], but checks that the assignment
+/// satisfies `circuit` while synthesizing it, and fails with
+/// `SynthesisError::Unsatisfiable`, with a report of the unsatisfied
+/// constraints attached as context, instead of producing a proof that does
+/// not verify.
+pub fn create_proof_checked<E, C, D>(
+    circuit: C,
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+{
+    create_proof::<E, _, D>(CheckedCircuit(circuit), params, r, s)
+}
+
+pub fn create_random_proof_inlined<E, C, D, R>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let r = E::Fr::rand(rng);
+    let s = E::Fr::rand(rng);
+
+    create_proof_inlined::<E, C, D>(circuit, params, r, s)
+}
+
+/// Creates a proof for `circuit` with its named linear combinations inlined,
+/// from parameters generated by
+/// [`generate_parameters_inlined`](crate::generate_parameters_inlined).
+///
+/// The circuit is synthesized into [`ConstraintMatrices`], which are
+/// inlined in place and proven directly.
+pub fn create_proof_inlined<E, C, D>(
+    circuit: C,
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+{
+    check_inlined(params, true)?;
+
+    let prover_time = start_timer!(|| "Prover");
+
+    // Synthesize the circuit.
+    #[cfg(feature = "namespace-paths")]
+    let circuit = r1cs_core::NamespacedCircuit(circuit);
+    let synthesis_time = start_timer!(|| "Constraint synthesis");
+    let mut matrices = ConstraintMatrices::synthesize(circuit)?;
+    end_timer!(synthesis_time);
+
+    let inlining_time = start_timer!(|| "Inline linear combinations");
+    matrices.inline_linear_combinations();
+    end_timer!(inlining_time);
+
+    let (input_assignment, aux_assignment) =
+        match (&matrices.input_assignment, &matrices.aux_assignment) {
+            (Some(input), Some(aux)) => (input, aux),
+            _ => return Err(SynthesisError::AssignmentMissing),
+        };
+    let proof = prove::<E, D>(
+        &matrices.a,
+        &matrices.b,
+        &matrices.c,
+        input_assignment,
+        aux_assignment,
+        params,
+        r,
+        s,
+    );
+
+    end_timer!(prover_time);
+
+    proof
+}
+
+/// Returns an error unless `params` were generated with the named linear
+/// combinations of the circuit inlined exactly if `inlined` holds.
+fn check_inlined<E: PairingEngine>(
+    params: &Parameters<E>,
+    inlined: bool,
+) -> Result<(), SynthesisError> {
+    if params.inlined == inlined {
+        return Ok(());
+    }
+    let message = if params.inlined {
+        "the parameters were generated with inlined linear combinations"
+    } else {
+        "the parameters were generated without inlining linear combinations"
+    };
+    Err(SynthesisError::MalformedVerifyingKey.context(message))
+}
+
+pub fn create_random_proof_with_matrices<E, D, R>(
+    matrices: &ConstraintMatrices<E::Fr>,
+    witness: &WitnessAssignment<E::Fr>,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let r = E::Fr::rand(rng);
+    let s = E::Fr::rand(rng);
+
+    create_proof_with_matrices::<E, D>(matrices, witness, params, r, s)
+}
+
+/// Creates a proof for the constraints in `matrices` from the assignment in
+/// `witness`, which is usually generated with
+/// [`WitnessAssignment::generate`]. `matrices` are computed once, e.g., by
+/// synthesizing the circuit without an assignment into
+/// [`ConstraintMatrices`], and reused for every proof, so that the prover
+/// does not build the constraints again.
+///
+/// The assignment in `matrices`, if any, is ignored. Returns
+/// `SynthesisError::AssignmentMissing` if `witness` does not assign exactly
+/// the variables of `matrices`.
+pub fn create_proof_with_matrices<E, D>(
+    matrices: &ConstraintMatrices<E::Fr>,
+    witness: &WitnessAssignment<E::Fr>,
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    D: EvaluationDomain<E::Fr>,
+{
+    if !witness.fits(matrices) {
+        return Err(SynthesisError::AssignmentMissing);
+    }
+
+    let prover_time = start_timer!(|| "Prover");
+    let proof = prove::<E, D>(
+        &matrices.a,
+        &matrices.b,
+        &matrices.c,
+        &witness.input_assignment,
+        &witness.aux_assignment,
+        params,
+        r,
+        s,
+    );
+    end_timer!(prover_time);
+
+    proof
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prove<E, D>(
+    at: &[Vec<(E::Fr, Index)>],
+    bt: &[Vec<(E::Fr, Index)>],
+    ct: &[Vec<(E::Fr, Index)>],
+    input_assignment: &[E::Fr],
+    aux_assignment: &[E::Fr],
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    D: EvaluationDomain<E::Fr>,
+{
     let witness_map_time = start_timer!(|| "R1CS to QAP witness map");
-    let h = R1CStoQAP::witness_map::<E, D>(&prover)?;
+    let h = R1CStoQAP::witness_map::<E, D>(at, bt, ct, input_assignment, aux_assignment)?;
     end_timer!(witness_map_time);
 
-    let input_assignment = prover.input_assignment[1..]
-        .into_iter()
+    let input_assignment = input_assignment[1..]
+        .iter()
         .map(|s| s.into_repr())
         .collect::<Vec<_>>();
 
-    let aux_assignment = cfg_into_iter!(prover.aux_assignment)
+    let aux_assignment = cfg_iter!(aux_assignment)
         .map(|s| s.into_repr())
         .collect::<Vec<_>>();
 
@@ -223,8 +444,6 @@ where
     g_c += &h_acc;
     end_timer!(c_acc_time);
 
-    end_timer!(prover_time);
-
     Ok(Proof {
         a: g_a.into_affine(),
         b: g2_b.into_affine(),
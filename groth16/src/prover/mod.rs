@@ -1,7 +1,7 @@
 use crate::{Parameters, Proof};
 use algebra_core::PairingEngine;
 use ff_fft::GeneralEvaluationDomain;
-use r1cs_core::{ConstraintSynthesizer, SynthesisError};
+use r1cs_core::{ConstraintMatrices, ConstraintSynthesizer, SynthesisError, WitnessAssignment};
 use rand::Rng;
 
 pub mod generic;
@@ -49,3 +49,99 @@ where
 {
     self::generic::create_proof::<E, C, GeneralEvaluationDomain<E::Fr>>(circuit, params, r, s)
 }
+
+#[inline]
+pub fn create_random_proof_checked<E, C, R>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    self::generic::create_random_proof_checked::<E, C, GeneralEvaluationDomain<E::Fr>, R>(
+        circuit, params, rng,
+    )
+}
+
+#[inline]
+pub fn create_proof_checked<E, C>(
+    circuit: C,
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    self::generic::create_proof_checked::<E, C, GeneralEvaluationDomain<E::Fr>>(
+        circuit, params, r, s,
+    )
+}
+
+#[inline]
+pub fn create_random_proof_inlined<E, C, R>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    self::generic::create_random_proof_inlined::<E, C, GeneralEvaluationDomain<E::Fr>, R>(
+        circuit, params, rng,
+    )
+}
+
+#[inline]
+pub fn create_proof_inlined<E, C>(
+    circuit: C,
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    self::generic::create_proof_inlined::<E, C, GeneralEvaluationDomain<E::Fr>>(
+        circuit, params, r, s,
+    )
+}
+
+#[inline]
+pub fn create_random_proof_with_matrices<E, R>(
+    matrices: &ConstraintMatrices<E::Fr>,
+    witness: &WitnessAssignment<E::Fr>,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    R: Rng,
+{
+    self::generic::create_random_proof_with_matrices::<E, GeneralEvaluationDomain<E::Fr>, R>(
+        matrices, witness, params, rng,
+    )
+}
+
+#[inline]
+pub fn create_proof_with_matrices<E>(
+    matrices: &ConstraintMatrices<E::Fr>,
+    witness: &WitnessAssignment<E::Fr>,
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+{
+    self::generic::create_proof_with_matrices::<E, GeneralEvaluationDomain<E::Fr>>(
+        matrices, witness, params, r, s,
+    )
+}
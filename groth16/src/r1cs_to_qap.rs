@@ -1,9 +1,9 @@
 use algebra_core::{One, PairingEngine, Zero};
-use ff_fft::{cfg_iter, cfg_iter_mut, EvaluationDomain};
+use ff_fft::{cfg_iter, cfg_iter_mut, EvaluationDomain, FFTPlan};
 
-use crate::{generator::KeypairAssembly, prover::ProvingAssignment, Vec};
+use crate::{generator::KeypairAssembly, Vec};
 use core::ops::AddAssign;
-use r1cs_core::{ConstraintSystem, Index, SynthesisError};
+use r1cs_core::{Index, SynthesisError};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -108,14 +108,17 @@ impl R1CStoQAP {
 
     #[inline]
     pub(crate) fn witness_map<E: PairingEngine, D: EvaluationDomain<E::Fr>>(
-        prover: &ProvingAssignment<E>,
+        at: &[Vec<(E::Fr, Index)>],
+        bt: &[Vec<(E::Fr, Index)>],
+        ct: &[Vec<(E::Fr, Index)>],
+        input_assignment: &[E::Fr],
+        aux_assignment: &[E::Fr],
     ) -> Result<Vec<E::Fr>, SynthesisError> {
         let zero = E::Fr::zero();
-        let num_inputs = prover.input_assignment.len();
-        let num_constraints = prover.num_constraints();
+        let num_inputs = input_assignment.len();
+        let num_constraints = at.len();
 
-        let full_input_assignment =
-            [&prover.input_assignment[..], &prover.aux_assignment[..]].concat();
+        let full_input_assignment = [input_assignment, aux_assignment].concat();
 
         let domain =
             D::new(num_constraints + num_inputs).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
@@ -126,8 +129,8 @@ impl R1CStoQAP {
 
         cfg_iter_mut!(a[..num_constraints])
             .zip(cfg_iter_mut!(b[..num_constraints]))
-            .zip(cfg_iter!(&prover.at))
-            .zip(cfg_iter!(&prover.bt))
+            .zip(cfg_iter!(at))
+            .zip(cfg_iter!(bt))
             .for_each(|(((a, b), at_i), bt_i)| {
                 *a = evaluate_constraint(&at_i, &full_input_assignment, num_inputs);
                 *b = evaluate_constraint(&bt_i, &full_input_assignment, num_inputs);
@@ -137,32 +140,30 @@ impl R1CStoQAP {
             a[num_constraints + i] = full_input_assignment[i];
         }
 
-        domain.ifft_in_place(&mut a);
-        domain.ifft_in_place(&mut b);
-
-        domain.coset_fft_in_place(&mut a);
-        domain.coset_fft_in_place(&mut b);
-
-        let mut ab = domain.mul_polynomials_in_evaluation_domain(&a, &b);
-        drop(a);
-        drop(b);
-
         let mut c = vec![zero; domain_size];
-        cfg_iter_mut!(c[..prover.num_constraints()])
+        cfg_iter_mut!(c[..num_constraints])
             .enumerate()
             .for_each(|(i, c)| {
-                *c = evaluate_constraint(&prover.ct[i], &full_input_assignment, num_inputs);
+                *c = evaluate_constraint(&ct[i], &full_input_assignment, num_inputs);
             });
 
-        domain.ifft_in_place(&mut c);
-        domain.coset_fft_in_place(&mut c);
+        // All seven FFTs below share the twiddle factors of `plan`.
+        let plan = FFTPlan::new(domain);
+        let mut abc = [a, b, c];
+        plan.ifft_batch_in_place(&mut abc);
+        plan.coset_fft_batch_in_place(&mut abc);
+        let [mut ab, b, c] = abc;
 
         cfg_iter_mut!(ab)
+            .zip(b)
             .zip(c)
-            .for_each(|(ab_i, c_i)| *ab_i -= &c_i);
+            .for_each(|((ab_i, b_i), c_i)| {
+                *ab_i *= &b_i;
+                *ab_i -= &c_i;
+            });
 
         domain.divide_by_vanishing_poly_on_coset_in_place(&mut ab);
-        domain.coset_ifft_in_place(&mut ab);
+        plan.coset_ifft_in_place(&mut ab);
 
         Ok(ab)
     }
@@ -1,5 +1,8 @@
 use algebra_core::Field;
-use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+use r1cs_core::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, SynthesisError, Variable,
+    WitnessAssignment,
+};
 struct MySillyCircuit<F: Field> {
     a: Option<F>,
     b: Option<F>,
@@ -34,14 +37,128 @@ impl<ConstraintF: Field> ConstraintSynthesizer<ConstraintF> for MySillyCircuit<C
     }
 }
 
+/// Proves knowledge of `a` and `b` such that `(a + b) * b = c`, naming `a + b`
+/// with a constraint of the form `lc * 1 = var`.
+struct NamedSumCircuit<F: Field> {
+    a: Option<F>,
+    b: Option<F>,
+}
+
+impl<ConstraintF: Field> ConstraintSynthesizer<ConstraintF> for NamedSumCircuit<ConstraintF> {
+    fn generate_constraints<CS: ConstraintSystem<ConstraintF>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let sum_val = self.a.and_then(|a| self.b.map(|b| a + &b));
+        let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+        let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+        let sum = cs.alloc(
+            || "a + b",
+            || sum_val.ok_or(SynthesisError::AssignmentMissing),
+        )?;
+        let c = cs.alloc_input(
+            || "c",
+            || {
+                let sum = sum_val.ok_or(SynthesisError::AssignmentMissing)?;
+                let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
+                Ok(sum * &b)
+            },
+        )?;
+
+        cs.enforce(
+            || "a + b",
+            |lc| lc + a + b,
+            |lc| lc + CS::one(),
+            |lc| lc + sum,
+        );
+        cs.enforce(
+            || "(a + b) * b = c",
+            |lc| lc + sum,
+            |lc| lc + b,
+            |lc| lc + c,
+        );
+
+        Ok(())
+    }
+}
+
+/// Proves knowledge of `a` and `b` such that `a * b = c`, but assigns `a + b`
+/// to `c`.
+struct WrongProductCircuit<F: Field> {
+    a: Option<F>,
+    b: Option<F>,
+}
+
+impl<ConstraintF: Field> ConstraintSynthesizer<ConstraintF> for WrongProductCircuit<ConstraintF> {
+    fn generate_constraints<CS: ConstraintSystem<ConstraintF>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let c_val = self.a.and_then(|a| self.b.map(|b| a + &b));
+        let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+        let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+        let c = cs.alloc_input(|| "c", || c_val.ok_or(SynthesisError::AssignmentMissing))?;
+
+        cs.enforce(|| "a * b = c", |lc| lc + a, |lc| lc + b, |lc| lc + c);
+
+        Ok(())
+    }
+}
+
+/// Proves knowledge of `b_i` such that `a * b_i = c_i` for each `i`, checking
+/// each product in its own child constraint system.
+struct ParallelProductsCircuit<F: Field> {
+    a: Option<F>,
+    bs: Vec<Option<F>>,
+}
+
+struct ProductCircuit<F: Field> {
+    a: Variable,
+    a_val: Option<F>,
+    b: Option<F>,
+}
+
+impl<ConstraintF: Field> ConstraintSynthesizer<ConstraintF> for ProductCircuit<ConstraintF> {
+    fn generate_constraints<CS: ConstraintSystem<ConstraintF>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let c_val = self.a_val.and_then(|a| self.b.map(|b| a * &b));
+        let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+        let c = cs.alloc_input(|| "c", || c_val.ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce(|| "a * b = c", |lc| lc + self.a, |lc| lc + b, |lc| lc + c);
+        Ok(())
+    }
+}
+
+impl<ConstraintF: Field> ConstraintSynthesizer<ConstraintF>
+    for ParallelProductsCircuit<ConstraintF>
+{
+    fn generate_constraints<CS: ConstraintSystem<ConstraintF>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let a_val = self.a;
+        let a = cs.alloc(|| "a", || a_val.ok_or(SynthesisError::AssignmentMissing))?;
+        let children = self
+            .bs
+            .into_iter()
+            .map(|b| ProductCircuit { a, a_val, b })
+            .collect();
+        cs.synthesize_in_parallel(|| "products", children)
+    }
+}
+
 mod bls12_377 {
     use super::*;
     use crate::{
-        create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+        create_random_proof, create_random_proof_checked, create_random_proof_inlined,
+        create_random_proof_with_matrices, generate_random_parameters,
+        generate_random_parameters_inlined, prepare_verifying_key, verify_proof,
     };
 
     use algebra::bls12_377::{Bls12_377, Fr};
-    use algebra_core::{test_rng, UniformRand};
+    use algebra_core::{test_rng, One, UniformRand};
     use core::ops::MulAssign;
 
     #[test]
@@ -74,6 +191,147 @@ mod bls12_377 {
             assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
         }
     }
+
+    #[test]
+    fn prove_and_verify_inlined() {
+        let rng = &mut test_rng();
+
+        let params = generate_random_parameters::<Bls12_377, _, _>(
+            NamedSumCircuit { a: None, b: None },
+            rng,
+        )
+        .unwrap();
+        let inlined_params = generate_random_parameters_inlined::<Bls12_377, _, _>(
+            NamedSumCircuit { a: None, b: None },
+            rng,
+        )
+        .unwrap();
+        assert!(inlined_params.inlined);
+        assert_eq!(inlined_params.l_query.len() + 1, params.l_query.len());
+        assert_eq!(inlined_params.a_query.len() + 1, params.a_query.len());
+
+        let pvk = prepare_verifying_key::<Bls12_377>(&inlined_params.vk);
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let circuit = || NamedSumCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let proof = create_random_proof_inlined(circuit(), &inlined_params, rng).unwrap();
+
+        assert!(verify_proof(&pvk, &proof, &[(a + &b) * &b]).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+
+        assert!(create_random_proof(circuit(), &inlined_params, rng).is_err());
+        assert!(create_random_proof_inlined(circuit(), &params, rng).is_err());
+    }
+
+    #[test]
+    fn prove_and_verify_checked() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key::<Bls12_377>(&params.vk);
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let proof = create_random_proof_checked(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+        )
+        .unwrap();
+
+        assert!(verify_proof(&pvk, &proof, &[a * &b]).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+
+        let params = generate_random_parameters::<Bls12_377, _, _>(
+            WrongProductCircuit { a: None, b: None },
+            rng,
+        )
+        .unwrap();
+        let error = create_random_proof_checked(
+            WrongProductCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+        )
+        .unwrap_err();
+        assert!(matches!(error.root_cause(), SynthesisError::Unsatisfiable));
+        assert!(error.messages()[0].starts_with("constraint 0 (a * b = c) is unsatisfied"));
+    }
+
+    #[test]
+    fn prove_and_verify_with_matrices() {
+        let rng = &mut test_rng();
+
+        let matrices =
+            ConstraintMatrices::synthesize(NamedSumCircuit { a: None, b: None }).unwrap();
+        let params = generate_random_parameters::<Bls12_377, _, _>(matrices.clone(), rng).unwrap();
+        let pvk = prepare_verifying_key::<Bls12_377>(&params.vk);
+
+        for _ in 0..10 {
+            let a = Fr::rand(rng);
+            let b = Fr::rand(rng);
+            let witness = WitnessAssignment::generate(NamedSumCircuit {
+                a: Some(a),
+                b: Some(b),
+            })
+            .unwrap();
+            let proof =
+                create_random_proof_with_matrices(&matrices, &witness, &params, rng).unwrap();
+
+            assert!(verify_proof(&pvk, &proof, &[(a + &b) * &b]).unwrap());
+            assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+        }
+
+        let witness = WitnessAssignment::generate(MySillyCircuit {
+            a: Some(Fr::one()),
+            b: Some(Fr::one()),
+        })
+        .unwrap();
+        assert!(create_random_proof_with_matrices(&matrices, &witness, &params, rng).is_err());
+    }
+
+    #[test]
+    fn prove_and_verify_in_parallel() {
+        let rng = &mut test_rng();
+
+        let params = generate_random_parameters::<Bls12_377, _, _>(
+            ParallelProductsCircuit {
+                a: None,
+                bs: vec![None; 8],
+            },
+            rng,
+        )
+        .unwrap();
+        let pvk = prepare_verifying_key::<Bls12_377>(&params.vk);
+
+        let a = Fr::rand(rng);
+        let bs = (0..8).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let cs = bs.iter().map(|b| a * b).collect::<Vec<_>>();
+        let proof = create_random_proof(
+            ParallelProductsCircuit {
+                a: Some(a),
+                bs: bs.into_iter().map(Some).collect(),
+            },
+            &params,
+            rng,
+        )
+        .unwrap();
+
+        assert!(verify_proof(&pvk, &proof, &cs).unwrap());
+        let mut swapped = cs.clone();
+        swapped.swap(0, 1);
+        assert!(!verify_proof(&pvk, &proof, &swapped).unwrap());
+    }
 }
 
 mod cp6_782 {
@@ -0,0 +1,220 @@
+use crate::{Error, Permutation, Vec};
+use algebra_core::{FftField, Field};
+use ff_fft::{EvaluationDomain, Evaluations};
+
+/// An arithmetic gate, which constrains the values `w_0, ..., w_{k-1}` of the
+/// cells of its row by
+///
+/// `q_0 w_0 + ... + q_{k-1} w_{k-1} + q_M w_0 w_1 + q_C + PI = 0`,
+///
+/// where `PI` is the negated public input of the row for the first rows of a
+/// circuit, and zero for the others.
+///
+/// With three wires this is the arithmetic gate of PLONK. Wider gates add up
+/// longer linear combinations in a single row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Gate<F: Field> {
+    /// The variable held by each cell of the row, or `None` if the cell is
+    /// unused, in which case its value is zero.
+    pub wires: Vec<Option<usize>>,
+    /// The coefficient `q_i` of the value of each cell.
+    pub linear: Vec<F>,
+    /// The coefficient `q_M` of the product of the first two cells.
+    pub mul: F,
+    /// The constant term `q_C`.
+    pub constant: F,
+}
+
+impl<F: Field> Gate<F> {
+    /// Creates a gate of `width` wires whose cells are all unused and whose
+    /// coefficients are all zero.
+    pub fn empty(width: usize) -> Self {
+        Self {
+            wires: vec![None; width],
+            linear: vec![F::zero(); width],
+            mul: F::zero(),
+            constant: F::zero(),
+        }
+    }
+
+    /// Creates a gate of `width` wires enforcing that `var` equals the
+    /// public input of the row.
+    pub fn public_input(width: usize, var: usize) -> Self {
+        let mut gate = Self::empty(width);
+        gate.wires[0] = Some(var);
+        gate.linear[0] = F::one();
+        gate
+    }
+
+    /// Returns the left-hand side of the gate equation, without the public
+    /// input, on the given values of the cells of the row.
+    pub fn evaluate(&self, values: &[F]) -> F {
+        let linear = self
+            .linear
+            .iter()
+            .zip(values)
+            .fold(F::zero(), |acc, (q, w)| acc + &(*q * w));
+        linear + &(self.mul * &values[0] * &values[1]) + &self.constant
+    }
+}
+
+/// A Plonkish circuit: a list of gates of the same width, one per row, whose
+/// cells holding the same variable must have the same value.
+///
+/// The first `num_public_inputs` variables are the public inputs, and the
+/// first `num_public_inputs` gates are the corresponding
+/// [public input gates](Gate::public_input).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlonkishCircuit<F: Field> {
+    /// The number of wires of each gate.
+    pub width: usize,
+    /// The number of public inputs.
+    pub num_public_inputs: usize,
+    /// The number of variables, including the public inputs.
+    pub num_variables: usize,
+    /// The gates, one per row.
+    pub gates: Vec<Gate<F>>,
+    /// The assignment of the variables, or `None` if it could not be
+    /// computed, such as during setup.
+    pub assignment: Option<Vec<F>>,
+}
+
+/// The first gate or copy constraint that a table of cell values violates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unsatisfied {
+    /// The gate in the given row is not satisfied.
+    Gate(usize),
+    /// The cell at `(column, row)` does not have the same value as the next
+    /// cell in its cycle of the copy-constraint permutation.
+    CopyConstraint {
+        /// The `(column, row)` position of the cell.
+        cell: (usize, usize),
+        /// The `(column, row)` position of the next cell in its cycle.
+        next: (usize, usize),
+    },
+}
+
+impl<F: Field> PlonkishCircuit<F> {
+    /// Returns the number of rows, which is the number of gates.
+    pub fn num_rows(&self) -> usize {
+        self.gates.len()
+    }
+
+    /// Returns the assignment of the public inputs, if the assignment is
+    /// available.
+    pub fn public_inputs(&self) -> Option<&[F]> {
+        self.assignment
+            .as_ref()
+            .map(|assignment| &assignment[..self.num_public_inputs])
+    }
+
+    /// Returns the copy-constraint permutation of the cells.
+    pub fn permutation(&self) -> Permutation {
+        Permutation::new(self)
+    }
+
+    /// Returns the values of the cells, column by column, under the
+    /// assignment of the variables.
+    pub fn columns(&self) -> Result<Vec<Vec<F>>, Error> {
+        let assignment = self.assignment.as_ref().ok_or(Error::AssignmentMissing)?;
+        Ok((0..self.width)
+            .map(|column| {
+                self.gates
+                    .iter()
+                    .map(|gate| gate.wires[column].map_or(F::zero(), |var| assignment[var]))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Returns the first gate or copy constraint that the cell values in
+    /// `columns` violate under `public_inputs`, or `None` if all are
+    /// satisfied.
+    ///
+    /// Unlike [`is_satisfied`](Self::is_satisfied), this checks an arbitrary
+    /// table rather than one derived from an assignment of the variables, so
+    /// that the copy constraints are checked too.
+    pub fn which_is_unsatisfied(
+        &self,
+        public_inputs: &[F],
+        columns: &[Vec<F>],
+    ) -> Result<Option<Unsatisfied>, Error> {
+        if public_inputs.len() != self.num_public_inputs {
+            return Err(Error::IncorrectNumberOfPublicInputs {
+                expected: self.num_public_inputs,
+                found: public_inputs.len(),
+            });
+        }
+        if columns.len() != self.width
+            || columns.iter().any(|column| column.len() != self.num_rows())
+        {
+            return Err(Error::IncorrectTableShape);
+        }
+
+        let mut values = Vec::with_capacity(self.width);
+        for (row, gate) in self.gates.iter().enumerate() {
+            values.clear();
+            values.extend(columns.iter().map(|column| column[row]));
+            let public_input = public_inputs.get(row).map_or(F::zero(), |pi| -*pi);
+            if !(gate.evaluate(&values) + &public_input).is_zero() {
+                return Ok(Some(Unsatisfied::Gate(row)));
+            }
+        }
+
+        let permutation = self.permutation();
+        for column in 0..self.width {
+            for row in 0..self.num_rows() {
+                let next = permutation.get(column, row);
+                if columns[column][row] != columns[next.0][next.1] {
+                    return Ok(Some(Unsatisfied::CopyConstraint {
+                        cell: (column, row),
+                        next,
+                    }));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns whether the assignment of the variables satisfies all gates.
+    ///
+    /// Returns `Error::AssignmentMissing` if the assignment is missing.
+    pub fn is_satisfied(&self) -> Result<bool, Error> {
+        let public_inputs = self.public_inputs().ok_or(Error::AssignmentMissing)?;
+        self.which_is_unsatisfied(public_inputs, &self.columns()?)
+            .map(|unsatisfied| unsatisfied.is_none())
+    }
+}
+
+impl<F: FftField> PlonkishCircuit<F> {
+    /// Returns the smallest domain of type `D` with at least one element per
+    /// row.
+    pub fn domain<D: EvaluationDomain<F>>(&self) -> Result<D, Error> {
+        D::new(self.num_rows()).ok_or(Error::DomainTooSmall {
+            num_rows: self.num_rows(),
+        })
+    }
+
+    /// Returns the evaluations over `domain` of the polynomials of each
+    /// column, whose value at the `i`-th element of `domain` is the value of
+    /// the cell in the `i`-th row. The rows beyond the last gate are padded
+    /// with zeros.
+    pub fn column_evaluations<D: EvaluationDomain<F>>(
+        &self,
+        domain: D,
+    ) -> Result<Vec<Evaluations<F, D>>, Error> {
+        if domain.size() < self.num_rows() {
+            return Err(Error::DomainTooSmall {
+                num_rows: self.num_rows(),
+            });
+        }
+        Ok(self
+            .columns()?
+            .into_iter()
+            .map(|mut column| {
+                column.resize(domain.size(), F::zero());
+                Evaluations::from_vec_and_domain(column, domain)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,389 @@
+use crate::{Error, Gate, PlonkishCircuit, Vec};
+use algebra_core::Field;
+use core::cmp;
+use r1cs_core::{ConstraintMatrices, ConstraintSynthesizer, Index};
+
+/// A linear combination of Plonkish variables, as `(variable, coefficient)`
+/// pairs.
+type Terms<F> = Vec<(usize, F)>;
+
+/// Converts rank-1 constraint systems into Plonkish circuits whose gates have
+/// a fixed number of wires.
+///
+/// Each constraint `A * B = C` becomes a gate
+/// `q_M x y + q_0 x + q_1 y + sum_i q_i z_i + q_C = 0`, where `x` and `y` are
+/// the variables of `A` and `B`, and the `z_i` are those of `C`. Linear
+/// combinations that do not fit are first summed into intermediate variables
+/// by linear-combination gates, each of which adds up to `width - 1` terms.
+/// Constraints in which `A` or `B` is constant become linear-combination
+/// gates altogether. Wider gates therefore trade a larger table for fewer
+/// rows.
+///
+/// The variables of the circuit are the public inputs of the constraint
+/// system other than the "one" input, followed by its private variables and
+/// by the intermediate variables. The "one" input is folded into the
+/// constant terms of the gates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Converter {
+    width: usize,
+}
+
+impl Default for Converter {
+    /// Returns a converter to gates of three wires, as in PLONK.
+    fn default() -> Self {
+        Self { width: 3 }
+    }
+}
+
+impl Converter {
+    /// Returns a converter to gates of `width` wires.
+    ///
+    /// Returns `Error::WidthTooSmall` if `width` is less than three.
+    pub fn new(width: usize) -> Result<Self, Error> {
+        if width < 3 {
+            return Err(Error::WidthTooSmall(width));
+        }
+        Ok(Self { width })
+    }
+
+    /// Returns the number of wires of the gates.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Synthesizes `circuit` and converts its constraints and assignment.
+    pub fn convert_circuit<F, C>(&self, circuit: C) -> Result<PlonkishCircuit<F>, Error>
+    where
+        F: Field,
+        C: ConstraintSynthesizer<F>,
+    {
+        self.convert(&ConstraintMatrices::synthesize(circuit)?)
+    }
+
+    /// Converts the constraints and assignment of `matrices`.
+    ///
+    /// Returns `Error::MalformedMatrices` if `matrices` is not well formed.
+    pub fn convert<F: Field>(
+        &self,
+        matrices: &ConstraintMatrices<F>,
+    ) -> Result<PlonkishCircuit<F>, Error> {
+        if !matrices.is_well_formed() {
+            return Err(Error::MalformedMatrices);
+        }
+        let num_public_inputs = matrices.num_inputs - 1;
+        let assignment = match (&matrices.input_assignment, &matrices.aux_assignment) {
+            (Some(inputs), Some(aux)) => Some(inputs[1..].iter().chain(aux).cloned().collect()),
+            _ => None,
+        };
+        let mut builder = Builder {
+            width: self.width,
+            gates: (0..num_public_inputs)
+                .map(|var| Gate::public_input(self.width, var))
+                .collect(),
+            num_variables: num_public_inputs + matrices.num_aux,
+            assignment,
+        };
+
+        let split = |row: &[(F, Index)]| {
+            let mut terms = Vec::with_capacity(row.len());
+            let mut constant = F::zero();
+            for (coeff, index) in row {
+                match *index {
+                    Index::Input(0) => constant += coeff,
+                    Index::Input(i) => terms.push((i - 1, *coeff)),
+                    Index::Aux(i) => terms.push((num_public_inputs + i, *coeff)),
+                }
+            }
+            (terms, constant)
+        };
+        for ((a, b), c) in matrices.a.iter().zip(&matrices.b).zip(&matrices.c) {
+            let (a, a0) = split(a);
+            let (b, b0) = split(b);
+            let (c, c0) = split(c);
+            builder.constraint((a, a0), (b, b0), (c, c0));
+        }
+
+        Ok(PlonkishCircuit {
+            width: self.width,
+            num_public_inputs,
+            num_variables: builder.num_variables,
+            gates: builder.gates,
+            assignment: builder.assignment,
+        })
+    }
+}
+
+/// The state of a conversion.
+struct Builder<F: Field> {
+    width: usize,
+    gates: Vec<Gate<F>>,
+    num_variables: usize,
+    assignment: Option<Vec<F>>,
+}
+
+impl<F: Field> Builder<F> {
+    /// Adds the gates enforcing `(a + a0) * (b + b0) = c + c0`.
+    fn constraint(
+        &mut self,
+        (a, a0): (Terms<F>, F),
+        (b, b0): (Terms<F>, F),
+        (c, c0): (Terms<F>, F),
+    ) {
+        let constant = a0 * &b0 - &c0;
+        if a.is_empty() || b.is_empty() {
+            // At most one side varies, so the constraint is linear:
+            // b0 * a + a0 * b - c + (a0 * b0 - c0) = 0.
+            let terms = a
+                .into_iter()
+                .map(|(var, coeff)| (var, coeff * &b0))
+                .chain(b.into_iter().map(|(var, coeff)| (var, coeff * &a0)))
+                .chain(c.into_iter().map(|(var, coeff)| (var, -coeff)))
+                .collect();
+            let mut terms = canonicalize(terms);
+            self.compress(&mut terms, self.width);
+            self.linear_gate(terms, constant);
+            return;
+        }
+
+        // (alpha x + a0) * (beta y + b0) = c + c0 expands to
+        // alpha beta x y + alpha b0 x + a0 beta y - c + (a0 b0 - c0) = 0.
+        let (x, alpha) = self.single_term(a);
+        let (y, beta) = self.single_term(b);
+        let mut gate = Gate::empty(self.width);
+        gate.wires[0] = Some(x);
+        gate.wires[1] = Some(y);
+        gate.mul = alpha * &beta;
+        gate.linear[0] = alpha * &b0;
+        gate.linear[1] = a0 * &beta;
+        gate.constant = constant;
+
+        let mut rest = Vec::with_capacity(c.len());
+        for (var, coeff) in c {
+            if var == x {
+                gate.linear[0] -= &coeff;
+            } else if var == y {
+                gate.linear[1] -= &coeff;
+            } else {
+                rest.push((var, coeff));
+            }
+        }
+        self.compress(&mut rest, self.width - 2);
+        for (column, (var, coeff)) in rest.into_iter().enumerate() {
+            gate.wires[column + 2] = Some(var);
+            gate.linear[column + 2] = -coeff;
+        }
+        self.gates.push(gate);
+    }
+
+    /// Reduces `terms`, which must not be empty, to a single term.
+    fn single_term(&mut self, mut terms: Terms<F>) -> (usize, F) {
+        self.compress(&mut terms, 1);
+        terms[0]
+    }
+
+    /// Replaces the first `width - 1` terms by a new variable holding their
+    /// sum, until at most `max` terms remain.
+    fn compress(&mut self, terms: &mut Terms<F>, max: usize) {
+        while terms.len() > max {
+            let len = cmp::min(terms.len(), self.width - 1);
+            let chunk = terms.drain(..len).collect::<Vec<_>>();
+            let sum = self.sum(chunk);
+            terms.push((sum, F::one()));
+        }
+    }
+
+    /// Allocates a new variable holding the sum of `terms`, which must fit in
+    /// a gate alongside it, and adds the gate enforcing it.
+    fn sum(&mut self, mut terms: Terms<F>) -> usize {
+        let var = self.num_variables;
+        self.num_variables += 1;
+        if let Some(assignment) = &mut self.assignment {
+            let value = terms
+                .iter()
+                .fold(F::zero(), |acc, (v, coeff)| acc + &(assignment[*v] * coeff));
+            assignment.push(value);
+        }
+        terms.push((var, -F::one()));
+        self.linear_gate(terms, F::zero());
+        var
+    }
+
+    /// Adds a gate enforcing that the sum of `terms`, which must fit in a
+    /// gate, and `constant` is zero.
+    fn linear_gate(&mut self, terms: Terms<F>, constant: F) {
+        let mut gate = Gate::empty(self.width);
+        for (column, (var, coeff)) in terms.into_iter().enumerate() {
+            gate.wires[column] = Some(var);
+            gate.linear[column] = coeff;
+        }
+        gate.constant = constant;
+        self.gates.push(gate);
+    }
+}
+
+/// Sorts `terms` by variable, merges the terms of each variable and drops
+/// those whose coefficient is zero.
+fn canonicalize<F: Field>(mut terms: Terms<F>) -> Terms<F> {
+    terms.sort_by_key(|(var, _)| *var);
+    let mut result: Terms<F> = Vec::with_capacity(terms.len());
+    for (var, coeff) in terms {
+        match result.last_mut() {
+            Some(last) if last.0 == var => last.1 += &coeff,
+            _ => result.push((var, coeff)),
+        }
+    }
+    result.retain(|(_, coeff)| !coeff.is_zero());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Converter, Error, PlonkishCircuit, Unsatisfied};
+    use algebra::bls12_381::Fr;
+    use algebra_core::{Field, One};
+    use ff_fft::{EvaluationDomain, GeneralEvaluationDomain};
+    use r1cs_core::{
+        ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, Index, SynthesisError,
+    };
+
+    /// Proves knowledge of `x` such that `x^3 + x + 5 = out`, where `out` is
+    /// public, with a sum of four terms to exercise linear-combination gates.
+    struct Cubic {
+        x: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Cubic {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let x_val = self.x;
+            let x2_val = x_val.map(|x| x.square());
+            let x3_val = x2_val.and_then(|x2| x_val.map(|x| x2 * &x));
+            let out_val = x3_val.and_then(|x3| x_val.map(|x| x3 + &x + &Fr::from(5u64)));
+
+            let out = cs.alloc_input(
+                || "out",
+                || out_val.ok_or(SynthesisError::AssignmentMissing),
+            )?;
+            let x = cs.alloc(|| "x", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+            let x2 = cs.alloc(|| "x2", || x2_val.ok_or(SynthesisError::AssignmentMissing))?;
+            let x3 = cs.alloc(|| "x3", || x3_val.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "x * x = x2", |lc| lc + x, |lc| lc + x, |lc| lc + x2);
+            cs.enforce(|| "x2 * x = x3", |lc| lc + x2, |lc| lc + x, |lc| lc + x3);
+            cs.enforce(
+                || "(x3 + x + x2 - x2 + 5) * 1 = out",
+                |lc| lc + x3 + x + x2 + (-Fr::one(), x2) + (Fr::from(5u64), CS::one()),
+                |lc| lc + CS::one(),
+                |lc| lc + out,
+            );
+            cs.enforce(
+                || "(x + x2) * (x + 1) = x3 + 2 x2 + x",
+                |lc| lc + x + x2,
+                |lc| lc + x + CS::one(),
+                |lc| lc + x3 + (Fr::from(2u64), x2) + x,
+            );
+            Ok(())
+        }
+    }
+
+    fn convert(width: usize, x: Option<Fr>) -> PlonkishCircuit<Fr> {
+        Converter::new(width)
+            .unwrap()
+            .convert_circuit(Cubic { x })
+            .unwrap()
+    }
+
+    #[test]
+    fn satisfiable_for_all_widths() {
+        for width in 3..6 {
+            let circuit = convert(width, Some(Fr::from(3u64)));
+            assert_eq!(circuit.public_inputs().unwrap(), &[Fr::from(35u64)]);
+            assert!(circuit.is_satisfied().unwrap());
+
+            let setup = convert(width, None);
+            assert!(setup.assignment.is_none());
+            assert_eq!(setup.gates, circuit.gates);
+        }
+        assert!(convert(4, None).num_rows() < convert(3, None).num_rows());
+        assert!(matches!(Converter::new(2), Err(Error::WidthTooSmall(2))));
+    }
+
+    #[test]
+    fn rejects_malformed_matrices() {
+        let converter = Converter::default();
+        let mut matrices = ConstraintMatrices::synthesize(Cubic { x: None }).unwrap();
+        assert!(converter.convert(&matrices).is_ok());
+
+        matrices.a[0].push((Fr::one(), Index::Aux(matrices.num_aux)));
+        assert!(matches!(
+            converter.convert(&matrices),
+            Err(Error::MalformedMatrices)
+        ));
+
+        let mut matrices = ConstraintMatrices::<Fr>::new();
+        matrices.num_inputs = 0;
+        assert!(matches!(
+            converter.convert(&matrices),
+            Err(Error::MalformedMatrices)
+        ));
+    }
+
+    #[test]
+    fn detects_violations() {
+        let circuit = convert(3, Some(Fr::from(3u64)));
+        let columns = circuit.columns().unwrap();
+        assert_eq!(
+            circuit
+                .which_is_unsatisfied(&[Fr::from(36u64)], &columns)
+                .unwrap(),
+            Some(Unsatisfied::Gate(0))
+        );
+
+        // Changing one of the cells holding the public input keeps its gate
+        // satisfied, but breaks the copy constraint with the other cells.
+        let mut columns = columns;
+        columns[0][0] = Fr::from(36u64);
+        assert!(matches!(
+            circuit
+                .which_is_unsatisfied(&[Fr::from(36u64)], &columns)
+                .unwrap(),
+            Some(Unsatisfied::CopyConstraint { cell: (0, 0), .. })
+        ));
+        assert!(matches!(
+            circuit.which_is_unsatisfied(&[], &circuit.columns().unwrap()),
+            Err(Error::IncorrectNumberOfPublicInputs {
+                expected: 1,
+                found: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn permutation_grand_product() {
+        let circuit = convert(3, Some(Fr::from(3u64)));
+        let domain = circuit.domain::<GeneralEvaluationDomain<Fr>>().unwrap();
+        let permutation = circuit.permutation();
+        let ids = permutation.identity_evaluations(domain).unwrap();
+        let sigmas = permutation.sigma_evaluations(domain).unwrap();
+        let beta = Fr::from(7u64);
+        let gamma = Fr::from(11u64);
+        let grand_product = |columns: &[ff_fft::Evaluations<Fr>]| {
+            let mut numerator = Fr::one();
+            let mut denominator = Fr::one();
+            for ((column, id), sigma) in columns.iter().zip(&ids).zip(&sigmas) {
+                for i in 0..domain.size() {
+                    let w = column.evals[i];
+                    numerator *= &(w + &(beta * &id.evals[i]) + &gamma);
+                    denominator *= &(w + &(beta * &sigma.evals[i]) + &gamma);
+                }
+            }
+            numerator == denominator
+        };
+
+        let mut columns = circuit.column_evaluations(domain).unwrap();
+        assert!(grand_product(&columns));
+        columns[0].evals[circuit.num_public_inputs] += &Fr::one();
+        assert!(!grand_product(&columns));
+    }
+}
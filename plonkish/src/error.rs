@@ -0,0 +1,77 @@
+use core::fmt;
+use r1cs_core::SynthesisError;
+
+/// The error type for Plonkish arithmetizations.
+#[derive(Debug)]
+pub enum Error {
+    /// The requested gate width is too small to hold a multiplication gate,
+    /// which needs two input wires and an output wire.
+    WidthTooSmall(usize),
+
+    /// Synthesizing the rank-1 constraint system failed.
+    Synthesis(SynthesisError),
+
+    /// The matrices of the rank-1 constraint system are not well formed, for
+    /// instance because they refer to variables that are out of range.
+    MalformedMatrices,
+
+    /// The assignment of the variables is missing.
+    AssignmentMissing,
+
+    /// The number of public inputs is not the number that the circuit
+    /// expects.
+    IncorrectNumberOfPublicInputs {
+        /// The expected number of public inputs.
+        expected: usize,
+        /// The number of public inputs supplied.
+        found: usize,
+    },
+
+    /// The table of cell values does not have one column per wire and one
+    /// row per gate.
+    IncorrectTableShape,
+
+    /// The evaluation domain is too small to hold one row per gate, or no
+    /// domain of the required size exists.
+    DomainTooSmall {
+        /// The number of rows of the circuit.
+        num_rows: usize,
+    },
+}
+
+impl From<SynthesisError> for Error {
+    fn from(e: SynthesisError) -> Self {
+        Error::Synthesis(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::WidthTooSmall(width) => write!(
+                f,
+                "gates must have at least 3 wires, but {:?} were requested",
+                width
+            ),
+            Error::Synthesis(e) => write!(f, "synthesis failed: {}", e),
+            Error::MalformedMatrices => write!(f, "the constraint matrices are malformed"),
+            Error::AssignmentMissing => write!(f, "the assignment is missing"),
+            Error::IncorrectNumberOfPublicInputs { expected, found } => write!(
+                f,
+                "expected {:?} public inputs, but found {:?}",
+                expected, found
+            ),
+            Error::IncorrectTableShape => write!(
+                f,
+                "the table does not have one column per wire and one row per gate"
+            ),
+            Error::DomainTooSmall { num_rows } => write!(
+                f,
+                "no evaluation domain of at least {:?} elements is available",
+                num_rows
+            ),
+        }
+    }
+}
+
+impl algebra_core::Error for Error {}
@@ -0,0 +1,40 @@
+//! A crate for converting rank-1 constraint systems into Plonkish
+//! arithmetizations, as used by universal-setup proof systems such as
+//! [PLONK][gwc19].
+//!
+//! A Plonkish circuit is a table with a fixed number of columns, in which
+//! each row is constrained by an arithmetic gate, and cells holding the same
+//! variable are tied together by copy constraints, which are expressed as a
+//! permutation of the cells.
+//!
+//! [gwc19]: https://ia.cr/2019/953
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_import_braces, unused_qualifications, trivial_casts)]
+#![deny(trivial_numeric_casts, private_in_public, variant_size_differences)]
+#![deny(stable_features, unreachable_pub, non_shorthand_field_patterns)]
+#![deny(unused_attributes, unused_imports, unused_mut, missing_docs)]
+#![deny(renamed_and_removed_lints, unused_allocation)]
+#![deny(unused_comparisons, bare_trait_objects, unused_must_use, const_err)]
+#![forbid(unsafe_code)]
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+pub(crate) use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+pub(crate) use std::vec::Vec;
+
+mod error;
+pub use error::*;
+
+mod circuit;
+pub use circuit::{Gate, PlonkishCircuit, Unsatisfied};
+
+mod converter;
+pub use converter::Converter;
+
+mod permutation;
+pub use permutation::Permutation;
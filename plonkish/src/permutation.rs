@@ -0,0 +1,122 @@
+use crate::{Error, PlonkishCircuit, Vec};
+use algebra_core::{FftField, Field};
+use ff_fft::{EvaluationDomain, Evaluations};
+
+/// The copy-constraint permutation of a Plonkish circuit.
+///
+/// The cells holding the same variable form a cycle of the permutation, so
+/// that a table of cell values satisfies the copy constraints if and only if
+/// every cell has the same value as its image. Unused cells are fixed points.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Permutation {
+    width: usize,
+    num_rows: usize,
+    /// The image of each cell, where the cell at `(column, row)` has index
+    /// `column * num_rows + row`.
+    sigma: Vec<usize>,
+}
+
+impl Permutation {
+    /// Computes the copy-constraint permutation of the cells of `circuit`.
+    pub fn new<F: Field>(circuit: &PlonkishCircuit<F>) -> Self {
+        let width = circuit.width;
+        let num_rows = circuit.num_rows();
+        let mut sigma = (0..width * num_rows).collect::<Vec<_>>();
+        // The last cell seen so far holding each variable, and the first one.
+        let mut last = vec![None; circuit.num_variables];
+        let mut first = vec![None; circuit.num_variables];
+        for column in 0..width {
+            for (row, gate) in circuit.gates.iter().enumerate() {
+                if let Some(var) = gate.wires[column] {
+                    let cell = column * num_rows + row;
+                    match last[var] {
+                        Some(previous) => sigma[previous] = cell,
+                        None => first[var] = Some(cell),
+                    }
+                    last[var] = Some(cell);
+                }
+            }
+        }
+        // Close each cycle.
+        for (last, first) in last.into_iter().zip(first) {
+            if let (Some(last), Some(first)) = (last, first) {
+                sigma[last] = first;
+            }
+        }
+        Self {
+            width,
+            num_rows,
+            sigma,
+        }
+    }
+
+    /// Returns the `(column, row)` position of the image of the cell at
+    /// `(column, row)`.
+    pub fn get(&self, column: usize, row: usize) -> (usize, usize) {
+        let image = self.sigma[column * self.num_rows + row];
+        (image / self.num_rows, image % self.num_rows)
+    }
+
+    /// Returns the label of each column of cells over `domain`, as in PLONK:
+    /// the cell at `(column, row)` is labelled by `k_column * x_row`, where
+    /// `x_row` is the `row`-th element of `domain` and `k_column` is the
+    /// `column`-th power of the multiplicative generator of the field, so
+    /// that distinct cells have distinct labels.
+    ///
+    /// The rows beyond the last gate are padding rows whose cells are fixed
+    /// points of the permutation.
+    pub fn identity_evaluations<F: FftField, D: EvaluationDomain<F>>(
+        &self,
+        domain: D,
+    ) -> Result<Vec<Evaluations<F, D>>, Error> {
+        let labels = self.labels(domain)?;
+        Ok(labels
+            .into_iter()
+            .map(|column| Evaluations::from_vec_and_domain(column, domain))
+            .collect())
+    }
+
+    /// Returns the evaluations over `domain` of the permutation polynomials
+    /// `sigma_column`, whose value at the `row`-th element of `domain` is the
+    /// label of the image of the cell at `(column, row)`, as defined by
+    /// [`identity_evaluations`](Self::identity_evaluations).
+    pub fn sigma_evaluations<F: FftField, D: EvaluationDomain<F>>(
+        &self,
+        domain: D,
+    ) -> Result<Vec<Evaluations<F, D>>, Error> {
+        let labels = self.labels(domain)?;
+        Ok((0..self.width)
+            .map(|column| {
+                let evals = (0..domain.size())
+                    .map(|row| {
+                        let (column, row) = if row < self.num_rows {
+                            self.get(column, row)
+                        } else {
+                            (column, row)
+                        };
+                        labels[column][row]
+                    })
+                    .collect();
+                Evaluations::from_vec_and_domain(evals, domain)
+            })
+            .collect())
+    }
+
+    /// Returns the label of each cell over `domain`, column by column.
+    fn labels<F: FftField, D: EvaluationDomain<F>>(&self, domain: D) -> Result<Vec<Vec<F>>, Error> {
+        if domain.size() < self.num_rows {
+            return Err(Error::DomainTooSmall {
+                num_rows: self.num_rows,
+            });
+        }
+        let elements = domain.elements().collect::<Vec<_>>();
+        let generator = F::multiplicative_generator();
+        let mut shift = F::one();
+        let mut labels = Vec::with_capacity(self.width);
+        for _ in 0..self.width {
+            labels.push(elements.iter().map(|x| shift * x).collect());
+            shift *= &generator;
+        }
+        Ok(labels)
+    }
+}
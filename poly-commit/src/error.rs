@@ -0,0 +1,109 @@
+use core::fmt;
+
+/// The error type for polynomial commitment schemes.
+#[derive(Debug)]
+pub enum Error {
+    /// The degree provided in setup was too small; degree 0 polynomials
+    /// are not supported.
+    DegreeIsZero,
+
+    /// The number of coefficients in the polynomial is greater than the
+    /// number of supported powers.
+    TooManyCoefficients {
+        /// The number of coefficients in the polynomial.
+        num_coefficients: usize,
+        /// The number of powers in the committer key.
+        num_powers: usize,
+    },
+
+    /// The hiding bound was zero, even though hiding was requested.
+    HidingBoundIsZero,
+
+    /// The hiding bound exceeds the maximum degree of the blinding powers.
+    HidingBoundToolarge {
+        /// The hiding bound.
+        hiding_poly_degree: usize,
+        /// The number of powers of `gamma * G` in the committer key.
+        num_powers: usize,
+    },
+
+    /// Hiding was requested, but no randomness source was supplied.
+    MissingRng,
+
+    /// The number of opening points is greater than the number of powers
+    /// of `beta * H` in the verifier key.
+    TooManyPoints {
+        /// The number of opening points.
+        num_points: usize,
+        /// The number of points supported by the verifier key.
+        max_points: usize,
+    },
+
+    /// The set of opening points contains a repeated point.
+    DuplicatePoint,
+
+    /// The requested domain does not fit in the supported powers.
+    UnsupportedDomainSize(usize),
+
+    /// The inputs to a batched operation do not have matching lengths.
+    IncorrectInputLength {
+        /// The expected number of elements.
+        expected: usize,
+        /// The number of elements that was supplied.
+        found: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DegreeIsZero => write!(
+                f,
+                "this scheme does not support committing to degree 0 polynomials"
+            ),
+            Error::TooManyCoefficients {
+                num_coefficients,
+                num_powers,
+            } => write!(
+                f,
+                "the number of coefficients in the polynomial ({:?}) is greater than \
+                 the maximum number of powers in `Powers` ({:?})",
+                num_coefficients, num_powers
+            ),
+            Error::HidingBoundIsZero => write!(
+                f,
+                "this scheme does not support non-`None` hiding bounds that are 0"
+            ),
+            Error::HidingBoundToolarge {
+                hiding_poly_degree,
+                num_powers,
+            } => write!(
+                f,
+                "the degree of the hiding poly ({:?}) is not less than the maximum number \
+                 of powers in `Powers` ({:?})",
+                hiding_poly_degree, num_powers
+            ),
+            Error::MissingRng => write!(f, "hiding commitments require `Some(rng)`"),
+            Error::TooManyPoints {
+                num_points,
+                max_points,
+            } => write!(
+                f,
+                "the number of opening points ({:?}) is greater than the maximum \
+                 supported by the verifier key ({:?})",
+                num_points, max_points
+            ),
+            Error::DuplicatePoint => write!(f, "the opening points are not distinct"),
+            Error::UnsupportedDomainSize(size) => write!(
+                f,
+                "a domain of size {:?} is not supported by the given powers",
+                size
+            ),
+            Error::IncorrectInputLength { expected, found } => {
+                write!(f, "expected {:?} inputs, but found {:?}", expected, found)
+            },
+        }
+    }
+}
+
+impl algebra_core::Error for Error {}
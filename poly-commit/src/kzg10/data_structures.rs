@@ -0,0 +1,291 @@
+use crate::{Cow, Vec};
+use algebra_core::{
+    bytes::ToBytes,
+    io::{self, Write},
+    serialize::*,
+    AffineCurve, PairingEngine, ProjectiveCurve, Zero,
+};
+use core::ops::{Add, AddAssign};
+use ff_fft::DensePolynomial;
+use rand::RngCore;
+
+/// `UniversalParams` are the universal parameters for the KZG10 scheme.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct UniversalParams<E: PairingEngine> {
+    /// Group elements of the form `{ \beta^i G }`, where `i` ranges from 0 to
+    /// `degree`.
+    pub powers_of_g: Vec<E::G1Affine>,
+    /// Group elements of the form `{ \beta^i \gamma G }`, where `i` ranges from
+    /// 0 to `degree`.
+    pub powers_of_gamma_g: Vec<E::G1Affine>,
+    /// Group elements of the form `{ \beta^i H }`, where `i` ranges from 0 to
+    /// `max_points`. These are needed to verify openings at multiple points.
+    pub powers_of_h: Vec<E::G2Affine>,
+}
+
+impl<E: PairingEngine> UniversalParams<E> {
+    /// Returns the maximum degree of a polynomial that can be committed to
+    /// with these parameters.
+    pub fn max_degree(&self) -> usize {
+        self.powers_of_g.len() - 1
+    }
+
+    /// Returns the maximum number of points at which a single opening can be
+    /// verified with these parameters.
+    pub fn max_points(&self) -> usize {
+        self.powers_of_h.len() - 1
+    }
+}
+
+/// `Powers` is used to commit to and create evaluation proofs for a given
+/// polynomial.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = "")
+)]
+pub struct Powers<'a, E: PairingEngine> {
+    /// Group elements of the form `\beta^i G`, for different values of `i`.
+    pub powers_of_g: Cow<'a, [E::G1Affine]>,
+    /// Group elements of the form `\beta^i \gamma G`, for different values of
+    /// `i`.
+    pub powers_of_gamma_g: Cow<'a, [E::G1Affine]>,
+}
+
+impl<E: PairingEngine> Powers<'_, E> {
+    /// The number of powers in `self`.
+    pub fn size(&self) -> usize {
+        self.powers_of_g.len()
+    }
+}
+
+/// `LagrangePowers` is used to commit to polynomials that are given by their
+/// evaluations over a domain.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LagrangePowers<E: PairingEngine> {
+    /// Group elements of the form `L_i(\beta) G`, where `L_i` is the `i`-th
+    /// Lagrange polynomial of the domain.
+    pub lagrange_powers_of_g: Vec<E::G1Affine>,
+    /// Group elements of the form `\beta^i \gamma G`, which are used to
+    /// commit to the (monomial-basis) blinding polynomial.
+    pub powers_of_gamma_g: Vec<E::G1Affine>,
+}
+
+impl<E: PairingEngine> LagrangePowers<E> {
+    /// The size of the domain that `self` was computed over.
+    pub fn size(&self) -> usize {
+        self.lagrange_powers_of_g.len()
+    }
+}
+
+/// `VerifierKey` is used to check evaluation proofs for a given commitment.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifierKey<E: PairingEngine> {
+    /// The generator of G1.
+    pub g: E::G1Affine,
+    /// The generator of G1 that is used for making a commitment hiding.
+    pub gamma_g: E::G1Affine,
+    /// The generator of G2.
+    pub h: E::G2Affine,
+    /// `\beta` times the above generator of G2.
+    pub beta_h: E::G2Affine,
+    /// Group elements of the form `\beta^i H`, used to check openings at
+    /// multiple points.
+    pub powers_of_h: Vec<E::G2Affine>,
+}
+
+impl<E: PairingEngine> VerifierKey<E> {
+    /// Returns the maximum number of points at which a single opening can be
+    /// verified with this key.
+    pub fn max_points(&self) -> usize {
+        self.powers_of_h.len().saturating_sub(1)
+    }
+}
+
+impl<E: PairingEngine> ToBytes for VerifierKey<E> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        self.g.write(&mut writer)?;
+        self.gamma_g.write(&mut writer)?;
+        self.h.write(&mut writer)?;
+        self.beta_h.write(&mut writer)?;
+        for h in &self.powers_of_h {
+            h.write(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// `Commitment` commits to a polynomial. It is output by `KZG10::commit`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Copy(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = "")
+)]
+pub struct Commitment<E: PairingEngine>(
+    /// The commitment is a group element.
+    pub E::G1Affine,
+);
+
+impl<E: PairingEngine> Default for Commitment<E> {
+    fn default() -> Self {
+        Commitment(E::G1Affine::zero())
+    }
+}
+
+impl<E: PairingEngine> ToBytes for Commitment<E> {
+    #[inline]
+    fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.0.write(writer)
+    }
+}
+
+impl<'a, E: PairingEngine> AddAssign<(E::Fr, &'a Commitment<E>)> for Commitment<E> {
+    #[inline]
+    fn add_assign(&mut self, (f, other): (E::Fr, &'a Commitment<E>)) {
+        let mut other = other.0.mul(f);
+        other.add_assign_mixed(&self.0);
+        self.0 = other.into();
+    }
+}
+
+/// `Randomness` hides the polynomial inside a commitment. It is output by
+/// `KZG10::commit`.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = "")
+)]
+pub struct Randomness<E: PairingEngine> {
+    /// For KZG10, the commitment randomness is a random polynomial.
+    pub blinding_polynomial: DensePolynomial<E::Fr>,
+}
+
+impl<E: PairingEngine> Randomness<E> {
+    /// Does `self` provide any hiding properties to the corresponding
+    /// commitment? `self.is_hiding() == true` only if the underlying
+    /// polynomial is non-zero.
+    #[inline]
+    pub fn is_hiding(&self) -> bool {
+        !self.blinding_polynomial.is_zero()
+    }
+
+    /// What is the degree of the hiding polynomial for a given hiding bound?
+    #[inline]
+    pub fn calculate_hiding_polynomial_degree(hiding_bound: usize) -> usize {
+        hiding_bound + 1
+    }
+
+    /// Returns empty randomness, which does not hide the committed polynomial.
+    pub fn empty() -> Self {
+        Self {
+            blinding_polynomial: DensePolynomial::zero(),
+        }
+    }
+
+    /// Samples randomness for commitments that can be opened at
+    /// `hiding_bound` points without revealing information about the
+    /// committed polynomial.
+    pub fn rand<R: RngCore>(hiding_bound: usize, rng: &mut R) -> Self {
+        let mut randomness = Randomness::empty();
+        let hiding_poly_degree = Self::calculate_hiding_polynomial_degree(hiding_bound);
+        randomness.blinding_polynomial = DensePolynomial::rand(hiding_poly_degree, rng);
+        randomness
+    }
+}
+
+impl<'a, E: PairingEngine> Add<&'a Randomness<E>> for Randomness<E> {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: &'a Self) -> Self {
+        self.blinding_polynomial += &other.blinding_polynomial;
+        self
+    }
+}
+
+impl<'a, E: PairingEngine> AddAssign<(E::Fr, &'a Randomness<E>)> for Randomness<E> {
+    #[inline]
+    fn add_assign(&mut self, (f, other): (E::Fr, &'a Randomness<E>)) {
+        self.blinding_polynomial += (f, &other.blinding_polynomial);
+    }
+}
+
+/// `Proof` is an evaluation proof that is output by `KZG10::open`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Copy(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = "")
+)]
+pub struct Proof<E: PairingEngine> {
+    /// This is a commitment to the witness polynomial; see the KZG10 paper for more
+    /// details.
+    pub w: E::G1Affine,
+    /// This is the evaluation of the random polynomial at the point for which
+    /// the evaluation proof was produced.
+    pub random_v: Option<E::Fr>,
+}
+
+impl<E: PairingEngine> Default for Proof<E> {
+    fn default() -> Self {
+        Self {
+            w: E::G1Affine::zero(),
+            random_v: None,
+        }
+    }
+}
+
+impl<E: PairingEngine> ToBytes for Proof<E> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        self.w.write(&mut writer)?;
+        self.random_v.unwrap_or_default().write(&mut writer)
+    }
+}
+
+/// `MultiPointProof` is an evaluation proof for the openings of a single
+/// polynomial at several points. It is output by `KZG10::open_at_points`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = "")
+)]
+pub struct MultiPointProof<E: PairingEngine> {
+    /// This is a commitment to the witness polynomial
+    /// `(p(X) - I(X)) / Z(X)`, where `I` interpolates the claimed evaluations
+    /// and `Z` vanishes on the opening points.
+    pub w: E::G1Affine,
+    /// These are the evaluations of the random polynomial at the opening
+    /// points.
+    pub random_v: Option<Vec<E::Fr>>,
+}
+
+impl<E: PairingEngine> ToBytes for MultiPointProof<E> {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        self.w.write(&mut writer)?;
+        if let Some(random_v) = &self.random_v {
+            for v in random_v {
+                v.write(&mut writer)?;
+            }
+        }
+        Ok(())
+    }
+}
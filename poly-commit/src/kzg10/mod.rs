@@ -0,0 +1,1015 @@
+//! Here we construct a polynomial commitment that enables users to commit to a
+//! single polynomial `p`, and then later provide an evaluation proof that
+//! convinces verifiers that a claimed value `v` is the true evaluation of `p`
+//! at a chosen point `x`. Our construction follows the template of the
+//! construction proposed by Kate, Zaverucha, and Goldberg ([KZG10]).
+//! This construction achieves extractability in the algebraic group model
+//! (AGM).
+//!
+//! In addition to single-point openings, the scheme supports
+//! * batch verification of many independent openings,
+//! * opening a random linear combination of several polynomials at several
+//!   points with a single group element, and
+//! * committing to polynomials given by their evaluations over an
+//!   `EvaluationDomain`, using powers in the Lagrange basis.
+//!
+//! [KZG10]: http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf
+
+use crate::{Cow, Error, Vec};
+use algebra_core::{
+    msm::{FixedBaseMSM, VariableBaseMSM},
+    AffineCurve, FftField, Field, One, PairingEngine, PrimeField, ProjectiveCurve, UniformRand,
+    Zero,
+};
+use core::marker::PhantomData;
+use ff_fft::{cfg_iter, DensePolynomial, EvaluationDomain, Evaluations, SubproductTree};
+use rand::RngCore;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+mod data_structures;
+pub use data_structures::*;
+
+/// `KZG10` is an implementation of the polynomial commitment scheme of
+/// [Kate, Zaverucha and Goldberg][kzg10]
+///
+/// [kzg10]: http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf
+pub struct KZG10<E: PairingEngine> {
+    _engine: PhantomData<E>,
+}
+
+impl<E: PairingEngine> KZG10<E> {
+    /// Constructs public parameters that allow committing to polynomials of
+    /// degree at most `max_degree`, and opening them at up to `max_points`
+    /// points at once.
+    pub fn setup<R: RngCore>(
+        max_degree: usize,
+        max_points: usize,
+        rng: &mut R,
+    ) -> Result<UniversalParams<E>, Error> {
+        if max_degree < 1 {
+            return Err(Error::DegreeIsZero);
+        }
+        let setup_time = start_timer!(|| format!("KZG10::Setup with degree {}", max_degree));
+        let max_points = max_points.max(1);
+
+        let beta = E::Fr::rand(rng);
+        let g = E::G1Projective::rand(rng);
+        let gamma_g = E::G1Projective::rand(rng);
+        let h = E::G2Projective::rand(rng);
+
+        let num_powers = max_degree.max(max_points) + 1;
+        let mut powers_of_beta = vec![E::Fr::one()];
+        let mut cur = beta;
+        for _ in 1..num_powers {
+            powers_of_beta.push(cur);
+            cur *= &beta;
+        }
+
+        let scalar_bits = E::Fr::size_in_bits();
+
+        let g_time = start_timer!(|| "Generating powers of G");
+        let window_size = FixedBaseMSM::get_mul_window_size(max_degree + 1);
+        let g_table = FixedBaseMSM::get_window_table(scalar_bits, window_size, g);
+        let mut powers_of_g = FixedBaseMSM::multi_scalar_mul::<E::G1Projective>(
+            scalar_bits,
+            window_size,
+            &g_table,
+            &powers_of_beta[..=max_degree],
+        );
+        end_timer!(g_time);
+
+        let gamma_g_time = start_timer!(|| "Generating powers of gamma * G");
+        let gamma_g_table = FixedBaseMSM::get_window_table(scalar_bits, window_size, gamma_g);
+        let mut powers_of_gamma_g = FixedBaseMSM::multi_scalar_mul::<E::G1Projective>(
+            scalar_bits,
+            window_size,
+            &gamma_g_table,
+            &powers_of_beta[..=max_degree],
+        );
+        end_timer!(gamma_g_time);
+
+        let h_time = start_timer!(|| "Generating powers of H");
+        let h_window_size = FixedBaseMSM::get_mul_window_size(max_points + 1);
+        let h_table = FixedBaseMSM::get_window_table(scalar_bits, h_window_size, h);
+        let mut powers_of_h = FixedBaseMSM::multi_scalar_mul::<E::G2Projective>(
+            scalar_bits,
+            h_window_size,
+            &h_table,
+            &powers_of_beta[..=max_points],
+        );
+        end_timer!(h_time);
+
+        let batch_normalization_time = start_timer!(|| "Convert parameters to affine");
+        E::G1Projective::batch_normalization(powers_of_g.as_mut_slice());
+        E::G1Projective::batch_normalization(powers_of_gamma_g.as_mut_slice());
+        E::G2Projective::batch_normalization(powers_of_h.as_mut_slice());
+        end_timer!(batch_normalization_time);
+
+        let pp = UniversalParams {
+            powers_of_g: powers_of_g.into_iter().map(Into::into).collect(),
+            powers_of_gamma_g: powers_of_gamma_g.into_iter().map(Into::into).collect(),
+            powers_of_h: powers_of_h.into_iter().map(Into::into).collect(),
+        };
+        end_timer!(setup_time);
+        Ok(pp)
+    }
+
+    /// Specializes the public parameters for polynomials of degree at most
+    /// `supported_degree`.
+    pub fn trim(
+        pp: &UniversalParams<E>,
+        supported_degree: usize,
+    ) -> Result<(Powers<'_, E>, VerifierKey<E>), Error> {
+        if supported_degree < 1 {
+            return Err(Error::DegreeIsZero);
+        }
+        if supported_degree > pp.max_degree() {
+            return Err(Error::TooManyCoefficients {
+                num_coefficients: supported_degree + 1,
+                num_powers: pp.powers_of_g.len(),
+            });
+        }
+
+        let powers = Powers {
+            powers_of_g: Cow::Borrowed(&pp.powers_of_g[..=supported_degree]),
+            powers_of_gamma_g: Cow::Borrowed(&pp.powers_of_gamma_g[..=supported_degree]),
+        };
+        let vk = VerifierKey {
+            g: pp.powers_of_g[0],
+            gamma_g: pp.powers_of_gamma_g[0],
+            h: pp.powers_of_h[0],
+            beta_h: pp.powers_of_h[1],
+            powers_of_h: pp.powers_of_h.clone(),
+        };
+        Ok((powers, vk))
+    }
+
+    /// Computes the powers `{ L_i(\beta) G }` of the Lagrange polynomials of
+    /// `domain` via an inverse FFT over the group elements `{ \beta^i G }`.
+    pub fn lagrange_powers<D: EvaluationDomain<E::Fr>>(
+        pp: &UniversalParams<E>,
+        domain: D,
+    ) -> Result<LagrangePowers<E>, Error> {
+        let size = domain.size();
+        if size > pp.powers_of_g.len() {
+            return Err(Error::UnsupportedDomainSize(size));
+        }
+        let lagrange_time = start_timer!(|| format!("Computing {} Lagrange powers", size));
+
+        let powers_of_g = cfg_iter!(pp.powers_of_g[..size])
+            .map(|g| g.into_projective())
+            .collect::<Vec<_>>();
+        let mut lagrange_powers_of_g = domain.monomial_to_lagrange_basis(&powers_of_g);
+        E::G1Projective::batch_normalization(lagrange_powers_of_g.as_mut_slice());
+
+        end_timer!(lagrange_time);
+        Ok(LagrangePowers {
+            lagrange_powers_of_g: lagrange_powers_of_g.into_iter().map(Into::into).collect(),
+            powers_of_gamma_g: pp.powers_of_gamma_g[..size].to_vec(),
+        })
+    }
+
+    /// Outputs a commitment to `polynomial`.
+    ///
+    /// If `hiding_bound` is `Some`, the commitment is blinded with a random
+    /// polynomial sampled from `rng`, so that it can be safely opened at up to
+    /// `hiding_bound` points.
+    pub fn commit(
+        powers: &Powers<E>,
+        polynomial: &DensePolynomial<E::Fr>,
+        hiding_bound: Option<usize>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<(Commitment<E>, Randomness<E>), Error> {
+        Self::check_degree_is_within_bounds(polynomial.coeffs.len(), powers.size())?;
+
+        let commit_time = start_timer!(|| format!(
+            "Committing to polynomial of degree {} with hiding_bound: {:?}",
+            polynomial.degree(),
+            hiding_bound,
+        ));
+
+        let (num_leading_zeros, plain_coeffs) =
+            skip_leading_zeros_and_convert_to_bigints(polynomial);
+
+        let msm_time = start_timer!(|| "MSM to compute commitment to plaintext poly");
+        let mut commitment = VariableBaseMSM::multi_scalar_mul(
+            &powers.powers_of_g[num_leading_zeros..],
+            &plain_coeffs,
+        );
+        end_timer!(msm_time);
+
+        let randomness = Self::blind_commitment(
+            &powers.powers_of_gamma_g,
+            hiding_bound,
+            rng,
+            &mut commitment,
+        )?;
+
+        end_timer!(commit_time);
+        Ok((Commitment(commitment.into()), randomness))
+    }
+
+    /// Outputs a commitment to the polynomial whose evaluations over the
+    /// domain of `lagrange_powers` are `evaluations`.
+    ///
+    /// The result is identical to committing to `evaluations.interpolate()`
+    /// with `KZG10::commit`, but no inverse FFT over the field is required.
+    pub fn commit_lagrange<D: EvaluationDomain<E::Fr>>(
+        lagrange_powers: &LagrangePowers<E>,
+        evaluations: &Evaluations<E::Fr, D>,
+        hiding_bound: Option<usize>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<(Commitment<E>, Randomness<E>), Error> {
+        if evaluations.evals.len() != lagrange_powers.size() {
+            return Err(Error::IncorrectInputLength {
+                expected: lagrange_powers.size(),
+                found: evaluations.evals.len(),
+            });
+        }
+
+        let commit_time = start_timer!(|| format!(
+            "Committing to {} evaluations with hiding_bound: {:?}",
+            evaluations.evals.len(),
+            hiding_bound,
+        ));
+
+        let msm_time = start_timer!(|| "MSM to compute commitment to plaintext evaluations");
+        let evals = convert_to_bigints(&evaluations.evals);
+        let mut commitment =
+            VariableBaseMSM::multi_scalar_mul(&lagrange_powers.lagrange_powers_of_g, &evals);
+        end_timer!(msm_time);
+
+        let randomness = Self::blind_commitment(
+            &lagrange_powers.powers_of_gamma_g,
+            hiding_bound,
+            rng,
+            &mut commitment,
+        )?;
+
+        end_timer!(commit_time);
+        Ok((Commitment(commitment.into()), randomness))
+    }
+
+    /// Samples a blinding polynomial (if hiding was requested) and adds its
+    /// commitment to `commitment`.
+    fn blind_commitment(
+        powers_of_gamma_g: &[E::G1Affine],
+        hiding_bound: Option<usize>,
+        rng: Option<&mut dyn RngCore>,
+        commitment: &mut E::G1Projective,
+    ) -> Result<Randomness<E>, Error> {
+        let hiding_bound = match hiding_bound {
+            Some(hiding_bound) => hiding_bound,
+            None => return Ok(Randomness::empty()),
+        };
+        Self::check_hiding_bound(hiding_bound, powers_of_gamma_g.len())?;
+        let mut rng = rng.ok_or(Error::MissingRng)?;
+
+        let sample_random_poly_time =
+            start_timer!(|| format!("Sampling a random polynomial of degree {}", hiding_bound));
+        let randomness = Randomness::rand(hiding_bound, &mut rng);
+        end_timer!(sample_random_poly_time);
+
+        let msm_time = start_timer!(|| "MSM to compute commitment to random poly");
+        let random_ints = convert_to_bigints(&randomness.blinding_polynomial.coeffs);
+        let random_commitment =
+            VariableBaseMSM::multi_scalar_mul(powers_of_gamma_g, random_ints.as_slice());
+        end_timer!(msm_time);
+
+        *commitment += &random_commitment;
+        Ok(randomness)
+    }
+
+    /// Compute witness polynomial.
+    ///
+    /// The witness polynomial w(x) the quotient of the division (p(x) - p(z)) /
+    /// (x - z) Observe that this quotient does not change with z because
+    /// p(z) is the remainder term. We can therefore omit p(z) when computing
+    /// the quotient.
+    pub fn compute_witness_polynomial(
+        p: &DensePolynomial<E::Fr>,
+        point: E::Fr,
+        randomness: &Randomness<E>,
+    ) -> (DensePolynomial<E::Fr>, Option<DensePolynomial<E::Fr>>) {
+        let divisor = DensePolynomial::from_coefficients_vec(vec![-point, E::Fr::one()]);
+
+        let witness_time = start_timer!(|| "Computing witness polynomial");
+        let witness_polynomial = p / &divisor;
+        end_timer!(witness_time);
+
+        let random_witness_polynomial = if randomness.is_hiding() {
+            let random_p = &randomness.blinding_polynomial;
+
+            let witness_time = start_timer!(|| "Computing random witness polynomial");
+            let random_witness_polynomial = random_p / &divisor;
+            end_timer!(witness_time);
+            Some(random_witness_polynomial)
+        } else {
+            None
+        };
+
+        (witness_polynomial, random_witness_polynomial)
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same.
+    pub fn open(
+        powers: &Powers<E>,
+        p: &DensePolynomial<E::Fr>,
+        point: E::Fr,
+        rand: &Randomness<E>,
+    ) -> Result<Proof<E>, Error> {
+        Self::check_degree_is_within_bounds(p.coeffs.len(), powers.size())?;
+        let open_time = start_timer!(|| format!("Opening polynomial of degree {}", p.degree()));
+
+        let (witness_poly, hiding_witness_poly) = Self::compute_witness_polynomial(p, point, rand);
+
+        let proof_time = start_timer!(|| "Computing proof");
+        let w = Self::commit_to_witness(powers, &witness_poly, hiding_witness_poly.as_ref());
+        end_timer!(proof_time);
+
+        let random_v = hiding_witness_poly.map(|_| rand.blinding_polynomial.evaluate(point));
+
+        end_timer!(open_time);
+        Ok(Proof {
+            w: w.into_affine(),
+            random_v,
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `point` of the polynomial
+    /// committed inside `comm`.
+    pub fn check(
+        vk: &VerifierKey<E>,
+        comm: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let check_time = start_timer!(|| "Checking evaluation");
+        let mut inner = comm.0.into_projective() - &vk.g.mul(value);
+        if let Some(random_v) = proof.random_v {
+            inner -= &vk.gamma_g.mul(random_v);
+        }
+        let lhs = E::pairing(inner, vk.h);
+
+        let inner = vk.beta_h.into_projective() - &vk.h.mul(point);
+        let rhs = E::pairing(proof.w, inner);
+
+        end_timer!(check_time, || format!("Result: {}", lhs == rhs));
+        Ok(lhs == rhs)
+    }
+
+    /// Check that each `proof_i` in `proofs` is a valid proof of evaluation for
+    /// `commitment_i` at `point_i`.
+    ///
+    /// The checks are combined with random scalars sampled from `rng`, so that
+    /// only two pairings are computed.
+    pub fn batch_check<R: RngCore>(
+        vk: &VerifierKey<E>,
+        commitments: &[Commitment<E>],
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proofs: &[Proof<E>],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let num_openings = commitments.len();
+        for len in [points.len(), values.len(), proofs.len()].iter() {
+            if *len != num_openings {
+                return Err(Error::IncorrectInputLength {
+                    expected: num_openings,
+                    found: *len,
+                });
+            }
+        }
+        let check_time = start_timer!(|| format!("Checking {} evaluation proofs", num_openings));
+
+        let g = vk.g.into_projective();
+        let gamma_g = vk.gamma_g.into_projective();
+
+        let mut total_c = E::G1Projective::zero();
+        let mut total_w = E::G1Projective::zero();
+
+        let combination_time = start_timer!(|| "Combining commitments and proofs");
+        let mut randomizer = E::Fr::one();
+        // Instead of multiplying g and gamma_g in each turn, we simply accumulate
+        // their coefficients and perform a final multiplication at the end.
+        let mut g_multiplier = E::Fr::zero();
+        let mut gamma_g_multiplier = E::Fr::zero();
+        for (((c, z), v), proof) in commitments.iter().zip(points).zip(values).zip(proofs) {
+            let w = proof.w;
+            let mut temp = w.mul(*z);
+            temp.add_assign_mixed(&c.0);
+            let c = temp;
+            g_multiplier += &(randomizer * v);
+            if let Some(random_v) = proof.random_v {
+                gamma_g_multiplier += &(randomizer * &random_v);
+            }
+            total_c += &c.mul(randomizer.into_repr());
+            total_w += &w.mul(randomizer);
+            // We don't need to sample randomizers from the full field,
+            // only from 128-bit strings.
+            randomizer = u128::rand(rng).into();
+        }
+        total_c -= &g.mul(g_multiplier.into_repr());
+        total_c -= &gamma_g.mul(gamma_g_multiplier.into_repr());
+        end_timer!(combination_time);
+
+        let to_affine_time = start_timer!(|| "Converting results to affine for pairing");
+        let affine_points = E::G1Projective::batch_normalization_into_affine(&[-total_w, total_c]);
+        let (total_w, total_c) = (affine_points[0], affine_points[1]);
+        end_timer!(to_affine_time);
+
+        let pairing_time = start_timer!(|| "Performing product of pairings");
+        let result = E::product_of_pairings(&[
+            (total_w.into(), vk.beta_h.into()),
+            (total_c.into(), vk.h.into()),
+        ])
+        .is_one();
+        end_timer!(pairing_time);
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Opens the linear combination of `polynomials` with coefficients
+    /// `1, opening_challenge, opening_challenge^2, ...` at every point in
+    /// `points`, producing a single group element as proof.
+    pub fn open_at_points(
+        powers: &Powers<E>,
+        polynomials: &[&DensePolynomial<E::Fr>],
+        points: &[E::Fr],
+        opening_challenge: E::Fr,
+        rands: &[&Randomness<E>],
+    ) -> Result<MultiPointProof<E>, Error> {
+        if rands.len() != polynomials.len() {
+            return Err(Error::IncorrectInputLength {
+                expected: polynomials.len(),
+                found: rands.len(),
+            });
+        }
+        check_points_are_distinct(points)?;
+        let open_time = start_timer!(|| format!(
+            "Opening {} polynomials at {} points",
+            polynomials.len(),
+            points.len()
+        ));
+
+        let combination_time = start_timer!(|| "Combining polynomials");
+        let mut combined_polynomial = DensePolynomial::zero();
+        let mut combined_rand = Randomness::empty();
+        let mut challenge_j = E::Fr::one();
+        for (polynomial, rand) in polynomials.iter().zip(rands) {
+            Self::check_degree_is_within_bounds(polynomial.coeffs.len(), powers.size())?;
+            combined_polynomial += (challenge_j, *polynomial);
+            combined_rand += (challenge_j, *rand);
+            challenge_j *= &opening_challenge;
+        }
+        end_timer!(combination_time);
+
+        // The quotient by the vanishing polynomial of `points` is the same as
+        // `(p(X) - I(X)) / Z(X)`, since `I(X)` is the remainder of the division.
+        let witness_time = start_timer!(|| "Computing witness polynomials");
+        let (vanishing_polynomial, _) = vanish_and_interpolate(points, &[])?;
+        let witness_polynomial = divide(&combined_polynomial, &vanishing_polynomial);
+        let hiding_witness_polynomial = if combined_rand.is_hiding() {
+            Some(divide(
+                &combined_rand.blinding_polynomial,
+                &vanishing_polynomial,
+            ))
+        } else {
+            None
+        };
+        end_timer!(witness_time);
+
+        let proof_time = start_timer!(|| "Computing proof");
+        let w = Self::commit_to_witness(
+            powers,
+            &witness_polynomial,
+            hiding_witness_polynomial.as_ref(),
+        );
+        end_timer!(proof_time);
+
+        let random_v = hiding_witness_polynomial.map(|_| {
+            points
+                .iter()
+                .map(|point| combined_rand.blinding_polynomial.evaluate(*point))
+                .collect()
+        });
+
+        end_timer!(open_time);
+        Ok(MultiPointProof {
+            w: w.into_affine(),
+            random_v,
+        })
+    }
+
+    /// Verifies a proof output by `KZG10::open_at_points`. Here `values[i][j]`
+    /// is the claimed evaluation of the polynomial committed in
+    /// `commitments[i]` at `points[j]`.
+    pub fn check_at_points(
+        vk: &VerifierKey<E>,
+        commitments: &[Commitment<E>],
+        points: &[E::Fr],
+        values: &[Vec<E::Fr>],
+        opening_challenge: E::Fr,
+        proof: &MultiPointProof<E>,
+    ) -> Result<bool, Error> {
+        if points.len() > vk.max_points() {
+            return Err(Error::TooManyPoints {
+                num_points: points.len(),
+                max_points: vk.max_points(),
+            });
+        }
+        if values.len() != commitments.len() {
+            return Err(Error::IncorrectInputLength {
+                expected: commitments.len(),
+                found: values.len(),
+            });
+        }
+        for v in values
+            .iter()
+            .map(Vec::len)
+            .chain(proof.random_v.iter().map(Vec::len))
+        {
+            if v != points.len() {
+                return Err(Error::IncorrectInputLength {
+                    expected: points.len(),
+                    found: v,
+                });
+            }
+        }
+        check_points_are_distinct(points)?;
+        let check_time = start_timer!(|| format!(
+            "Checking openings of {} commitments at {} points",
+            commitments.len(),
+            points.len()
+        ));
+
+        let combination_time = start_timer!(|| "Combining commitments and evaluations");
+        let mut combined_comm = E::G1Projective::zero();
+        let mut combined_values = vec![E::Fr::zero(); points.len()];
+        let mut challenge_j = E::Fr::one();
+        for (comm, values) in commitments.iter().zip(values) {
+            combined_comm += &comm.0.mul(challenge_j);
+            for (combined_value, value) in combined_values.iter_mut().zip(values) {
+                *combined_value += &(challenge_j * value);
+            }
+            challenge_j *= &opening_challenge;
+        }
+        end_timer!(combination_time);
+
+        let h_time = start_timer!(|| "Computing vanishing and interpolating polynomials in G2");
+        let mut evals = vec![combined_values.as_slice()];
+        evals.extend(proof.random_v.as_deref());
+        let (vanishing_polynomial, interpolating_polynomials) =
+            vanish_and_interpolate(points, &evals)?;
+        let vanishing_h = commit_in_g2::<E>(&vk.powers_of_h, &vanishing_polynomial);
+        let mut interpolating_h = interpolating_polynomials
+            .iter()
+            .map(|p| commit_in_g2::<E>(&vk.powers_of_h, p));
+        let combined_interpolating_h = interpolating_h.next().unwrap();
+        let random_interpolating_h = interpolating_h.next();
+        end_timer!(h_time);
+
+        // We check that
+        // e(C, H) = e(W, Z(beta) H) * e(G, I(beta) H) * e(gamma G, I_r(beta) H).
+        let pairing_time = start_timer!(|| "Performing product of pairings");
+        let mut pairs = vec![
+            (combined_comm.into_affine().into(), vk.h.into()),
+            ((-proof.w).into(), vanishing_h.into_affine().into()),
+            (
+                (-vk.g).into(),
+                combined_interpolating_h.into_affine().into(),
+            ),
+        ];
+        if let Some(random_interpolating_h) = random_interpolating_h {
+            pairs.push((
+                (-vk.gamma_g).into(),
+                random_interpolating_h.into_affine().into(),
+            ));
+        }
+        let result = E::product_of_pairings(&pairs).is_one();
+        end_timer!(pairing_time);
+
+        end_timer!(check_time, || format!("Result: {}", result));
+        Ok(result)
+    }
+
+    /// Commits to the witness polynomial (and the witness for the blinding
+    /// polynomial, if present).
+    fn commit_to_witness(
+        powers: &Powers<E>,
+        witness_polynomial: &DensePolynomial<E::Fr>,
+        hiding_witness_polynomial: Option<&DensePolynomial<E::Fr>>,
+    ) -> E::G1Projective {
+        let (num_leading_zeros, witness_coeffs) =
+            skip_leading_zeros_and_convert_to_bigints(witness_polynomial);
+
+        let mut w = VariableBaseMSM::multi_scalar_mul(
+            &powers.powers_of_g[num_leading_zeros..],
+            &witness_coeffs,
+        );
+
+        if let Some(hiding_witness_polynomial) = hiding_witness_polynomial {
+            let random_witness_coeffs = convert_to_bigints(&hiding_witness_polynomial.coeffs);
+            w += &VariableBaseMSM::multi_scalar_mul(
+                &powers.powers_of_gamma_g,
+                &random_witness_coeffs,
+            );
+        }
+        w
+    }
+
+    pub(crate) fn check_degree_is_within_bounds(
+        num_coefficients: usize,
+        num_powers: usize,
+    ) -> Result<(), Error> {
+        if num_coefficients > num_powers {
+            Err(Error::TooManyCoefficients {
+                num_coefficients,
+                num_powers,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn check_hiding_bound(hiding_bound: usize, num_powers: usize) -> Result<(), Error> {
+        let hiding_poly_degree = Randomness::<E>::calculate_hiding_polynomial_degree(hiding_bound);
+        if hiding_bound == 0 {
+            Err(Error::HidingBoundIsZero)
+        } else if hiding_poly_degree >= num_powers {
+            // The hiding bound is too large for the committer key.
+            Err(Error::HidingBoundToolarge {
+                hiding_poly_degree,
+                num_powers,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn skip_leading_zeros_and_convert_to_bigints<F: PrimeField>(
+    p: &DensePolynomial<F>,
+) -> (usize, Vec<F::BigInt>) {
+    let mut num_leading_zeros = 0;
+    while num_leading_zeros < p.coeffs.len() && p.coeffs[num_leading_zeros].is_zero() {
+        num_leading_zeros += 1;
+    }
+    let coeffs = convert_to_bigints(&p.coeffs[num_leading_zeros..]);
+    (num_leading_zeros, coeffs)
+}
+
+fn convert_to_bigints<F: PrimeField>(p: &[F]) -> Vec<F::BigInt> {
+    let to_bigint_time = start_timer!(|| "Converting polynomial coeffs to bigints");
+    let coeffs = cfg_iter!(p).map(|s| s.into_repr()).collect::<Vec<_>>();
+    end_timer!(to_bigint_time);
+    coeffs
+}
+
+/// Computes `sum_i p_i \beta^i H` from the powers `{ \beta^i H }`.
+fn commit_in_g2<E: PairingEngine>(
+    powers_of_h: &[E::G2Affine],
+    p: &DensePolynomial<E::Fr>,
+) -> E::G2Projective {
+    VariableBaseMSM::multi_scalar_mul(powers_of_h, &convert_to_bigints(&p.coeffs))
+}
+
+fn check_points_are_distinct<F: Field>(points: &[F]) -> Result<(), Error> {
+    let mut sorted = points.to_vec();
+    sorted.sort();
+    if sorted.windows(2).any(|w| w[0] == w[1]) {
+        Err(Error::DuplicatePoint)
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the polynomial `prod_i (X - points[i])`, together with the
+/// polynomials of degree less than `points.len()` that interpolate each of
+/// `evals` over `points`.
+fn vanish_and_interpolate<F: FftField>(
+    points: &[F],
+    evals: &[&[F]],
+) -> Result<(DensePolynomial<F>, Vec<DensePolynomial<F>>), Error> {
+    if points.is_empty() {
+        let one = DensePolynomial::from_coefficients_vec(vec![F::one()]);
+        return Ok((one, vec![DensePolynomial::zero(); evals.len()]));
+    }
+    let tree = SubproductTree::new(points);
+    let interpolating_polynomials = evals
+        .iter()
+        .map(|evals| tree.interpolate(evals).ok_or(Error::DuplicatePoint))
+        .collect::<Result<_, _>>()?;
+    Ok((
+        tree.vanishing_polynomial().clone(),
+        interpolating_polynomials,
+    ))
+}
+
+/// Returns the quotient of `dividend` by `divisor`.
+fn divide<F: FftField>(
+    dividend: &DensePolynomial<F>,
+    divisor: &DensePolynomial<F>,
+) -> DensePolynomial<F> {
+    dividend.fast_divide_with_q_and_r(divisor).unwrap().0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{kzg10::*, Error};
+    use algebra::{
+        bls12_377::Bls12_377,
+        bls12_381::{Bls12_381, Fr},
+    };
+    use algebra_core::{test_rng, PairingEngine, UniformRand};
+    use ff_fft::{EvaluationDomain, GeneralEvaluationDomain};
+    use rand::Rng;
+
+    type KZGBls12_381 = KZG10<Bls12_381>;
+
+    #[test]
+    fn add_commitments_test() {
+        let rng = &mut test_rng();
+        let p = DensePolynomial::from_coefficients_slice(&[
+            Fr::rand(rng),
+            Fr::rand(rng),
+            Fr::rand(rng),
+            Fr::rand(rng),
+            Fr::rand(rng),
+        ]);
+        let f = Fr::rand(rng);
+        let mut f_p = DensePolynomial::zero();
+        f_p += (f, &p);
+
+        let degree = 4;
+        let pp = KZGBls12_381::setup(degree, 1, rng).unwrap();
+        let (powers, _) = KZGBls12_381::trim(&pp, degree).unwrap();
+
+        let hiding_bound = None;
+        let (comm, _) = KZG10::commit(&powers, &p, hiding_bound, Some(rng)).unwrap();
+        let (f_comm, _) = KZG10::commit(&powers, &f_p, hiding_bound, Some(rng)).unwrap();
+        let mut f_comm_2 = Commitment::default();
+        f_comm_2 += (f, &comm);
+
+        assert_eq!(f_comm, f_comm_2);
+    }
+
+    fn end_to_end_test_template<E: PairingEngine>() -> Result<(), Error> {
+        let rng = &mut test_rng();
+        for _ in 0..100 {
+            let mut degree = 0;
+            while degree <= 1 {
+                degree = usize::rand(rng) % 20;
+            }
+            let pp = KZG10::<E>::setup(degree, 1, rng)?;
+            let (ck, vk) = KZG10::trim(&pp, degree)?;
+            let p = DensePolynomial::rand(degree, rng);
+            let hiding_bound = Some(1);
+            let (comm, rand) = KZG10::commit(&ck, &p, hiding_bound, Some(rng))?;
+            let point = E::Fr::rand(rng);
+            let value = p.evaluate(point);
+            let proof = KZG10::open(&ck, &p, point, &rand)?;
+            assert!(
+                KZG10::check(&vk, &comm, point, value, &proof)?,
+                "proof was incorrect for max_degree = {}, polynomial_degree = {}, hiding_bound = {:?}",
+                degree,
+                p.degree(),
+                hiding_bound,
+            );
+            assert!(!KZG10::check(
+                &vk,
+                &comm,
+                point,
+                value + &E::Fr::one(),
+                &proof
+            )?);
+        }
+        Ok(())
+    }
+
+    fn linear_polynomial_test_template<E: PairingEngine>() -> Result<(), Error> {
+        let rng = &mut test_rng();
+        for _ in 0..100 {
+            let degree = 50;
+            let pp = KZG10::<E>::setup(degree, 1, rng)?;
+            let (ck, vk) = KZG10::trim(&pp, 2)?;
+            let p = DensePolynomial::rand(1, rng);
+            let hiding_bound = Some(1);
+            let (comm, rand) = KZG10::commit(&ck, &p, hiding_bound, Some(rng))?;
+            let point = E::Fr::rand(rng);
+            let value = p.evaluate(point);
+            let proof = KZG10::open(&ck, &p, point, &rand)?;
+            assert!(
+                KZG10::check(&vk, &comm, point, value, &proof)?,
+                "proof was incorrect for max_degree = {}, polynomial_degree = {}, hiding_bound = {:?}",
+                degree,
+                p.degree(),
+                hiding_bound,
+            );
+        }
+        Ok(())
+    }
+
+    fn batch_check_test_template<E: PairingEngine>() -> Result<(), Error> {
+        let rng = &mut test_rng();
+        for _ in 0..10 {
+            let mut degree = 0;
+            while degree <= 1 {
+                degree = usize::rand(rng) % 20;
+            }
+            let pp = KZG10::<E>::setup(degree, 1, rng)?;
+            let (ck, vk) = KZG10::trim(&pp, degree)?;
+            let mut comms = Vec::new();
+            let mut values = Vec::new();
+            let mut points = Vec::new();
+            let mut proofs = Vec::new();
+            for i in 0..10 {
+                let p = DensePolynomial::rand(degree, rng);
+                // Mix hiding and non-hiding commitments.
+                let hiding_bound = if i % 2 == 0 { Some(1) } else { None };
+                let (comm, rand) = KZG10::commit(&ck, &p, hiding_bound, Some(rng))?;
+                let point = E::Fr::rand(rng);
+                let value = p.evaluate(point);
+                let proof = KZG10::open(&ck, &p, point, &rand)?;
+
+                assert!(KZG10::check(&vk, &comm, point, value, &proof)?);
+                comms.push(comm);
+                values.push(value);
+                points.push(point);
+                proofs.push(proof);
+            }
+            assert!(KZG10::batch_check(
+                &vk, &comms, &points, &values, &proofs, rng
+            )?);
+
+            values[0] += &E::Fr::one();
+            assert!(!KZG10::batch_check(
+                &vk, &comms, &points, &values, &proofs, rng
+            )?);
+        }
+        Ok(())
+    }
+
+    fn multi_point_test_template<E: PairingEngine>() -> Result<(), Error> {
+        let rng = &mut test_rng();
+        for hiding_bound in [None, Some(4)].iter() {
+            let degree = 16;
+            let num_points = 4;
+            let pp = KZG10::<E>::setup(degree, num_points, rng)?;
+            let (ck, vk) = KZG10::trim(&pp, degree)?;
+
+            let mut polynomials = Vec::new();
+            let mut comms = Vec::new();
+            let mut rands = Vec::new();
+            for _ in 0..3 {
+                let p = DensePolynomial::rand(rng.gen_range(1, degree + 1), rng);
+                let (comm, rand) = KZG10::commit(&ck, &p, *hiding_bound, Some(rng))?;
+                polynomials.push(p);
+                comms.push(comm);
+                rands.push(rand);
+            }
+            let points = (0..num_points)
+                .map(|_| E::Fr::rand(rng))
+                .collect::<Vec<_>>();
+            let values = polynomials
+                .iter()
+                .map(|p| points.iter().map(|z| p.evaluate(*z)).collect())
+                .collect::<Vec<Vec<_>>>();
+            let opening_challenge = E::Fr::rand(rng);
+
+            let proof = KZG10::open_at_points(
+                &ck,
+                &polynomials.iter().collect::<Vec<_>>(),
+                &points,
+                opening_challenge,
+                &rands.iter().collect::<Vec<_>>(),
+            )?;
+            assert!(KZG10::check_at_points(
+                &vk,
+                &comms,
+                &points,
+                &values,
+                opening_challenge,
+                &proof
+            )?);
+
+            let mut bad_values = values.clone();
+            bad_values[1][2] += &E::Fr::one();
+            assert!(!KZG10::check_at_points(
+                &vk,
+                &comms,
+                &points,
+                &bad_values,
+                opening_challenge,
+                &proof
+            )?);
+
+            // Verifying at more points than the key supports must fail.
+            let too_many_points = (0..=num_points)
+                .map(|_| E::Fr::rand(rng))
+                .collect::<Vec<_>>();
+            match KZG10::check_at_points(
+                &vk,
+                &comms,
+                &too_many_points,
+                &values,
+                opening_challenge,
+                &proof,
+            ) {
+                Err(Error::TooManyPoints { .. }) => {},
+                _ => panic!("expected `Error::TooManyPoints`"),
+            }
+        }
+        Ok(())
+    }
+
+    fn lagrange_commitment_test_template<E: PairingEngine>() -> Result<(), Error> {
+        let rng = &mut test_rng();
+        for log_size in 2..6 {
+            let size = 1 << log_size;
+            let pp = KZG10::<E>::setup(size, 1, rng)?;
+            let (ck, vk) = KZG10::trim(&pp, size - 1)?;
+            let domain = GeneralEvaluationDomain::<E::Fr>::new(size).unwrap();
+            let lagrange_powers = KZG10::lagrange_powers(&pp, domain)?;
+
+            let p = DensePolynomial::rand(size - 1, rng);
+            let evaluations = p.evaluate_over_domain_by_ref(domain);
+            let (lagrange_comm, _) =
+                KZG10::commit_lagrange(&lagrange_powers, &evaluations, None, None)?;
+            let (comm, _) = KZG10::commit(&ck, &p, None, None)?;
+            assert_eq!(lagrange_comm, comm);
+
+            let (hiding_comm, rand) =
+                KZG10::commit_lagrange(&lagrange_powers, &evaluations, Some(1), Some(rng))?;
+            let point = E::Fr::rand(rng);
+            let proof = KZG10::open(&ck, &p, point, &rand)?;
+            assert!(KZG10::check(
+                &vk,
+                &hiding_comm,
+                point,
+                p.evaluate(point),
+                &proof
+            )?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn end_to_end_test() {
+        end_to_end_test_template::<Bls12_377>().expect("test failed for bls12-377");
+        end_to_end_test_template::<Bls12_381>().expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn linear_polynomial_test() {
+        linear_polynomial_test_template::<Bls12_377>().expect("test failed for bls12-377");
+        linear_polynomial_test_template::<Bls12_381>().expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn batch_check_test() {
+        batch_check_test_template::<Bls12_377>().expect("test failed for bls12-377");
+        batch_check_test_template::<Bls12_381>().expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn multi_point_test() {
+        multi_point_test_template::<Bls12_377>().expect("test failed for bls12-377");
+        multi_point_test_template::<Bls12_381>().expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn lagrange_commitment_test() {
+        lagrange_commitment_test_template::<Bls12_377>().expect("test failed for bls12-377");
+        lagrange_commitment_test_template::<Bls12_381>().expect("test failed for bls12-381");
+    }
+
+    #[test]
+    fn hiding_bound_errors() {
+        let rng = &mut test_rng();
+        let pp = KZGBls12_381::setup(4, 1, rng).unwrap();
+        let (ck, _) = KZGBls12_381::trim(&pp, 4).unwrap();
+        let p = DensePolynomial::rand(3, rng);
+        match KZGBls12_381::commit(&ck, &p, Some(0), Some(rng)) {
+            Err(Error::HidingBoundIsZero) => {},
+            _ => panic!("expected `Error::HidingBoundIsZero`"),
+        }
+        match KZGBls12_381::commit(&ck, &p, Some(4), Some(rng)) {
+            Err(Error::HidingBoundToolarge { .. }) => {},
+            _ => panic!("expected `Error::HidingBoundToolarge`"),
+        }
+        match KZGBls12_381::commit(&ck, &p, Some(1), None) {
+            Err(Error::MissingRng) => {},
+            _ => panic!("expected `Error::MissingRng`"),
+        }
+        let p = DensePolynomial::rand(5, rng);
+        match KZGBls12_381::commit(&ck, &p, None, None) {
+            Err(Error::TooManyCoefficients { .. }) => {},
+            _ => panic!("expected `Error::TooManyCoefficients`"),
+        }
+    }
+}
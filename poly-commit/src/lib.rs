@@ -0,0 +1,38 @@
+//! A crate for polynomial commitment schemes.
+//!
+//! Currently this crate implements the pairing-based scheme of
+//! [Kate, Zaverucha and Goldberg][kzg10].
+//!
+//! [kzg10]: http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_import_braces, unused_qualifications, trivial_casts)]
+#![deny(trivial_numeric_casts, private_in_public, variant_size_differences)]
+#![deny(stable_features, unreachable_pub, non_shorthand_field_patterns)]
+#![deny(unused_attributes, unused_imports, unused_mut, missing_docs)]
+#![deny(renamed_and_removed_lints, unused_allocation)]
+#![deny(unused_comparisons, bare_trait_objects, unused_must_use, const_err)]
+#![forbid(unsafe_code)]
+
+#[macro_use]
+extern crate bench_utils;
+
+#[macro_use]
+extern crate derivative;
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+pub(crate) use alloc::{borrow::Cow, vec::Vec};
+
+#[cfg(feature = "std")]
+pub(crate) use std::{borrow::Cow, vec::Vec};
+
+mod error;
+pub use error::*;
+
+/// The polynomial commitment scheme of [Kate, Zaverucha and Goldberg][kzg10].
+///
+/// [kzg10]: http://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf
+pub mod kzg10;
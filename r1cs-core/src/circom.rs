@@ -0,0 +1,488 @@
+//! Reading and writing the binary `.r1cs` and `.wtns` formats of
+//! [circom](https://github.com/iden3/circom).
+//!
+//! A `.r1cs` file describes the constraints over a list of wires, where wire
+//! 0 is the constant one, followed by the public outputs, the public inputs
+//! and the private wires. A `.wtns` file assigns a value to each wire. Both
+//! formats consist of a magic string, a version and a list of sections, each
+//! with a type and a size, and store integers and field elements in
+//! little-endian order. Field elements are stored as integers in
+//! `[0, modulus)`, with as many bytes as the header specifies.
+//!
+//! Circom wires map to the variables of [`ConstraintMatrices`] as follows:
+//! public wires map to public inputs, in order, and the remaining wires map to
+//! private variables, in order. [`ConstraintMatrices`] implements
+//! [`ConstraintSynthesizer`](crate::ConstraintSynthesizer), so an imported
+//! circuit can be given to any proving system, and any circuit can be
+//! exported with [`ConstraintMatrices::synthesize`].
+
+use crate::Vec;
+use algebra_core::{
+    io::{Read, Write},
+    BigInteger, FpParameters, PrimeField, SerializationError, ToBytes,
+};
+
+use crate::{constraint_matrices::canonicalize_row, ConstraintMatrices, Index, Matrix};
+
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+const R1CS_VERSION: u32 = 1;
+const R1CS_HEADER: u32 = 1;
+const R1CS_CONSTRAINTS: u32 = 2;
+const R1CS_WIRE_TO_LABEL: u32 = 3;
+
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+const WTNS_VERSION: u32 = 2;
+const WTNS_HEADER: u32 = 1;
+const WTNS_WITNESS: u32 = 2;
+
+/// Reads the constraints of a `.r1cs` file. The assignment of the returned
+/// matrices is missing; use [`read_wtns`] to set it.
+///
+/// Fails with `SerializationError::InvalidData` if the file is malformed, if
+/// its prime differs from the modulus of `F`, or if it uses features that
+/// cannot be represented as rank-1 constraints, such as custom gates.
+pub fn read_r1cs<F: PrimeField, R: Read>(
+    reader: R,
+) -> Result<ConstraintMatrices<F>, SerializationError> {
+    let sections = read_sections(reader, R1CS_MAGIC, R1CS_VERSION)?;
+    let mut header = &find_section(&sections, R1CS_HEADER)?[..];
+    read_field_size::<F>(&mut header)?;
+    let num_wires = read_u32(&mut header)? as usize;
+    let num_public_outputs = read_u32(&mut header)? as usize;
+    let num_public_inputs = read_u32(&mut header)? as usize;
+    let _num_private_inputs = read_u32(&mut header)?;
+    let _num_labels = read_u64(&mut header)?;
+    let num_constraints = read_u32(&mut header)? as usize;
+    if sections.iter().any(|(ty, _)| *ty > R1CS_WIRE_TO_LABEL) {
+        return Err(SerializationError::InvalidData);
+    }
+
+    let num_inputs = 1 + num_public_outputs + num_public_inputs;
+    if num_inputs > num_wires {
+        return Err(SerializationError::InvalidData);
+    }
+    let mut matrices = ConstraintMatrices {
+        num_inputs,
+        num_aux: num_wires - num_inputs,
+        a: Matrix::new(),
+        b: Matrix::new(),
+        c: Matrix::new(),
+        input_assignment: None,
+        aux_assignment: None,
+    };
+    let mut constraints = &find_section(&sections, R1CS_CONSTRAINTS)?[..];
+    for _ in 0..num_constraints {
+        for matrix in &mut [&mut matrices.a, &mut matrices.b, &mut matrices.c] {
+            let num_entries = read_u32(&mut constraints)? as usize;
+            let mut row = Vec::with_capacity(num_entries.min(num_wires));
+            for _ in 0..num_entries {
+                let wire = read_u32(&mut constraints)? as usize;
+                let index = if wire < num_inputs {
+                    Index::Input(wire)
+                } else {
+                    Index::Aux(wire - num_inputs)
+                };
+                row.push((read_field::<F, _>(&mut constraints)?, index));
+            }
+            matrix.push(canonicalize_row(row));
+        }
+    }
+    if !constraints.is_empty() || !matrices.is_well_formed() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(matrices)
+}
+
+/// Reads the wire values of a `.wtns` file into the assignment of
+/// `matrices`.
+///
+/// Fails with `SerializationError::InvalidData` if the file is malformed, if
+/// its prime differs from the modulus of `F`, if the number of wires differs
+/// from that of `matrices`, or if wire 0 is not one.
+pub fn read_wtns<F: PrimeField, R: Read>(
+    reader: R,
+    matrices: &mut ConstraintMatrices<F>,
+) -> Result<(), SerializationError> {
+    let sections = read_sections(reader, WTNS_MAGIC, WTNS_VERSION)?;
+    let mut header = &find_section(&sections, WTNS_HEADER)?[..];
+    read_field_size::<F>(&mut header)?;
+    let num_wires = read_u32(&mut header)? as usize;
+    if num_wires != matrices.num_inputs + matrices.num_aux {
+        return Err(SerializationError::InvalidData);
+    }
+
+    let mut witness = &find_section(&sections, WTNS_WITNESS)?[..];
+    let mut values = Vec::with_capacity(num_wires);
+    for _ in 0..num_wires {
+        values.push(read_field::<F, _>(&mut witness)?);
+    }
+    if !witness.is_empty() || !values[0].is_one() {
+        return Err(SerializationError::InvalidData);
+    }
+    let aux = values.split_off(matrices.num_inputs);
+    matrices.input_assignment = Some(values);
+    matrices.aux_assignment = Some(aux);
+    Ok(())
+}
+
+/// Writes the constraints of `matrices` as a `.r1cs` file, with all public
+/// inputs as circom public inputs and all private variables as internal
+/// wires. Each wire is its own label.
+pub fn write_r1cs<F: PrimeField, W: Write>(
+    matrices: &ConstraintMatrices<F>,
+    mut writer: W,
+) -> Result<(), SerializationError> {
+    let num_wires = matrices.num_inputs + matrices.num_aux;
+    let mut header = Vec::new();
+    write_field_size::<F, _>(&mut header)?;
+    write_u32(&mut header, num_wires)?;
+    write_u32(&mut header, 0)?;
+    write_u32(&mut header, matrices.num_inputs - 1)?;
+    write_u32(&mut header, 0)?;
+    (num_wires as u64).write(&mut header)?;
+    write_u32(&mut header, matrices.a.len())?;
+
+    let mut constraints = Vec::new();
+    for ((a, b), c) in matrices.a.iter().zip(&matrices.b).zip(&matrices.c) {
+        for row in &[a, b, c] {
+            write_u32(&mut constraints, row.len())?;
+            for (coeff, index) in row.iter() {
+                let wire = match *index {
+                    Index::Input(i) => i,
+                    Index::Aux(i) => matrices.num_inputs + i,
+                };
+                write_u32(&mut constraints, wire)?;
+                coeff.into_repr().write(&mut constraints)?;
+            }
+        }
+    }
+
+    let mut labels = Vec::with_capacity(8 * num_wires);
+    for wire in 0..num_wires as u64 {
+        wire.write(&mut labels)?;
+    }
+
+    write_sections(
+        &mut writer,
+        R1CS_MAGIC,
+        R1CS_VERSION,
+        &[
+            (R1CS_HEADER, header),
+            (R1CS_CONSTRAINTS, constraints),
+            (R1CS_WIRE_TO_LABEL, labels),
+        ],
+    )
+}
+
+/// Writes the assignment of `matrices` as a `.wtns` file.
+///
+/// Fails with `SerializationError::InvalidData` if the assignment is missing.
+pub fn write_wtns<F: PrimeField, W: Write>(
+    matrices: &ConstraintMatrices<F>,
+    mut writer: W,
+) -> Result<(), SerializationError> {
+    let (inputs, aux) = match (&matrices.input_assignment, &matrices.aux_assignment) {
+        (Some(inputs), Some(aux)) => (inputs, aux),
+        _ => return Err(SerializationError::InvalidData),
+    };
+    let mut header = Vec::new();
+    write_field_size::<F, _>(&mut header)?;
+    write_u32(&mut header, inputs.len() + aux.len())?;
+
+    let mut witness = Vec::new();
+    for value in inputs.iter().chain(aux) {
+        value.into_repr().write(&mut witness)?;
+    }
+
+    write_sections(
+        &mut writer,
+        WTNS_MAGIC,
+        WTNS_VERSION,
+        &[(WTNS_HEADER, header), (WTNS_WITNESS, witness)],
+    )
+}
+
+/// The number of bytes with which elements of `F` are written.
+fn field_size<F: PrimeField>() -> usize {
+    8 * <F::BigInt as BigInteger>::NUM_LIMBS
+}
+
+/// Reads the size of field elements and the prime from a header, and checks
+/// that they are those of `F`. The size is checked before anything else is
+/// read, so that an untrusted size never determines an allocation.
+fn read_field_size<F: PrimeField>(reader: &mut &[u8]) -> Result<(), SerializationError> {
+    let n8 = read_u32(&mut *reader)? as usize;
+    if n8 != field_size::<F>() {
+        return Err(SerializationError::InvalidData);
+    }
+    let modulus = read_integer::<F::BigInt, _>(&mut *reader)?;
+    if modulus != F::Params::MODULUS {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(())
+}
+
+fn write_field_size<F: PrimeField, W: Write>(mut writer: W) -> Result<(), SerializationError> {
+    write_u32(&mut writer, field_size::<F>())?;
+    F::Params::MODULUS.write(&mut writer)?;
+    Ok(())
+}
+
+/// Reads a little-endian integer of `8 * B::NUM_LIMBS` bytes.
+fn read_integer<B: BigInteger, R: Read>(reader: R) -> Result<B, SerializationError> {
+    Ok(B::read(reader)?)
+}
+
+/// Reads a field element. Fails unless it is smaller than the modulus.
+fn read_field<F: PrimeField, R: Read>(reader: R) -> Result<F, SerializationError> {
+    F::from_repr(read_integer(reader)?).ok_or(SerializationError::InvalidData)
+}
+
+fn read_u32<R: Read>(mut reader: R) -> Result<u32, SerializationError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(mut reader: R) -> Result<u64, SerializationError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Writes `value` as a `u32`. Fails if it does not fit.
+fn write_u32<W: Write>(mut writer: W, value: usize) -> Result<(), SerializationError> {
+    if value > u32::MAX as usize {
+        return Err(SerializationError::InvalidData);
+    }
+    writer.write_all(&(value as u32).to_le_bytes())?;
+    Ok(())
+}
+
+/// Reads the magic string, the version and the sections of a file, as
+/// `(type, contents)` pairs. Sections may appear in any order.
+fn read_sections<R: Read>(
+    mut reader: R,
+    magic: &[u8; 4],
+    version: u32,
+) -> Result<Vec<(u32, Vec<u8>)>, SerializationError> {
+    let mut file_magic = [0u8; 4];
+    reader.read_exact(&mut file_magic)?;
+    if &file_magic != magic || read_u32(&mut reader)? != version {
+        return Err(SerializationError::InvalidData);
+    }
+    let num_sections = read_u32(&mut reader)?;
+    let mut sections = Vec::new();
+    for _ in 0..num_sections {
+        let ty = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)? as usize;
+        // Grow the buffer as bytes arrive rather than trusting `size`.
+        let mut contents = Vec::new();
+        let mut chunk = [0u8; 4096];
+        while contents.len() < size {
+            let n = chunk.len().min(size - contents.len());
+            reader.read_exact(&mut chunk[..n])?;
+            contents.extend_from_slice(&chunk[..n]);
+        }
+        sections.push((ty, contents));
+    }
+    Ok(sections)
+}
+
+/// Returns the contents of the section of type `ty`. Fails unless there is
+/// exactly one such section.
+fn find_section(sections: &[(u32, Vec<u8>)], ty: u32) -> Result<&Vec<u8>, SerializationError> {
+    let mut matching = sections.iter().filter(|(t, _)| *t == ty);
+    match (matching.next(), matching.next()) {
+        (Some((_, contents)), None) => Ok(contents),
+        _ => Err(SerializationError::InvalidData),
+    }
+}
+
+fn write_sections<W: Write>(
+    mut writer: W,
+    magic: &[u8; 4],
+    version: u32,
+    sections: &[(u32, Vec<u8>)],
+) -> Result<(), SerializationError> {
+    writer.write_all(magic)?;
+    version.write(&mut writer)?;
+    (sections.len() as u32).write(&mut writer)?;
+    for (ty, contents) in sections {
+        ty.write(&mut writer)?;
+        (contents.len() as u64).write(&mut writer)?;
+        writer.write_all(contents)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+    use algebra::{
+        bls12_381::{Fq, Fr},
+        Field, Zero,
+    };
+
+    /// Proves knowledge of `x` such that `x^2 = y` and `x + 2y = z` for public
+    /// `y` and `z`.
+    struct Square(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Square {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let x_val = self.0;
+            let y_val = x_val.map(|x| x.square());
+            let z_val = x_val.map(|x| x + &x.square().double());
+            let y = cs.alloc_input(|| "y", || y_val.ok_or(SynthesisError::AssignmentMissing))?;
+            let z = cs.alloc_input(|| "z", || z_val.ok_or(SynthesisError::AssignmentMissing))?;
+            let x = cs.alloc(|| "x", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "x^2", |lc| lc + x, |lc| lc + x, |lc| lc + y);
+            cs.enforce(
+                || "sum",
+                |lc| lc + x + (Fr::from(2u64), y),
+                |lc| lc + CS::one(),
+                |lc| lc + z,
+            );
+            Ok(())
+        }
+    }
+
+    fn field_element(value: u64) -> Vec<u8> {
+        let mut bytes = value.to_le_bytes().to_vec();
+        bytes.resize(32, 0);
+        bytes
+    }
+
+    fn section(ty: u32, contents: &[u8]) -> Vec<u8> {
+        let mut bytes = ty.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(contents);
+        bytes
+    }
+
+    fn modulus_bytes() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        <Fr as PrimeField>::Params::MODULUS
+            .write(&mut bytes)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn reads_circom_files() {
+        // The `Square` circuit as circom would emit it, with one public
+        // output `y`, one public input `z` and one private input `x`, and with
+        // the constraint section ahead of the header.
+        let mut header = 32u32.to_le_bytes().to_vec();
+        header.extend(modulus_bytes());
+        for n in &[4u32, 1, 1, 1] {
+            header.extend_from_slice(&n.to_le_bytes());
+        }
+        header.extend_from_slice(&4u64.to_le_bytes());
+        header.extend_from_slice(&2u32.to_le_bytes());
+        let mut constraints = Vec::new();
+        let rows: &[&[(u32, u64)]] = &[
+            &[(3, 1)],
+            &[(3, 1)],
+            &[(1, 1)],
+            &[(1, 2), (3, 1)],
+            &[(0, 1)],
+            &[(2, 1)],
+        ];
+        for row in rows {
+            constraints.extend_from_slice(&(row.len() as u32).to_le_bytes());
+            for (wire, coeff) in row.iter() {
+                constraints.extend_from_slice(&wire.to_le_bytes());
+                constraints.extend(field_element(*coeff));
+            }
+        }
+        let mut r1cs = b"r1cs".to_vec();
+        r1cs.extend_from_slice(&1u32.to_le_bytes());
+        r1cs.extend_from_slice(&2u32.to_le_bytes());
+        r1cs.extend(section(2, &constraints));
+        r1cs.extend(section(1, &header));
+
+        let mut matrices = read_r1cs::<Fr, _>(&r1cs[..]).unwrap();
+        let expected = ConstraintMatrices::synthesize(Square(None)).unwrap();
+        assert_eq!(matrices, expected);
+
+        let mut wtns = b"wtns".to_vec();
+        wtns.extend_from_slice(&2u32.to_le_bytes());
+        wtns.extend_from_slice(&2u32.to_le_bytes());
+        let mut header = 32u32.to_le_bytes().to_vec();
+        header.extend(modulus_bytes());
+        header.extend_from_slice(&4u32.to_le_bytes());
+        wtns.extend(section(1, &header));
+        let witness: Vec<u8> = [1, 9, 21, 3]
+            .iter()
+            .flat_map(|v| field_element(*v))
+            .collect();
+        wtns.extend(section(2, &witness));
+
+        read_wtns(&wtns[..], &mut matrices).unwrap();
+        assert_eq!(
+            matrices,
+            ConstraintMatrices::synthesize(Square(Some(Fr::from(3u64)))).unwrap()
+        );
+        assert!(matrices.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let matrices = ConstraintMatrices::synthesize(Square(Some(Fr::from(5u64)))).unwrap();
+        let mut r1cs = Vec::new();
+        write_r1cs(&matrices, &mut r1cs).unwrap();
+        let mut wtns = Vec::new();
+        write_wtns(&matrices, &mut wtns).unwrap();
+
+        let mut read = read_r1cs::<Fr, _>(&r1cs[..]).unwrap();
+        assert_eq!(read.input_assignment, None);
+        read_wtns(&wtns[..], &mut read).unwrap();
+        assert_eq!(read, matrices);
+
+        let setup = ConstraintMatrices::synthesize(Square(None)).unwrap();
+        assert!(matches!(
+            write_wtns(&setup, &mut Vec::new()),
+            Err(SerializationError::InvalidData)
+        ));
+    }
+
+    #[test]
+    fn rejects_other_fields() {
+        let matrices = ConstraintMatrices::synthesize(Square(Some(Fr::zero()))).unwrap();
+        let mut r1cs = Vec::new();
+        write_r1cs(&matrices, &mut r1cs).unwrap();
+        assert!(matches!(
+            read_r1cs::<Fq, _>(&r1cs[..]),
+            Err(SerializationError::InvalidData)
+        ));
+
+        let mut wtns = Vec::new();
+        write_wtns(&matrices, &mut wtns).unwrap();
+        let mut other = ConstraintMatrices::<Fq>::new();
+        other.num_aux = matrices.num_inputs + matrices.num_aux - 1;
+        assert!(matches!(
+            read_wtns(&wtns[..], &mut other),
+            Err(SerializationError::InvalidData)
+        ));
+    }
+
+    #[test]
+    fn rejects_other_field_sizes() {
+        // A huge field size must be rejected without allocating the prime.
+        let mut header = u32::MAX.to_le_bytes().to_vec();
+        header.extend(modulus_bytes());
+        let mut r1cs = b"r1cs".to_vec();
+        r1cs.extend_from_slice(&1u32.to_le_bytes());
+        r1cs.extend_from_slice(&1u32.to_le_bytes());
+        r1cs.extend(section(1, &header));
+        assert!(matches!(
+            read_r1cs::<Fr, _>(&r1cs[..]),
+            Err(SerializationError::InvalidData)
+        ));
+    }
+}
@@ -0,0 +1,526 @@
+use crate::{format, String, Vec};
+use algebra_core::{
+    io::{Read, Write},
+    serialize::*,
+    Field,
+};
+
+use crate::{
+    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+};
+
+/// A sparse matrix, stored row by row. Each row lists the nonzero entries of
+/// one constraint as `(coefficient, variable)` pairs, sorted by variable, with
+/// public inputs ordered before private variables.
+pub type Matrix<F> = Vec<Vec<(F, Index)>>;
+
+/// The `A`, `B` and `C` matrices of a rank-1 constraint system, together with
+/// the assignment of its variables.
+///
+/// The assignment `z` satisfies the system if `(A z) ∘ (B z) = C z`, where
+/// `z` consists of the public inputs followed by the private variables. Input
+/// 0 is the "one" input, whose assignment is always one.
+///
+/// `ConstraintMatrices` is itself a [`ConstraintSystem`]: synthesizing a
+/// circuit into it records the circuit's constraints and assignment, without
+/// any proving system specific processing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintMatrices<F: Field> {
+    /// The number of public inputs, including the "one" input.
+    pub num_inputs: usize,
+    /// The number of private variables.
+    pub num_aux: usize,
+    /// The left inputs of the constraints.
+    pub a: Matrix<F>,
+    /// The right inputs of the constraints.
+    pub b: Matrix<F>,
+    /// The outputs of the constraints.
+    pub c: Matrix<F>,
+    /// The assignment of the public inputs, starting with the "one" input, or
+    /// `None` if it could not be computed, such as during setup.
+    pub input_assignment: Option<Vec<F>>,
+    /// The assignment of the private variables, or `None` if it could not be
+    /// computed, such as during setup or verification.
+    pub aux_assignment: Option<Vec<F>>,
+}
+
+impl<F: Field> Default for ConstraintMatrices<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> ConstraintMatrices<F> {
+    /// Creates a constraint system without constraints, whose only variable is
+    /// the "one" input.
+    pub fn new() -> Self {
+        Self {
+            num_inputs: 1,
+            num_aux: 0,
+            a: Vec::new(),
+            b: Vec::new(),
+            c: Vec::new(),
+            input_assignment: Some(vec![F::one()]),
+            aux_assignment: Some(Vec::new()),
+        }
+    }
+
+    /// Synthesizes `circuit` and records its constraints and assignment.
+    pub fn synthesize<C: ConstraintSynthesizer<F>>(circuit: C) -> Result<Self, SynthesisError> {
+        let mut cs = Self::new();
+        circuit.generate_constraints(&mut cs)?;
+        Ok(cs)
+    }
+
+    /// Synthesizes `circuit` in setup mode and records its constraints,
+    /// without computing an assignment.
+    pub fn synthesize_setup<C: ConstraintSynthesizer<F>>(
+        circuit: C,
+    ) -> Result<Self, SynthesisError> {
+        let mut cs = Self::new();
+        cs.input_assignment = None;
+        cs.aux_assignment = None;
+        circuit.generate_constraints(&mut cs)?;
+        Ok(cs)
+    }
+
+    /// Returns the index of the first constraint that is not satisfied by the
+    /// assignment, or `None` if all constraints are satisfied.
+    ///
+    /// Returns `SynthesisError::AssignmentMissing` if the assignment is
+    /// missing.
+    pub fn which_is_unsatisfied(&self) -> Result<Option<usize>, SynthesisError> {
+        let inputs = self
+            .input_assignment
+            .as_ref()
+            .ok_or(SynthesisError::AssignmentMissing)?;
+        let aux = self
+            .aux_assignment
+            .as_ref()
+            .ok_or(SynthesisError::AssignmentMissing)?;
+        let eval = |row: &[(F, Index)]| {
+            row.iter().fold(F::zero(), |acc, (coeff, index)| {
+                let value = match *index {
+                    Index::Input(i) => inputs[i],
+                    Index::Aux(i) => aux[i],
+                };
+                acc + &(value * coeff)
+            })
+        };
+        for (i, ((a, b), c)) in self.a.iter().zip(&self.b).zip(&self.c).enumerate() {
+            if eval(a) * &eval(b) != eval(c) {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns whether all constraints are satisfied by the assignment.
+    ///
+    /// Returns `SynthesisError::AssignmentMissing` if the assignment is
+    /// missing.
+    pub fn is_satisfied(&self) -> Result<bool, SynthesisError> {
+        self.which_is_unsatisfied().map(|i| i.is_none())
+    }
+
+    /// Returns whether the rows are well formed: each matrix has one row per
+    /// constraint, the entries of a row are nonzero and sorted by strictly
+    /// increasing variable, all variables are in range, and the assignments
+    /// have the right lengths.
+    pub fn is_well_formed(&self) -> bool {
+        let num_constraints = self.a.len();
+        let rows_are_valid = [&self.a, &self.b, &self.c].iter().all(|matrix| {
+            matrix.len() == num_constraints
+                && matrix.iter().all(|row| {
+                    row.windows(2).all(|w| w[0].1 < w[1].1)
+                        && row.iter().all(|(coeff, index)| {
+                            !coeff.is_zero()
+                                && match *index {
+                                    Index::Input(i) => i < self.num_inputs,
+                                    Index::Aux(i) => i < self.num_aux,
+                                }
+                        })
+                })
+        });
+        let inputs_are_valid = self.input_assignment.as_ref().map_or(true, |inputs| {
+            inputs.len() == self.num_inputs && inputs.first().map_or(false, F::is_one)
+        });
+        let aux_is_valid = self
+            .aux_assignment
+            .as_ref()
+            .map_or(true, |aux| aux.len() == self.num_aux);
+        self.num_inputs > 0 && rows_are_valid && inputs_are_valid && aux_is_valid
+    }
+}
+
+/// Converts `lc` into a row.
+fn to_row<F: Field>(lc: LinearCombination<F>) -> Vec<(F, Index)> {
+    canonicalize_row(
+        lc.as_ref()
+            .iter()
+            .map(|(var, coeff)| (*coeff, var.get_unchecked()))
+            .collect(),
+    )
+}
+
+/// Sorts the entries of `row` by variable, merges the entries of each variable
+/// and drops those whose coefficient is zero.
+pub(crate) fn canonicalize_row<F: Field>(mut entries: Vec<(F, Index)>) -> Vec<(F, Index)> {
+    entries.sort_by_key(|(_, index)| *index);
+    let mut row: Vec<(F, Index)> = Vec::with_capacity(entries.len());
+    for (coeff, index) in entries {
+        match row.last_mut() {
+            Some(last) if last.1 == index => last.0 += &coeff,
+            _ => row.push((coeff, index)),
+        }
+    }
+    row.retain(|(coeff, _)| !coeff.is_zero());
+    row
+}
+
+/// Pushes the result of `f` onto `assignment`, or discards the whole
+/// assignment if `f` reports a missing assignment. Once discarded, `f` is no
+/// longer invoked.
+fn push_assignment<F: Field>(
+    assignment: &mut Option<Vec<F>>,
+    f: impl FnOnce() -> Result<F, SynthesisError>,
+) -> Result<(), SynthesisError> {
+    if let Some(values) = assignment {
+        match f() {
+            Ok(value) => values.push(value),
+            Err(e) => match e.root_cause() {
+                SynthesisError::AssignmentMissing => *assignment = None,
+                _ => return Err(e),
+            },
+        }
+    }
+    Ok(())
+}
+
+impl<F: Field> ConstraintSystem<F> for ConstraintMatrices<F> {
+    type Root = Self;
+
+    fn alloc<FN, A, AR>(&mut self, _: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        push_assignment(&mut self.aux_assignment, f)?;
+        let index = self.num_aux;
+        self.num_aux += 1;
+        Ok(Variable::new_unchecked(Index::Aux(index)))
+    }
+
+    fn alloc_input<FN, A, AR>(&mut self, _: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        push_assignment(&mut self.input_assignment, f)?;
+        let index = self.num_inputs;
+        self.num_inputs += 1;
+        Ok(Variable::new_unchecked(Index::Input(index)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        self.a.push(to_row(a(LinearCombination::zero())));
+        self.b.push(to_row(b(LinearCombination::zero())));
+        self.c.push(to_row(c(LinearCombination::zero())));
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self) {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.a.len()
+    }
+
+    fn supports_parallel_synthesis(&self) -> bool {
+        true
+    }
+
+    /// Returns whether both assignments are missing, so that there is no
+    /// assignment left to record.
+    fn is_in_setup_mode(&self) -> bool {
+        self.input_assignment.is_none() && self.aux_assignment.is_none()
+    }
+}
+
+/// Replays the recorded variables and constraints, so that a circuit can be
+/// proven again without being synthesized again, or a circuit imported from
+/// another format can be proven. A missing assignment is reported as
+/// `SynthesisError::AssignmentMissing`.
+impl<F: Field> ConstraintSynthesizer<F> for ConstraintMatrices<F> {
+    fn generate_constraints<CS: ConstraintSystem<F>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let value =
+            |assignment: &Option<Vec<F>>, i: usize| assignment.as_ref().map(|values| values[i]);
+        replay(
+            cs,
+            self.num_inputs,
+            self.num_aux,
+            |i| value(&self.input_assignment, i),
+            |i| value(&self.aux_assignment, i),
+            self.a
+                .iter()
+                .zip(&self.b)
+                .zip(&self.c)
+                .map(|((a, b), c)| (a.iter().cloned(), b.iter().cloned(), c.iter().cloned())),
+        )
+    }
+}
+
+/// Allocates `num_inputs - 1` public inputs, after the "one" input, and
+/// `num_aux` private variables in `cs`, with the values returned by
+/// `input_value` and `aux_value`, and enforces `constraints`, whose entries
+/// refer to these variables by index. A missing value is reported to `cs` as
+/// `SynthesisError::AssignmentMissing`.
+fn replay<F, CS, R>(
+    cs: &mut CS,
+    num_inputs: usize,
+    num_aux: usize,
+    input_value: impl Fn(usize) -> Option<F>,
+    aux_value: impl Fn(usize) -> Option<F>,
+    constraints: impl Iterator<Item = (R, R, R)>,
+) -> Result<(), SynthesisError>
+where
+    F: Field,
+    CS: ConstraintSystem<F>,
+    R: IntoIterator<Item = (F, Index)>,
+{
+    let mut inputs = Vec::with_capacity(num_inputs);
+    inputs.push(CS::one());
+    for i in 1..num_inputs {
+        inputs.push(cs.alloc_input(
+            || format!("input {}", i),
+            || input_value(i).ok_or(SynthesisError::AssignmentMissing),
+        )?);
+    }
+    let mut aux = Vec::with_capacity(num_aux);
+    for i in 0..num_aux {
+        aux.push(cs.alloc(
+            || format!("aux {}", i),
+            || aux_value(i).ok_or(SynthesisError::AssignmentMissing),
+        )?);
+    }
+    let map = |row: R, mut lc: LinearCombination<F>| {
+        for (coeff, index) in row {
+            let var = match index {
+                Index::Input(i) => inputs[i],
+                Index::Aux(i) => aux[i],
+            };
+            lc += (coeff, var);
+        }
+        lc
+    };
+    for (i, (a, b, c)) in constraints.enumerate() {
+        cs.enforce(
+            || format!("constraint {}", i),
+            |lc| map(a, lc),
+            |lc| map(b, lc),
+            |lc| map(c, lc),
+        );
+    }
+    Ok(())
+}
+
+impl<F: Field> CanonicalSerialize for ConstraintMatrices<F> {
+    #[inline]
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.num_inputs.serialize(&mut writer)?;
+        self.num_aux.serialize(&mut writer)?;
+        self.a.serialize(&mut writer)?;
+        self.b.serialize(&mut writer)?;
+        self.c.serialize(&mut writer)?;
+        self.input_assignment.serialize(&mut writer)?;
+        self.aux_assignment.serialize(&mut writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.num_inputs.serialized_size()
+            + self.num_aux.serialized_size()
+            + self.a.serialized_size()
+            + self.b.serialized_size()
+            + self.c.serialized_size()
+            + self.input_assignment.serialized_size()
+            + self.aux_assignment.serialized_size()
+    }
+}
+
+impl<F: Field> CanonicalDeserialize for ConstraintMatrices<F> {
+    /// Fails unless the matrices have one row per constraint, each row is
+    /// sorted by strictly increasing variable and has only nonzero entries,
+    /// every variable is in range, and the assignments have the right lengths.
+    #[inline]
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let matrices = Self {
+            num_inputs: usize::deserialize(&mut reader)?,
+            num_aux: usize::deserialize(&mut reader)?,
+            a: Matrix::deserialize(&mut reader)?,
+            b: Matrix::deserialize(&mut reader)?,
+            c: Matrix::deserialize(&mut reader)?,
+            input_assignment: Option::deserialize(&mut reader)?,
+            aux_assignment: Option::deserialize(&mut reader)?,
+        };
+        if !matrices.is_well_formed() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(matrices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algebra::{bls12_381::Fr, One};
+
+    /// Proves knowledge of `x` such that `x^3 + x + 5 = y` for a public `y`.
+    struct Cubic(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Cubic {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let x_val = self.0;
+            let x_sq_val = x_val.map(|x| x.square());
+            let y_val = x_val.map(|x| x.square() * &x + &x + &Fr::from(5u64));
+            let x = cs.alloc(|| "x", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+            let x_sq = cs.alloc(
+                || "x^2",
+                || x_sq_val.ok_or(SynthesisError::AssignmentMissing),
+            )?;
+            let y = cs.alloc_input(|| "y", || y_val.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "x^2", |lc| lc + x, |lc| lc + x, |lc| lc + x_sq);
+            cs.enforce(
+                || "y",
+                |lc| lc + x_sq,
+                |lc| lc + x,
+                // The `x` terms cancel and are dropped from the row.
+                |lc| lc + y + x - x - (Fr::from(5u64), CS::one()) - x,
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn records_matrices_and_assignment() {
+        let matrices = ConstraintMatrices::synthesize(Cubic(Some(Fr::from(3u64)))).unwrap();
+        assert_eq!(matrices.num_inputs, 2);
+        assert_eq!(matrices.num_aux, 2);
+        assert_eq!(matrices.num_constraints(), 2);
+        assert_eq!(matrices.a[0], vec![(Fr::one(), Index::Aux(0))]);
+        assert_eq!(
+            matrices.c[1],
+            vec![
+                (-Fr::from(5u64), Index::Input(0)),
+                (Fr::one(), Index::Input(1)),
+                (-Fr::one(), Index::Aux(0)),
+            ]
+        );
+        assert_eq!(
+            matrices.input_assignment,
+            Some(vec![Fr::one(), Fr::from(35u64)])
+        );
+        assert!(matrices.is_well_formed());
+        assert!(matrices.is_satisfied().unwrap());
+
+        let mut wrong = matrices.clone();
+        wrong.aux_assignment.as_mut().unwrap()[1] = Fr::from(10u64);
+        assert_eq!(wrong.which_is_unsatisfied().unwrap(), Some(0));
+    }
+
+    #[test]
+    fn records_matrices_without_assignment() {
+        let matrices = ConstraintMatrices::synthesize(Cubic(None)).unwrap();
+        let with_assignment = ConstraintMatrices::synthesize(Cubic(Some(Fr::one()))).unwrap();
+        assert_eq!(matrices.input_assignment, None);
+        assert_eq!(matrices.aux_assignment, None);
+        assert_eq!(
+            (&matrices.a, &matrices.b, &matrices.c),
+            (&with_assignment.a, &with_assignment.b, &with_assignment.c)
+        );
+        assert!(matches!(
+            matrices.is_satisfied(),
+            Err(SynthesisError::AssignmentMissing)
+        ));
+    }
+
+    #[test]
+    fn replays_matrices() {
+        let matrices = ConstraintMatrices::synthesize(Cubic(Some(Fr::from(2u64)))).unwrap();
+        let replayed = ConstraintMatrices::synthesize(matrices.clone()).unwrap();
+        assert_eq!(replayed, matrices);
+
+        let setup = ConstraintMatrices::synthesize(Cubic(None)).unwrap();
+        assert_eq!(
+            ConstraintMatrices::synthesize(setup.clone()).unwrap(),
+            setup
+        );
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        for x in &[None, Some(Fr::from(7u64))] {
+            let matrices = ConstraintMatrices::synthesize(Cubic(*x)).unwrap();
+            let mut bytes = vec![];
+            matrices.serialize(&mut bytes).unwrap();
+            assert_eq!(bytes.len(), matrices.serialized_size());
+            let deserialized = ConstraintMatrices::<Fr>::deserialize(&bytes[..]).unwrap();
+            assert_eq!(deserialized, matrices);
+        }
+
+        let mut matrices = ConstraintMatrices::synthesize(Cubic(Some(Fr::one()))).unwrap();
+        matrices.a[0].push((Fr::one(), Index::Aux(2)));
+        let mut bytes = vec![];
+        matrices.serialize(&mut bytes).unwrap();
+        assert!(matches!(
+            ConstraintMatrices::<Fr>::deserialize(&bytes[..]),
+            Err(SerializationError::InvalidData)
+        ));
+    }
+
+    #[test]
+    fn deserialization_rejects_missing_one_input() {
+        let matrices = ConstraintMatrices::<Fr> {
+            num_inputs: 0,
+            num_aux: 0,
+            a: vec![],
+            b: vec![],
+            c: vec![],
+            input_assignment: Some(vec![]),
+            aux_assignment: Some(vec![]),
+        };
+        let mut bytes = vec![];
+        matrices.serialize(&mut bytes).unwrap();
+        assert!(matches!(
+            ConstraintMatrices::<Fr>::deserialize(&bytes[..]),
+            Err(SerializationError::InvalidData)
+        ));
+    }
+}
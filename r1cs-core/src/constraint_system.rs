@@ -1,4 +1,4 @@
-use crate::String;
+use crate::{format, String, Vec};
 use algebra_core::Field;
 use core::marker::PhantomData;
 
@@ -73,6 +73,109 @@ pub trait ConstraintSystem<F: Field>: Sized {
 
     /// Output the number of constraints in the system.
     fn num_constraints(&self) -> usize;
+
+    /// Returns whether the constraint system only records the structure of
+    /// the constraints, as during parameter generation. It then never invokes
+    /// the functions passed to `alloc` and `alloc_input`, and gadgets should
+    /// not compute assignments either.
+    fn is_in_setup_mode(&self) -> bool {
+        false
+    }
+
+    /// Returns the names of the namespaces currently entered, separated by
+    /// `/`, or `None` if the constraint system does not keep track of
+    /// namespaces. Used to record where errors occur.
+    fn namespace_path(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns whether [`synthesize_in_parallel`](Self::synthesize_in_parallel)
+    /// may synthesize children on separate threads. Constraint systems opt in
+    /// when the work saved by synthesizing in parallel outweighs the cost of
+    /// recording the children and merging them back.
+    fn supports_parallel_synthesis(&self) -> bool {
+        false
+    }
+
+    /// Synthesizes each of `children` in a namespace named by `annotation`,
+    /// as `child.generate_constraints(&mut cs.ns(|| format!("child {}", i)))`
+    /// would for the `i`-th child.
+    ///
+    /// Children may use the variables allocated before the call, but not those
+    /// allocated by other children. If the `parallel` feature is enabled and
+    /// the constraint system supports it, each child is synthesized on a rayon
+    /// thread into a forked constraint system, and the forks are then merged
+    /// back in order, so that the resulting variables, constraints and
+    /// namespaces are the same as with sequential synthesis.
+    fn synthesize_in_parallel<C, A, AR>(
+        &mut self,
+        annotation: A,
+        children: Vec<C>,
+    ) -> Result<(), SynthesisError>
+    where
+        C: ConstraintSynthesizer<F> + Send,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let mut cs = self.ns(annotation);
+        #[cfg(feature = "parallel")]
+        {
+            if cs.supports_parallel_synthesis() {
+                return crate::fork::synthesize_children(&mut cs, children);
+            }
+        }
+        for (i, child) in children.into_iter().enumerate() {
+            child.generate_constraints(&mut cs.ns(|| format!("child {}", i)))?;
+        }
+        Ok(())
+    }
+
+    /// Marks the public inputs allocated until the matching
+    /// `end_public_input` as encoding the public input `name` of type `ty`.
+    /// Not intended for downstream use; use `public_input` instead.
+    fn begin_public_input(&mut self, _name: String, _ty: String) {}
+
+    /// Ends the public input begun by the last unmatched
+    /// `begin_public_input`. Not intended for downstream use; use
+    /// `public_input` instead.
+    fn end_public_input(&mut self) {}
+
+    /// Invokes `f` on a namespace named `name` to allocate the public inputs
+    /// encoding the public input `name` of type `ty`, such as a digest
+    /// allocated as several field elements.
+    ///
+    /// Constraint systems that record the layout of the public inputs, such as
+    /// the one used by [`PublicInputLayout::synthesize`], attribute these
+    /// inputs to `name`. Public inputs allocated by nested calls are attributed
+    /// to the outermost one.
+    ///
+    /// [`PublicInputLayout::synthesize`]: crate::PublicInputLayout::synthesize
+    fn public_input<N, T, R, FN>(&mut self, name: N, ty: T, f: FN) -> Result<R, SynthesisError>
+    where
+        N: Into<String>,
+        T: Into<String>,
+        FN: FnOnce(&mut Namespace<'_, F, Self::Root>) -> Result<R, SynthesisError>,
+    {
+        let name = name.into();
+        self.get_root().begin_public_input(name.clone(), ty.into());
+        let result = f(&mut self.ns(|| name));
+        self.get_root().end_public_input();
+        result
+    }
+
+    /// Invokes `f` to compute an assignment, unless the constraint system is
+    /// in setup mode, in which case `SynthesisError::AssignmentMissing` is
+    /// returned without invoking `f`.
+    fn compute_assignment<T, FN>(&self, f: FN) -> Result<T, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+    {
+        if self.is_in_setup_mode() {
+            Err(SynthesisError::AssignmentMissing)
+        } else {
+            f()
+        }
+    }
 }
 
 /// This is a "namespaced" constraint system which borrows a constraint system
@@ -106,7 +209,8 @@ impl<F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for Namespace<'_, F,
         A: FnOnce() -> AR,
         AR: Into<String>,
     {
-        self.0.alloc(annotation, f)
+        let result = self.0.alloc(annotation, f);
+        result.map_err(|e| e.at_namespace_of(self))
     }
 
     #[inline]
@@ -116,7 +220,8 @@ impl<F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for Namespace<'_, F,
         A: FnOnce() -> AR,
         AR: Into<String>,
     {
-        self.0.alloc_input(annotation, f)
+        let result = self.0.alloc_input(annotation, f);
+        result.map_err(|e| e.at_namespace_of(self))
     }
 
     #[inline]
@@ -158,6 +263,31 @@ impl<F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for Namespace<'_, F,
     fn num_constraints(&self) -> usize {
         self.0.num_constraints()
     }
+
+    #[inline]
+    fn is_in_setup_mode(&self) -> bool {
+        self.0.is_in_setup_mode()
+    }
+
+    #[inline]
+    fn namespace_path(&self) -> Option<String> {
+        self.0.namespace_path()
+    }
+
+    #[inline]
+    fn supports_parallel_synthesis(&self) -> bool {
+        self.0.supports_parallel_synthesis()
+    }
+
+    #[inline]
+    fn begin_public_input(&mut self, name: String, ty: String) {
+        self.get_root().begin_public_input(name, ty)
+    }
+
+    #[inline]
+    fn end_public_input(&mut self) {
+        self.get_root().end_public_input()
+    }
 }
 
 impl<F: Field, CS: ConstraintSystem<F>> Drop for Namespace<'_, F, CS> {
@@ -232,4 +362,29 @@ impl<F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for &mut CS {
     fn num_constraints(&self) -> usize {
         (**self).num_constraints()
     }
+
+    #[inline]
+    fn is_in_setup_mode(&self) -> bool {
+        (**self).is_in_setup_mode()
+    }
+
+    #[inline]
+    fn namespace_path(&self) -> Option<String> {
+        (**self).namespace_path()
+    }
+
+    #[inline]
+    fn supports_parallel_synthesis(&self) -> bool {
+        (**self).supports_parallel_synthesis()
+    }
+
+    #[inline]
+    fn begin_public_input(&mut self, name: String, ty: String) {
+        (**self).begin_public_input(name, ty)
+    }
+
+    #[inline]
+    fn end_public_input(&mut self) {
+        (**self).end_public_input()
+    }
 }
@@ -0,0 +1,532 @@
+use crate::{Rc, String, Vec};
+use algebra_core::Field;
+use core::cell::{Ref, RefCell};
+
+use crate::{
+    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+};
+
+/// A constraint system that records constraints and assignments in memory.
+struct ConstraintSystemData<F: Field> {
+    /// The assignments of the public inputs, starting with the "one" input.
+    /// An assignment is `None` if it could not be computed, such as during
+    /// setup.
+    inputs: Vec<Option<F>>,
+    /// The assignments of the private variables.
+    aux: Vec<Option<F>>,
+    constraints: Vec<(
+        LinearCombination<F>,
+        LinearCombination<F>,
+        LinearCombination<F>,
+    )>,
+    /// The names of the namespaces that are currently open.
+    current_namespace: Vec<String>,
+    /// The operations performed on the system, in order, so that they can be
+    /// replayed into another constraint system.
+    operations: Vec<Operation>,
+    /// Whether the system is used for setup, in which case no assignment is
+    /// computed.
+    setup_mode: bool,
+}
+
+/// An operation performed on a [`ConstraintSystemRef`], along with its
+/// annotation. Variables and constraints are stored separately, in the order
+/// of the operations that create them.
+enum Operation {
+    AllocInput(String),
+    Alloc(String),
+    Enforce(String),
+    PushNamespace(String),
+    PopNamespace,
+    BeginPublicInput(String, String),
+    EndPublicInput,
+}
+
+/// A shared, reference-counted handle to an in-memory constraint system.
+///
+/// Unlike other constraint systems, a `ConstraintSystemRef` is cheap to clone,
+/// and all clones refer to the same system. Gadget values can therefore keep a
+/// handle to the system they live in, which lets them create constraints
+/// without being passed a constraint system, as needed for operator
+/// overloading.
+///
+/// `ConstraintSystemRef` implements [`ConstraintSystem`], so existing gadgets
+/// and [`ConstraintSynthesizer`] circuits can be synthesized into it directly
+/// through `&mut cs.clone()`. Conversely, [`RefCircuit`] turns circuits written
+/// against `ConstraintSystemRef` into a [`ConstraintSynthesizer`], for use with
+/// existing provers.
+pub struct ConstraintSystemRef<F: Field> {
+    inner: Rc<RefCell<ConstraintSystemData<F>>>,
+}
+
+impl<F: Field> Clone for ConstraintSystemRef<F> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<F: Field> core::fmt::Debug for ConstraintSystemRef<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let inner = self.inner.borrow();
+        f.debug_struct("ConstraintSystemRef")
+            .field("num_inputs", &inner.inputs.len())
+            .field("num_aux", &inner.aux.len())
+            .field("num_constraints", &inner.constraints.len())
+            .finish()
+    }
+}
+
+impl<F: Field> Default for ConstraintSystemRef<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> ConstraintSystemRef<F> {
+    /// Creates a new, empty constraint system.
+    pub fn new() -> Self {
+        Self::with_setup_mode(false)
+    }
+
+    /// Creates a new, empty constraint system in setup mode, in which the
+    /// closures computing assignments are not invoked and all assignments
+    /// are missing.
+    pub fn new_for_setup() -> Self {
+        Self::with_setup_mode(true)
+    }
+
+    fn with_setup_mode(setup_mode: bool) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(ConstraintSystemData {
+                inputs: vec![Some(F::one())],
+                aux: Vec::new(),
+                constraints: Vec::new(),
+                current_namespace: Vec::new(),
+                operations: Vec::new(),
+                setup_mode,
+            })),
+        }
+    }
+
+    /// Returns whether `self` and `other` refer to the same constraint system.
+    pub fn is_same(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// The number of public inputs, including the "one" input.
+    pub fn num_inputs(&self) -> usize {
+        self.inner.borrow().inputs.len()
+    }
+
+    /// The number of private variables.
+    pub fn num_aux(&self) -> usize {
+        self.inner.borrow().aux.len()
+    }
+
+    /// The assignment of `var`, or `None` if it could not be computed.
+    ///
+    /// Panics if `var` was not allocated in `self`.
+    pub fn assignment(&self, var: Variable) -> Option<F> {
+        let inner = self.inner.borrow();
+        match var.get_unchecked() {
+            Index::Input(i) => inner.inputs[i],
+            Index::Aux(i) => inner.aux[i],
+        }
+    }
+
+    /// Returns the names of the namespaces that are currently open.
+    pub fn current_namespace(&self) -> Ref<'_, [String]> {
+        Ref::map(self.inner.borrow(), |inner| &inner.current_namespace[..])
+    }
+
+    /// Returns the index of the first constraint that is not satisfied by the
+    /// assignment, or `None` if all constraints are satisfied.
+    ///
+    /// Returns `SynthesisError::AssignmentMissing` if an assignment is
+    /// missing.
+    pub fn which_is_unsatisfied(&self) -> Result<Option<usize>, SynthesisError> {
+        let inner = self.inner.borrow();
+        let eval = |lc: &LinearCombination<F>| -> Result<F, SynthesisError> {
+            let mut acc = F::zero();
+            for (var, coeff) in lc.as_ref() {
+                let value = match var.get_unchecked() {
+                    Index::Input(i) => inner.inputs[i],
+                    Index::Aux(i) => inner.aux[i],
+                };
+                acc += &(value.ok_or(SynthesisError::AssignmentMissing)? * coeff);
+            }
+            Ok(acc)
+        };
+        for (i, (a, b, c)) in inner.constraints.iter().enumerate() {
+            if eval(a)? * &eval(b)? != eval(c)? {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns whether all constraints are satisfied by the assignment.
+    ///
+    /// Returns `SynthesisError::AssignmentMissing` if an assignment is
+    /// missing.
+    pub fn is_satisfied(&self) -> Result<bool, SynthesisError> {
+        self.which_is_unsatisfied().map(|i| i.is_none())
+    }
+
+    /// Returns the assignment computed by `f`, or `None` if it is missing or
+    /// if `self` is in setup mode, in which case `f` is not invoked.
+    fn assignment_of<FN>(&self, f: FN) -> Result<Option<F>, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+    {
+        match self.compute_assignment(f) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) => match e.root_cause() {
+                SynthesisError::AssignmentMissing => Ok(None),
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Replays the variables and constraints of `self` into `cs`, in order,
+    /// with the same annotations, namespaces and public input annotations.
+    /// Missing assignments are reported to `cs` as
+    /// `SynthesisError::AssignmentMissing`.
+    pub fn synthesize_into<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let inner = self.inner.borrow();
+        let mut inputs = Vec::with_capacity(inner.inputs.len());
+        inputs.push(CS::one());
+        let mut aux = Vec::with_capacity(inner.aux.len());
+        let mut constraints = inner.constraints.iter();
+        // The number of namespaces opened in `cs` and not closed yet, which
+        // are closed if replaying fails.
+        let mut depth = 0;
+        for operation in &inner.operations {
+            let result = match operation {
+                Operation::AllocInput(annotation) => {
+                    let value = inner.inputs[inputs.len()];
+                    cs.alloc_input(
+                        || annotation.as_str(),
+                        || value.ok_or(SynthesisError::AssignmentMissing),
+                    )
+                    .map(|var| inputs.push(var))
+                },
+                Operation::Alloc(annotation) => {
+                    let value = inner.aux[aux.len()];
+                    cs.alloc(
+                        || annotation.as_str(),
+                        || value.ok_or(SynthesisError::AssignmentMissing),
+                    )
+                    .map(|var| aux.push(var))
+                },
+                Operation::Enforce(annotation) => {
+                    let (a, b, c) = constraints.next().expect("every constraint is recorded");
+                    let map = |row: &LinearCombination<F>, mut lc: LinearCombination<F>| {
+                        for (var, coeff) in row.as_ref() {
+                            let var = match var.get_unchecked() {
+                                Index::Input(i) => inputs[i],
+                                Index::Aux(i) => aux[i],
+                            };
+                            lc += (*coeff, var);
+                        }
+                        lc
+                    };
+                    cs.enforce(
+                        || annotation.as_str(),
+                        |lc| map(a, lc),
+                        |lc| map(b, lc),
+                        |lc| map(c, lc),
+                    );
+                    Ok(())
+                },
+                Operation::PushNamespace(name) => {
+                    cs.get_root().push_namespace(|| name.as_str());
+                    depth += 1;
+                    Ok(())
+                },
+                Operation::PopNamespace => {
+                    cs.get_root().pop_namespace();
+                    depth -= 1;
+                    Ok(())
+                },
+                Operation::BeginPublicInput(name, ty) => {
+                    cs.get_root().begin_public_input(name.clone(), ty.clone());
+                    Ok(())
+                },
+                Operation::EndPublicInput => {
+                    cs.get_root().end_public_input();
+                    Ok(())
+                },
+            };
+            if let Err(e) = result {
+                for _ in 0..depth {
+                    cs.get_root().pop_namespace();
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<F: Field> ConstraintSystem<F> for ConstraintSystemRef<F> {
+    type Root = Self;
+
+    fn alloc<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let value = self.assignment_of(f)?;
+        let mut inner = self.inner.borrow_mut();
+        inner.aux.push(value);
+        inner.operations.push(Operation::Alloc(annotation().into()));
+        Ok(Variable::new_unchecked(Index::Aux(inner.aux.len() - 1)))
+    }
+
+    fn alloc_input<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let value = self.assignment_of(f)?;
+        let mut inner = self.inner.borrow_mut();
+        inner.inputs.push(value);
+        inner
+            .operations
+            .push(Operation::AllocInput(annotation().into()));
+        Ok(Variable::new_unchecked(Index::Input(
+            inner.inputs.len() - 1,
+        )))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        let a = a(LinearCombination::zero());
+        let b = b(LinearCombination::zero());
+        let c = c(LinearCombination::zero());
+        let mut inner = self.inner.borrow_mut();
+        inner.constraints.push((a, b, c));
+        inner
+            .operations
+            .push(Operation::Enforce(annotation().into()));
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name = name_fn().into();
+        let mut inner = self.inner.borrow_mut();
+        inner.current_namespace.push(name.clone());
+        inner.operations.push(Operation::PushNamespace(name));
+    }
+
+    fn pop_namespace(&mut self) {
+        let mut inner = self.inner.borrow_mut();
+        assert!(inner.current_namespace.pop().is_some());
+        inner.operations.push(Operation::PopNamespace);
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.inner.borrow().constraints.len()
+    }
+
+    fn is_in_setup_mode(&self) -> bool {
+        self.inner.borrow().setup_mode
+    }
+
+    fn namespace_path(&self) -> Option<String> {
+        Some(self.inner.borrow().current_namespace.join("/"))
+    }
+
+    fn begin_public_input(&mut self, name: String, ty: String) {
+        self.inner
+            .borrow_mut()
+            .operations
+            .push(Operation::BeginPublicInput(name, ty));
+    }
+
+    fn end_public_input(&mut self) {
+        self.inner
+            .borrow_mut()
+            .operations
+            .push(Operation::EndPublicInput);
+    }
+}
+
+/// A circuit that generates constraints through a [`ConstraintSystemRef`].
+pub trait RefConstraintSynthesizer<F: Field> {
+    /// Drives generation of new constraints inside `cs`.
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError>;
+}
+
+/// Adapts a [`RefConstraintSynthesizer`] to a [`ConstraintSynthesizer`], by
+/// synthesizing it into a fresh [`ConstraintSystemRef`], in setup mode if the
+/// given constraint system is, and replaying the result into the given
+/// constraint system.
+#[derive(Clone, Debug)]
+pub struct RefCircuit<C>(pub C);
+
+impl<F: Field, C: RefConstraintSynthesizer<F>> ConstraintSynthesizer<F> for RefCircuit<C> {
+    fn generate_constraints<CS: ConstraintSystem<F>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let cs_ref = ConstraintSystemRef::with_setup_mode(cs.is_in_setup_mode());
+        self.0.generate_constraints(cs_ref.clone())?;
+        cs_ref.synthesize_into(cs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstraintMatrices, SatisfiabilityChecker};
+    use algebra::bls12_381::Fr;
+
+    struct Square(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Square {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let x = cs.alloc(|| "x", || self.0.ok_or(SynthesisError::AssignmentMissing))?;
+            let y = cs.alloc_input(
+                || "y",
+                || {
+                    self.0
+                        .map(|x| x * &x)
+                        .ok_or(SynthesisError::AssignmentMissing)
+                },
+            )?;
+            let mut cs = cs.ns(|| "square");
+            cs.enforce(|| "x * x = y", |lc| lc + x, |lc| lc + x, |lc| lc + y);
+            Ok(())
+        }
+    }
+
+    struct RefSquare(Option<Fr>);
+
+    impl RefConstraintSynthesizer<Fr> for RefSquare {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            Square(self.0).generate_constraints(&mut cs.clone())?;
+            assert!(cs.current_namespace().is_empty());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn records_existing_circuits() {
+        let cs = ConstraintSystemRef::new();
+        Square(Some(Fr::from(3u64)))
+            .generate_constraints(&mut cs.clone())
+            .unwrap();
+        assert_eq!(cs.num_inputs(), 2);
+        assert_eq!(cs.num_aux(), 1);
+        assert_eq!(cs.num_constraints(), 1);
+        assert_eq!(
+            cs.assignment(Variable::new_unchecked(Index::Input(1))),
+            Some(Fr::from(9u64))
+        );
+        assert!(cs.is_satisfied().unwrap());
+
+        let clone = cs.clone();
+        clone.clone().alloc(|| "z", || Ok(Fr::from(1u64))).unwrap();
+        assert!(clone.is_same(&cs));
+        assert_eq!(cs.num_aux(), 2);
+        assert!(!cs.is_same(&ConstraintSystemRef::new()));
+    }
+
+    #[test]
+    fn records_missing_assignments() {
+        let cs = ConstraintSystemRef::new();
+        Square(None).generate_constraints(&mut cs.clone()).unwrap();
+        assert_eq!(cs.num_constraints(), 1);
+        assert_eq!(cs.assignment(Variable::new_unchecked(Index::Aux(0))), None);
+        assert!(cs.is_satisfied().is_err());
+    }
+
+    #[test]
+    fn replays_ref_circuits() {
+        let expected = ConstraintSystemRef::new();
+        Square(Some(Fr::from(3u64)))
+            .generate_constraints(&mut expected.clone())
+            .unwrap();
+
+        let cs = ConstraintSystemRef::new();
+        RefCircuit(RefSquare(Some(Fr::from(3u64))))
+            .generate_constraints(&mut cs.clone())
+            .unwrap();
+        assert_eq!(cs.num_inputs(), expected.num_inputs());
+        assert_eq!(cs.num_aux(), expected.num_aux());
+        assert_eq!(cs.num_constraints(), expected.num_constraints());
+        assert!(cs.is_satisfied().unwrap());
+
+        let cs = ConstraintSystemRef::new();
+        RefCircuit(RefSquare(Some(Fr::from(3u64))))
+            .generate_constraints(&mut cs.clone())
+            .unwrap();
+        cs.inner.borrow_mut().inputs[1] = Some(Fr::from(10u64));
+        assert_eq!(cs.which_is_unsatisfied().unwrap(), Some(0));
+    }
+
+    #[test]
+    fn replays_namespaces_and_annotations() {
+        let cs = ConstraintSystemRef::new();
+        RefCircuit(RefSquare(Some(Fr::from(3u64))))
+            .generate_constraints(&mut cs.clone())
+            .unwrap();
+        cs.inner.borrow_mut().inputs[1] = Some(Fr::from(10u64));
+
+        let mut checker = SatisfiabilityChecker::new();
+        cs.synthesize_into(&mut checker).unwrap();
+        assert_eq!(
+            checker.which_is_unsatisfied().unwrap().path,
+            "square/x * x = y"
+        );
+    }
+
+    #[test]
+    fn passes_setup_mode_through() {
+        struct Setup;
+
+        impl RefConstraintSynthesizer<Fr> for Setup {
+            fn generate_constraints(
+                self,
+                cs: ConstraintSystemRef<Fr>,
+            ) -> Result<(), SynthesisError> {
+                assert!(cs.is_in_setup_mode());
+                cs.clone().alloc(
+                    || "x",
+                    || panic!("assignments are not computed during setup"),
+                )?;
+                Ok(())
+            }
+        }
+
+        let matrices = ConstraintMatrices::synthesize_setup(RefCircuit(Setup)).unwrap();
+        assert_eq!(matrices.num_aux, 1);
+        assert!(!ConstraintSystemRef::<Fr>::new().is_in_setup_mode());
+        assert!(ConstraintSystemRef::<Fr>::new_for_setup().is_in_setup_mode());
+    }
+}
@@ -1,9 +1,16 @@
-use algebra_core::io;
+use crate::{Box, String, Vec};
+use algebra_core::{io, Field};
 use core::fmt;
 
+use crate::ConstraintSystem;
+
 /// This is an error that could occur during circuit synthesis contexts,
 /// such as CRS generation, proving or verification.
+///
+/// More variants may be added in the future, so matches on this enum outside
+/// of `r1cs-core` need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum SynthesisError {
     /// During synthesis, we lacked knowledge of a variable assignment.
     AssignmentMissing,
@@ -21,6 +28,138 @@ pub enum SynthesisError {
     MalformedVerifyingKey,
     /// During CRS generation, we observed an unconstrained auxiliary variable
     UnconstrainedVariable,
+    /// During verification, the public inputs did not match the layout
+    /// recorded for the circuit, or, while recording the layout, two public
+    /// inputs had the same name.
+    PublicInputMismatch(String),
+    /// An error together with context about where and why it occurred.
+    Context(Box<ErrorContext>),
+}
+
+/// Context attached to a [`SynthesisError`].
+#[derive(Debug)]
+pub struct ErrorContext {
+    /// The names of the namespaces enclosing the point of failure, separated
+    /// by `/`, if known.
+    pub path: Option<String>,
+    /// A description of what was being done when the error occurred.
+    pub message: Option<String>,
+    /// The error that occurred.
+    pub source: SynthesisError,
+}
+
+impl SynthesisError {
+    /// Attaches `message` to the error.
+    pub fn context<M: Into<String>>(self, message: M) -> Self {
+        SynthesisError::Context(Box::new(ErrorContext {
+            path: None,
+            message: Some(message.into()),
+            source: self,
+        }))
+    }
+
+    /// Records `path` as the namespace path at which the error occurred,
+    /// unless a path is already recorded.
+    pub fn at_path(mut self, path: String) -> Self {
+        if self.path().is_some() {
+            return self;
+        }
+        if let SynthesisError::Context(context) = &mut self {
+            context.path = Some(path);
+            return self;
+        }
+        SynthesisError::Context(Box::new(ErrorContext {
+            path: Some(path),
+            message: None,
+            source: self,
+        }))
+    }
+
+    /// Records the current namespace path of `cs` as the path at which the
+    /// error occurred, unless a path is already recorded or `cs` does not keep
+    /// track of namespaces.
+    pub fn at_namespace_of<F: Field, CS: ConstraintSystem<F>>(self, cs: &CS) -> Self {
+        if self.path().is_some() {
+            return self;
+        }
+        match cs.namespace_path() {
+            Some(path) => self.at_path(path),
+            None => self,
+        }
+    }
+
+    /// Returns the namespace path at which the error occurred, if known.
+    pub fn path(&self) -> Option<&str> {
+        let mut error = self;
+        while let SynthesisError::Context(context) = error {
+            if let Some(path) = &context.path {
+                return Some(path);
+            }
+            error = &context.source;
+        }
+        None
+    }
+
+    /// Returns the messages attached to the error, outermost first.
+    pub fn messages(&self) -> Vec<&str> {
+        let mut messages = Vec::new();
+        let mut error = self;
+        while let SynthesisError::Context(context) = error {
+            messages.extend(context.message.as_deref());
+            error = &context.source;
+        }
+        messages
+    }
+
+    /// Returns the underlying error, without any context.
+    pub fn root_cause(&self) -> &SynthesisError {
+        let mut error = self;
+        while let SynthesisError::Context(context) = error {
+            error = &context.source;
+        }
+        error
+    }
+}
+
+/// Combinators attaching context to the error of a synthesis result.
+pub trait SynthesisResultExt<T> {
+    /// Attaches `message` to the error, if any.
+    fn context<M: Into<String>>(self, message: M) -> Result<T, SynthesisError>;
+
+    /// Attaches the message returned by `f` to the error, if any. `f` is only
+    /// invoked on error.
+    fn with_context<M, FN>(self, f: FN) -> Result<T, SynthesisError>
+    where
+        M: Into<String>,
+        FN: FnOnce() -> M;
+
+    /// Records the current namespace path of `cs` in the error, if any, as in
+    /// [`SynthesisError::at_namespace_of`].
+    fn at_namespace_of<F: Field, CS: ConstraintSystem<F>>(
+        self,
+        cs: &CS,
+    ) -> Result<T, SynthesisError>;
+}
+
+impl<T> SynthesisResultExt<T> for Result<T, SynthesisError> {
+    fn context<M: Into<String>>(self, message: M) -> Result<T, SynthesisError> {
+        self.map_err(|e| e.context(message))
+    }
+
+    fn with_context<M, FN>(self, f: FN) -> Result<T, SynthesisError>
+    where
+        M: Into<String>,
+        FN: FnOnce() -> M,
+    {
+        self.map_err(|e| e.context(f()))
+    }
+
+    fn at_namespace_of<F: Field, CS: ConstraintSystem<F>>(
+        self,
+        cs: &CS,
+    ) -> Result<T, SynthesisError> {
+        self.map_err(|e| e.at_namespace_of(cs))
+    }
 }
 
 impl From<io::Error> for SynthesisError {
@@ -32,7 +171,10 @@ impl From<io::Error> for SynthesisError {
 #[cfg(feature = "std")]
 impl std::error::Error for SynthesisError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        None
+        match self {
+            SynthesisError::Context(context) => Some(&context.source),
+            _ => None,
+        }
     }
 }
 
@@ -41,18 +183,56 @@ impl fmt::Display for SynthesisError {
         match self {
             SynthesisError::AssignmentMissing => {
                 write!(f, "an assignment for a variable could not be computed")
-            }
+            },
             SynthesisError::DivisionByZero => write!(f, "division by zero"),
             SynthesisError::Unsatisfiable => write!(f, "unsatisfiable constraint system"),
             SynthesisError::PolynomialDegreeTooLarge => write!(f, "polynomial degree is too large"),
             SynthesisError::UnexpectedIdentity => {
                 write!(f, "encountered an identity element in the CRS")
-            }
+            },
             SynthesisError::IoError(err) => write!(f, "I/O error: {:?}", err),
             SynthesisError::MalformedVerifyingKey => write!(f, "malformed verifying key"),
             SynthesisError::UnconstrainedVariable => {
                 write!(f, "auxiliary variable was unconstrained")
-            }
+            },
+            SynthesisError::PublicInputMismatch(message) => {
+                write!(f, "public input mismatch: {}", message)
+            },
+            SynthesisError::Context(context) => {
+                if let Some(path) = &context.path {
+                    write!(f, "{}: ", path)?;
+                }
+                if let Some(message) = &context.message {
+                    write!(f, "{}: ", message)?;
+                }
+                write!(f, "{}", context.source)
+            },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SatisfiabilityChecker;
+    use algebra::bls12_381::Fr;
+
+    #[test]
+    fn records_namespace_path_and_context() {
+        let mut cs = SatisfiabilityChecker::<Fr>::new();
+        let mut outer = cs.ns(|| "outer");
+        let mut inner = outer.ns(|| "inner");
+        let error = inner
+            .alloc(|| "x", || Err(SynthesisError::DivisionByZero))
+            .context("computing x")
+            .unwrap_err();
+
+        assert_eq!(error.path(), Some("outer/inner"));
+        assert_eq!(error.messages(), vec!["computing x"]);
+        assert!(matches!(error.root_cause(), SynthesisError::DivisionByZero));
+        assert_eq!(
+            format!("{}", error),
+            "computing x: outer/inner: division by zero"
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{format, String, Vec};
+use algebra_core::Field;
+
+use crate::{
+    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+};
+
+use rayon::prelude::*;
+
+/// An operation performed on a forked constraint system, to be replayed on
+/// its parent.
+enum Event<F: Field> {
+    Alloc(String, Option<F>),
+    AllocInput(String, Option<F>),
+    Enforce(
+        String,
+        LinearCombination<F>,
+        LinearCombination<F>,
+        LinearCombination<F>,
+    ),
+    PushNamespace(String),
+    PopNamespace,
+    BeginPublicInput(String, String),
+    EndPublicInput,
+}
+
+/// Constraint system into which a child circuit is synthesized independently
+/// of its parent, and which is merged back into the parent afterwards by
+/// replaying its operations on it.
+///
+/// The child may use the variables that the parent allocated before the fork.
+/// Variables allocated by the child are numbered downwards from `usize::MAX`
+/// so that they can be told apart from those of the parent, and are replaced
+/// by the corresponding variables allocated in the parent when merging.
+pub(crate) struct ForkedConstraintSystem<F: Field> {
+    setup_mode: bool,
+    events: Vec<Event<F>>,
+    num_inputs: usize,
+    num_aux: usize,
+    num_constraints: usize,
+}
+
+impl<F: Field> ForkedConstraintSystem<F> {
+    /// Creates an empty child of a constraint system, which is in setup mode
+    /// if `setup_mode` is set.
+    pub(crate) fn new(setup_mode: bool) -> Self {
+        Self {
+            setup_mode,
+            events: Vec::new(),
+            num_inputs: 0,
+            num_aux: 0,
+            num_constraints: 0,
+        }
+    }
+
+    fn compute_value<FN>(&self, f: FN) -> Result<Option<F>, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+    {
+        if self.setup_mode {
+            return Ok(None);
+        }
+        // A missing assignment is only an error if the parent says so when the
+        // allocation is replayed.
+        match f() {
+            Ok(value) => Ok(Some(value)),
+            Err(e) => match e.root_cause() {
+                SynthesisError::AssignmentMissing => Ok(None),
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Replays the operations performed on `self` on `cs`.
+    pub(crate) fn merge_into<CS: ConstraintSystem<F>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let mut inputs = Vec::with_capacity(self.num_inputs);
+        let mut aux = Vec::with_capacity(self.num_aux);
+        let mut depth = 0;
+        let mut public_input_depth = 0;
+        let mut result = Ok(());
+        for event in self.events {
+            match event {
+                Event::Alloc(annotation, value) => {
+                    match cs.alloc(
+                        || annotation,
+                        || value.ok_or(SynthesisError::AssignmentMissing),
+                    ) {
+                        Ok(var) => aux.push(var),
+                        Err(e) => {
+                            result = Err(e);
+                            break;
+                        },
+                    }
+                },
+                Event::AllocInput(annotation, value) => {
+                    match cs.alloc_input(
+                        || annotation,
+                        || value.ok_or(SynthesisError::AssignmentMissing),
+                    ) {
+                        Ok(var) => inputs.push(var),
+                        Err(e) => {
+                            result = Err(e);
+                            break;
+                        },
+                    }
+                },
+                Event::Enforce(annotation, a, b, c) => {
+                    let a = translate(a, &inputs, &aux);
+                    let b = translate(b, &inputs, &aux);
+                    let c = translate(c, &inputs, &aux);
+                    cs.enforce(|| annotation, |lc| lc + &a, |lc| lc + &b, |lc| lc + &c);
+                },
+                Event::PushNamespace(name) => {
+                    cs.get_root().push_namespace(|| name);
+                    depth += 1;
+                },
+                Event::PopNamespace => {
+                    cs.get_root().pop_namespace();
+                    depth -= 1;
+                },
+                Event::BeginPublicInput(name, ty) => {
+                    cs.get_root().begin_public_input(name, ty);
+                    public_input_depth += 1;
+                },
+                Event::EndPublicInput => {
+                    cs.get_root().end_public_input();
+                    public_input_depth -= 1;
+                },
+            }
+        }
+        // Leave the namespaces and public inputs entered before an allocation
+        // failed.
+        for _ in 0..depth {
+            cs.get_root().pop_namespace();
+        }
+        for _ in 0..public_input_depth {
+            cs.get_root().end_public_input();
+        }
+        result
+    }
+}
+
+/// Replaces the variables allocated by a forked constraint system in `lc` by
+/// the corresponding variables allocated in its parent.
+fn translate<F: Field>(
+    lc: LinearCombination<F>,
+    inputs: &[Variable],
+    aux: &[Variable],
+) -> LinearCombination<F> {
+    let mut result = LinearCombination::zero();
+    for (var, coeff) in lc.0 {
+        let var = match var.get_unchecked() {
+            Index::Input(i) if usize::MAX - i < inputs.len() => inputs[usize::MAX - i],
+            Index::Aux(i) if usize::MAX - i < aux.len() => aux[usize::MAX - i],
+            _ => var,
+        };
+        result += (coeff, var);
+    }
+    result
+}
+
+/// Synthesizes each of `children` into a fork of `cs` on a rayon thread, and
+/// merges the forks back into `cs` in order.
+pub(crate) fn synthesize_children<F, CS, C>(
+    cs: &mut CS,
+    children: Vec<C>,
+) -> Result<(), SynthesisError>
+where
+    F: Field,
+    CS: ConstraintSystem<F>,
+    C: ConstraintSynthesizer<F> + Send,
+{
+    let setup_mode = cs.is_in_setup_mode();
+    let forks = children
+        .into_par_iter()
+        .map(|child| {
+            let mut fork = ForkedConstraintSystem::new(setup_mode);
+            child.generate_constraints(&mut fork).map(|_| fork)
+        })
+        .collect::<Vec<_>>();
+    // Merge in order, so that the variables, the constraints and the error
+    // returned do not depend on scheduling.
+    for (i, fork) in forks.into_iter().enumerate() {
+        fork?.merge_into(&mut cs.ns(|| format!("child {}", i)))?;
+    }
+    Ok(())
+}
+
+impl<F: Field> ConstraintSystem<F> for ForkedConstraintSystem<F> {
+    type Root = Self;
+
+    #[inline]
+    fn alloc<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let value = self.compute_value(f)?;
+        self.events.push(Event::Alloc(annotation().into(), value));
+        self.num_aux += 1;
+        Ok(Variable::new_unchecked(Index::Aux(
+            usize::MAX - (self.num_aux - 1),
+        )))
+    }
+
+    #[inline]
+    fn alloc_input<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let value = self.compute_value(f)?;
+        self.events
+            .push(Event::AllocInput(annotation().into(), value));
+        self.num_inputs += 1;
+        Ok(Variable::new_unchecked(Index::Input(
+            usize::MAX - (self.num_inputs - 1),
+        )))
+    }
+
+    #[inline]
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        self.events.push(Event::Enforce(
+            annotation().into(),
+            a(LinearCombination::zero()),
+            b(LinearCombination::zero()),
+            c(LinearCombination::zero()),
+        ));
+        self.num_constraints += 1;
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.events.push(Event::PushNamespace(name_fn().into()));
+    }
+
+    fn pop_namespace(&mut self) {
+        self.events.push(Event::PopNamespace);
+    }
+
+    fn begin_public_input(&mut self, name: String, ty: String) {
+        self.events.push(Event::BeginPublicInput(name, ty));
+    }
+
+    fn end_public_input(&mut self) {
+        self.events.push(Event::EndPublicInput);
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_constraints
+    }
+
+    fn is_in_setup_mode(&self) -> bool {
+        self.setup_mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+    use algebra::bls12_381::Fr;
+
+    /// Proves knowledge of the square roots of `y` in each child.
+    struct Roots {
+        y: Option<Fr>,
+        roots: Vec<Option<Fr>>,
+        in_parallel: bool,
+    }
+
+    struct Root {
+        y: crate::Variable,
+        root: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Root {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let root = self.root;
+            let x = cs.alloc(|| "x", || root.ok_or(SynthesisError::AssignmentMissing))?;
+            let mut cs = cs.ns(|| "square");
+            cs.enforce(|| "x * x = y", |lc| lc + x, |lc| lc + x, |lc| lc + self.y);
+            Ok(())
+        }
+    }
+
+    impl ConstraintSynthesizer<Fr> for Roots {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let y_val = self.y;
+            let y = cs.alloc_input(|| "y", || y_val.ok_or(SynthesisError::AssignmentMissing))?;
+            let children = self
+                .roots
+                .into_iter()
+                .map(|root| Root { y, root })
+                .collect::<Vec<_>>();
+            if self.in_parallel {
+                cs.synthesize_in_parallel(|| "roots", children)?;
+            } else {
+                let mut cs = cs.ns(|| "roots");
+                for (i, child) in children.into_iter().enumerate() {
+                    child.generate_constraints(&mut cs.ns(|| format!("child {}", i)))?;
+                }
+            }
+            let z = cs.alloc(|| "z", || y_val.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "z = y", |lc| lc + z, |lc| lc + CS::one(), |lc| lc + y);
+            Ok(())
+        }
+    }
+
+    fn roots(y: Option<Fr>, roots: Vec<Option<Fr>>, in_parallel: bool) -> ConstraintMatrices<Fr> {
+        ConstraintMatrices::synthesize(Roots {
+            y,
+            roots,
+            in_parallel,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn matches_sequential_synthesis() {
+        let x = Fr::from(3u64);
+        let y = Some(x * &x);
+        let witness = vec![Some(x), Some(-x), Some(x), Some(-x)];
+        let parallel = roots(y, witness.clone(), true);
+        assert_eq!(parallel, roots(y, witness, false));
+        assert_eq!(parallel.num_constraints(), 5);
+        assert!(parallel.is_satisfied().unwrap());
+
+        let setup = vec![None; 4];
+        assert_eq!(roots(None, setup.clone(), true), roots(None, setup, false));
+    }
+}
@@ -0,0 +1,234 @@
+use crate::Vec;
+use algebra_core::Field;
+
+use crate::{constraint_matrices::canonicalize_row, ConstraintMatrices, Index};
+
+impl<F: Field> ConstraintMatrices<F> {
+    /// Eliminates the constraints that merely name a linear combination, that
+    /// is, constraints of the form `lc * c = k var` or `c * lc = k var` for
+    /// constants `c` and `k` and a private variable `var` that does not occur
+    /// in `lc`, and similarly with `var` on the left. `var` is replaced by
+    /// `(c / k) lc` in all other constraints and then removed, and the
+    /// remaining private variables are renumbered in order.
+    ///
+    /// The result only depends on the structure of the constraints, not on
+    /// the assignment, so the generator and the prover of a proving system
+    /// arrive at the same constraint system. Linear combinations grow as they
+    /// are inlined, which trades proving time for fewer constraints and
+    /// variables, and thus smaller proving keys. The Groth16 and GM17 crates
+    /// expose this as the `_inlined` variants of their parameter generation
+    /// and proving functions, which inline the constraints in place.
+    ///
+    /// Returns the number of eliminated constraints.
+    pub fn inline_linear_combinations(&mut self) -> usize {
+        let num_constraints = self.a.len();
+        // The constraints in which each private variable may occur. Entries
+        // become stale as variables are substituted, and are checked on use.
+        let mut occurrences = vec![Vec::new(); self.num_aux];
+        for (i, ((a, b), c)) in self.a.iter().zip(&self.b).zip(&self.c).enumerate() {
+            for (_, index) in a.iter().chain(b).chain(c) {
+                if let Index::Aux(v) = *index {
+                    if occurrences[v].last() != Some(&i) {
+                        occurrences[v].push(i);
+                    }
+                }
+            }
+        }
+
+        let mut is_removed = vec![false; num_constraints];
+        let mut is_inlined = vec![false; self.num_aux];
+        for i in 0..num_constraints {
+            let (var, lc) = match definition(&self.a[i], &self.b[i], &self.c[i]) {
+                Some(definition) => definition,
+                None => continue,
+            };
+            is_removed[i] = true;
+            is_inlined[var] = true;
+            for j in core::mem::take(&mut occurrences[var]) {
+                if is_removed[j] {
+                    continue;
+                }
+                for matrix in &mut [&mut self.a, &mut self.b, &mut self.c] {
+                    if substitute(&mut matrix[j], var, &lc) {
+                        for (_, index) in &lc {
+                            if let Index::Aux(v) = *index {
+                                occurrences[v].push(j);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut new_index = Vec::with_capacity(self.num_aux);
+        let mut num_aux = 0;
+        for &inlined in &is_inlined {
+            new_index.push(num_aux);
+            num_aux += usize::from(!inlined);
+        }
+        for matrix in &mut [&mut self.a, &mut self.b, &mut self.c] {
+            let mut removed = is_removed.iter();
+            matrix.retain(|_| !*removed.next().unwrap());
+            for row in matrix.iter_mut() {
+                for (_, index) in row.iter_mut() {
+                    if let Index::Aux(v) = *index {
+                        *index = Index::Aux(new_index[v]);
+                    }
+                }
+            }
+        }
+        if let Some(aux) = &mut self.aux_assignment {
+            let mut inlined = is_inlined.iter();
+            aux.retain(|_| !*inlined.next().unwrap());
+        }
+        self.num_aux = num_aux;
+        num_constraints - self.a.len()
+    }
+}
+
+/// If the constraint `a * b = c` defines a private variable as a linear
+/// combination of other variables, returns the variable and the linear
+/// combination.
+fn definition<F: Field>(
+    a: &[(F, Index)],
+    b: &[(F, Index)],
+    c: &[(F, Index)],
+) -> Option<(usize, Vec<(F, Index)>)> {
+    let constant = |row: &[(F, Index)]| match row {
+        [(coeff, Index::Input(0))] => Some(*coeff),
+        _ => None,
+    };
+    let (scalar, lc) = match (constant(a), constant(b)) {
+        (_, Some(scalar)) => (scalar, a),
+        (Some(scalar), None) => (scalar, b),
+        (None, None) => return None,
+    };
+    // `scalar * lc = c`, where `scalar` is nonzero since rows have no zero
+    // entries.
+    let single_aux = |row: &[(F, Index)], other: &[(F, Index)]| match row {
+        [(coeff, Index::Aux(var))] if other.iter().all(|(_, index)| *index != Index::Aux(*var)) => {
+            Some((*coeff, *var))
+        },
+        _ => None,
+    };
+    // If both sides are single variables, eliminate the later one, which is
+    // more likely to have been allocated just to name the other side.
+    let (var, factor, definition) = match (single_aux(c, lc), single_aux(lc, c)) {
+        (Some((coeff, var)), other) if other.map_or(true, |(_, v)| v < var) => {
+            (var, scalar * &coeff.inverse().unwrap(), lc)
+        },
+        (_, Some((coeff, var))) => (var, (scalar * &coeff).inverse().unwrap(), c),
+        _ => return None,
+    };
+    Some((
+        var,
+        definition
+            .iter()
+            .map(|(k, index)| (*k * &factor, *index))
+            .collect(),
+    ))
+}
+
+/// Replaces `var` in `row` by `lc`. Returns whether `var` occurred in `row`.
+fn substitute<F: Field>(row: &mut Vec<(F, Index)>, var: usize, lc: &[(F, Index)]) -> bool {
+    let position = match row.binary_search_by_key(&Index::Aux(var), |(_, index)| *index) {
+        Ok(position) => position,
+        Err(_) => return false,
+    };
+    let (coeff, _) = row.remove(position);
+    row.extend(lc.iter().map(|(k, index)| (*k * &coeff, *index)));
+    *row = canonicalize_row(core::mem::take(row));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+    use algebra::{bls12_381::Fr, Field, One};
+
+    /// Computes `w = (x + y)^2 * 3x` for public `w`, naming `x + y` and `3x`
+    /// with constraints of the form `lc * 1 = var`.
+    struct Named {
+        x: Option<Fr>,
+        y: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Named {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let missing = || SynthesisError::AssignmentMissing;
+            let x_val = self.x;
+            let sum_val = self.x.and_then(|x| self.y.map(|y| x + &y));
+            let triple_val = x_val.map(|x| x.double() + &x);
+            let square_val = sum_val.map(|s| s.square());
+            let w_val = square_val.and_then(|s| triple_val.map(|t| s * &t));
+
+            let w = cs.alloc_input(|| "w", || w_val.ok_or_else(missing))?;
+            let x = cs.alloc(|| "x", || x_val.ok_or_else(missing))?;
+            let y = cs.alloc(|| "y", || self.y.ok_or_else(missing))?;
+            let sum = cs.alloc(|| "x + y", || sum_val.ok_or_else(missing))?;
+            cs.enforce(
+                || "sum",
+                |lc| lc + x + y,
+                |lc| lc + CS::one(),
+                |lc| lc + sum,
+            );
+            let triple = cs.alloc(|| "3x", || triple_val.ok_or_else(missing))?;
+            cs.enforce(
+                || "triple",
+                |lc| lc + (Fr::from(2u64), CS::one()),
+                |lc| lc + triple,
+                |lc| lc + (Fr::from(6u64), x),
+            );
+            let square = cs.alloc(|| "square", || square_val.ok_or_else(missing))?;
+            cs.enforce(|| "square", |lc| lc + sum, |lc| lc + sum, |lc| lc + square);
+            cs.enforce(|| "w", |lc| lc + square, |lc| lc + triple, |lc| lc + w);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn inlines_named_linear_combinations() {
+        let (x, y) = (Fr::from(2u64), Fr::from(5u64));
+        let mut matrices = ConstraintMatrices::synthesize(Named {
+            x: Some(x),
+            y: Some(y),
+        })
+        .unwrap();
+        assert_eq!(matrices.inline_linear_combinations(), 2);
+        assert_eq!(matrices.num_aux, 3);
+        assert_eq!(matrices.a.len(), 2);
+        assert_eq!(matrices.aux_assignment, Some(vec![x, y, Fr::from(49u64)]));
+        assert_eq!(
+            matrices.a[0],
+            vec![(Fr::one(), Index::Aux(0)), (Fr::one(), Index::Aux(1))]
+        );
+        assert_eq!(matrices.b[1], vec![(Fr::from(3u64), Index::Aux(0))]);
+        assert!(matrices.is_well_formed());
+        assert!(matrices.is_satisfied().unwrap());
+
+        matrices.input_assignment.as_mut().unwrap()[1].double_in_place();
+        assert_eq!(matrices.which_is_unsatisfied().unwrap(), Some(1));
+    }
+
+    #[test]
+    fn inlining_does_not_depend_on_assignment() {
+        let mut setup = ConstraintMatrices::synthesize_setup(Named { x: None, y: None }).unwrap();
+        setup.inline_linear_combinations();
+        let mut proving = ConstraintMatrices::synthesize(Named {
+            x: Some(Fr::from(3u64)),
+            y: Some(Fr::from(4u64)),
+        })
+        .unwrap();
+        proving.inline_linear_combinations();
+        assert_eq!(
+            (&setup.a, &setup.b, &setup.c),
+            (&proving.a, &proving.b, &proving.c)
+        );
+        assert_eq!(setup.num_aux, proving.num_aux);
+        assert!(proving.is_satisfied().unwrap());
+    }
+}
@@ -10,26 +10,43 @@
 #![forbid(unsafe_code)]
 
 #[cfg(not(feature = "std"))]
+#[macro_use]
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-pub(crate) use alloc::string::String;
+pub(crate) use alloc::{boxed::Box, format, rc::Rc, string::String, vec::Vec};
 
 #[cfg(feature = "std")]
-pub(crate) use std::string::String;
+pub(crate) use std::{boxed::Box, format, rc::Rc, string::String, vec::Vec};
 
+pub mod circom;
+mod constraint_matrices;
 mod constraint_system;
+mod constraint_system_ref;
 mod error;
+#[cfg(feature = "parallel")]
+mod fork;
 mod impl_constraint_var;
 mod impl_lc;
+mod lc_inlining;
+mod namespace_tracking;
+mod public_input_layout;
+mod satisfiability;
+mod witness_assignment;
 
 pub use algebra_core::{
     bytes::{FromBytes, ToBytes},
     serialize::*,
     Field, ToConstraintField,
 };
+pub use constraint_matrices::{ConstraintMatrices, Matrix};
 pub use constraint_system::{ConstraintSynthesizer, ConstraintSystem, Namespace};
-pub use error::SynthesisError;
+pub use constraint_system_ref::{ConstraintSystemRef, RefCircuit, RefConstraintSynthesizer};
+pub use error::{ErrorContext, SynthesisError, SynthesisResultExt};
+pub use namespace_tracking::NamespacedCircuit;
+pub use public_input_layout::{PublicInputEntry, PublicInputLayout};
+pub use satisfiability::{CheckedCircuit, SatisfiabilityChecker, UnsatisfiedConstraint};
+pub use witness_assignment::WitnessAssignment;
 
 use core::cmp::Ordering;
 use smallvec::SmallVec as StackVec;
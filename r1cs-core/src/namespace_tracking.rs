@@ -0,0 +1,143 @@
+use crate::{String, Vec};
+use algebra_core::Field;
+
+use crate::{ConstraintSynthesizer, ConstraintSystem, LinearCombination, SynthesisError, Variable};
+
+/// A circuit that keeps track of the namespaces it enters while it is
+/// synthesized, so that errors record the namespace path at which they
+/// occurred even if the constraint system it is synthesized into does not.
+///
+/// The constraint systems of the provers and parameter generators do not keep
+/// track of namespaces, to avoid building the names of namespaces that are
+/// never used. Wrapping a circuit in `NamespacedCircuit`, as they do when the
+/// `namespace-paths` feature is enabled, trades that cost for errors that say
+/// where they occurred. Children synthesized with
+/// [`synthesize_in_parallel`](ConstraintSystem::synthesize_in_parallel) are
+/// then synthesized sequentially.
+pub struct NamespacedCircuit<C>(pub C);
+
+impl<F: Field, C: ConstraintSynthesizer<F>> ConstraintSynthesizer<F> for NamespacedCircuit<C> {
+    fn generate_constraints<CS: ConstraintSystem<F>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        self.0.generate_constraints(&mut NamespaceTracker {
+            cs,
+            current_namespace: Vec::new(),
+        })
+    }
+}
+
+/// Forwards everything to `cs` while keeping track of the namespaces entered.
+struct NamespaceTracker<'a, CS> {
+    cs: &'a mut CS,
+    current_namespace: Vec<String>,
+}
+
+impl<'a, F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for NamespaceTracker<'a, CS> {
+    type Root = Self;
+
+    fn alloc<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.cs.alloc(annotation, f)
+    }
+
+    fn alloc_input<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.cs.alloc_input(annotation, f)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        self.cs.enforce(annotation, a, b, c)
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name = name_fn().into();
+        self.current_namespace.push(name.clone());
+        self.cs.get_root().push_namespace(|| name);
+    }
+
+    fn pop_namespace(&mut self) {
+        assert!(self.current_namespace.pop().is_some());
+        self.cs.get_root().pop_namespace();
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.cs.num_constraints()
+    }
+
+    fn is_in_setup_mode(&self) -> bool {
+        self.cs.is_in_setup_mode()
+    }
+
+    fn namespace_path(&self) -> Option<String> {
+        Some(self.current_namespace.join("/"))
+    }
+
+    fn begin_public_input(&mut self, name: String, ty: String) {
+        self.cs.get_root().begin_public_input(name, ty);
+    }
+
+    fn end_public_input(&mut self) {
+        self.cs.get_root().end_public_input();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstraintMatrices;
+    use algebra::bls12_381::Fr;
+
+    /// Fails to allocate a variable in nested namespaces.
+    struct FailsInNamespace;
+
+    impl ConstraintSynthesizer<Fr> for FailsInNamespace {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let mut cs = cs.ns(|| "outer");
+            cs.ns(|| "inner")
+                .alloc(|| "x", || Err(SynthesisError::DivisionByZero))?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn records_namespace_path_of_errors() {
+        let error = FailsInNamespace
+            .generate_constraints(&mut ConstraintMatrices::<Fr>::new())
+            .unwrap_err();
+        assert_eq!(error.path(), None);
+
+        let error = NamespacedCircuit(FailsInNamespace)
+            .generate_constraints(&mut ConstraintMatrices::<Fr>::new())
+            .unwrap_err();
+        assert!(matches!(error.root_cause(), SynthesisError::DivisionByZero));
+        assert_eq!(error.path(), Some("outer/inner"));
+    }
+}
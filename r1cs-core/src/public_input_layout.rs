@@ -0,0 +1,492 @@
+use crate::{format, String, Vec};
+use algebra_core::{
+    io::{Read, Write},
+    serialize::*,
+    Field,
+};
+
+use crate::{
+    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+};
+
+/// A public input of a circuit, which is encoded as consecutive field
+/// elements of the verifier's input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicInputEntry {
+    /// The name of the public input. Public inputs allocated outside of
+    /// [`ConstraintSystem::public_input`] are named by the namespaces enclosing
+    /// them, followed by their annotation, separated by `/`.
+    pub name: String,
+    /// The type of the public input, as declared by the circuit, or `None` if
+    /// it was allocated outside of [`ConstraintSystem::public_input`], in
+    /// which case it is a single field element.
+    pub ty: Option<String>,
+    /// The index of the first field element of the public input, not counting
+    /// the "one" input.
+    pub offset: usize,
+    /// The number of field elements encoding the public input.
+    pub len: usize,
+}
+
+/// The order, names and types of the public inputs of a circuit.
+///
+/// The layout is recorded by synthesizing the circuit in setup mode, and can
+/// be serialized alongside a verifying key. Verifiers then assemble their
+/// input with [`assemble`](Self::assemble) from named values, which fails on
+/// unknown, missing or duplicate names, or on values of the wrong length,
+/// instead of producing a proof that fails to verify.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PublicInputLayout {
+    /// The public inputs, in the order in which they were allocated.
+    pub entries: Vec<PublicInputEntry>,
+}
+
+impl PublicInputLayout {
+    /// Synthesizes `circuit` in setup mode and records the layout of its
+    /// public inputs.
+    ///
+    /// Returns `SynthesisError::PublicInputMismatch` if two public inputs
+    /// have the same name, since they could not be told apart when
+    /// assembling the verifier's input.
+    pub fn synthesize<F: Field, C: ConstraintSynthesizer<F>>(
+        circuit: C,
+    ) -> Result<Self, SynthesisError> {
+        let mut recorder = LayoutRecorder::default();
+        circuit.generate_constraints(&mut recorder)?;
+        if let Some(name) = recorder.layout.duplicate_name() {
+            return Err(SynthesisError::PublicInputMismatch(format!(
+                "public input `{}` is allocated twice",
+                name
+            )));
+        }
+        Ok(recorder.layout)
+    }
+
+    /// Returns the number of field elements of the verifier's input, not
+    /// counting the "one" input.
+    pub fn num_inputs(&self) -> usize {
+        self.entries.iter().map(|entry| entry.len).sum()
+    }
+
+    /// Returns the public input named `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&PublicInputEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Assembles the verifier's input from the field elements encoding each
+    /// public input, given by name in any order.
+    ///
+    /// Returns `SynthesisError::PublicInputMismatch` if a name is unknown or
+    /// supplied twice, if a public input is missing, or if it is not encoded
+    /// by the expected number of field elements.
+    pub fn assemble<F: Field>(&self, values: &[(&str, &[F])]) -> Result<Vec<F>, SynthesisError> {
+        let mut assigned: Vec<Option<&[F]>> = vec![None; self.entries.len()];
+        for (name, value) in values {
+            let index = self
+                .entries
+                .iter()
+                .position(|entry| entry.name == *name)
+                .ok_or_else(|| {
+                    SynthesisError::PublicInputMismatch(format!("unknown public input `{}`", name))
+                })?;
+            let entry = &self.entries[index];
+            if value.len() != entry.len {
+                return Err(SynthesisError::PublicInputMismatch(format!(
+                    "public input `{}` is encoded by {} field elements, but {} were supplied",
+                    name,
+                    entry.len,
+                    value.len()
+                )));
+            }
+            if assigned[index].replace(value).is_some() {
+                return Err(SynthesisError::PublicInputMismatch(format!(
+                    "public input `{}` was supplied twice",
+                    name
+                )));
+            }
+        }
+        let mut inputs = Vec::with_capacity(self.num_inputs());
+        for (entry, value) in self.entries.iter().zip(assigned) {
+            let value = value.ok_or_else(|| {
+                SynthesisError::PublicInputMismatch(format!(
+                    "public input `{}` is missing",
+                    entry.name
+                ))
+            })?;
+            inputs.extend_from_slice(value);
+        }
+        Ok(inputs)
+    }
+
+    /// Returns a name shared by two entries, if any.
+    fn duplicate_name(&self) -> Option<&str> {
+        let mut names: Vec<&str> = self.entries.iter().map(|entry| &entry.name[..]).collect();
+        names.sort_unstable();
+        names
+            .windows(2)
+            .find(|pair| pair[0] == pair[1])
+            .map(|pair| pair[0])
+    }
+
+    /// Returns whether the entries are contiguous, start at offset zero and
+    /// have distinct names.
+    fn is_well_formed(&self) -> bool {
+        let mut offset = 0;
+        let is_contiguous = self.entries.iter().all(|entry| {
+            let is_contiguous = entry.offset == offset;
+            offset += entry.len;
+            is_contiguous
+        });
+        is_contiguous && self.duplicate_name().is_none()
+    }
+}
+
+/// Constraint system that records the layout of the public inputs of a
+/// circuit, without computing any assignment.
+#[derive(Default)]
+struct LayoutRecorder {
+    layout: PublicInputLayout,
+    num_inputs: usize,
+    num_aux: usize,
+    num_constraints: usize,
+    current_namespace: Vec<String>,
+    /// The public input being allocated, if any, and how many
+    /// `begin_public_input` calls it encloses.
+    current_input: Option<(PublicInputEntry, usize)>,
+}
+
+impl<F: Field> ConstraintSystem<F> for LayoutRecorder {
+    type Root = Self;
+
+    fn alloc<FN, A, AR>(&mut self, _: A, _: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.num_aux += 1;
+        Ok(Variable::new_unchecked(Index::Aux(self.num_aux - 1)))
+    }
+
+    fn alloc_input<FN, A, AR>(&mut self, annotation: A, _: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        match &mut self.current_input {
+            Some((entry, _)) => entry.len += 1,
+            None => {
+                let mut path = self.current_namespace.join("/");
+                if !path.is_empty() {
+                    path.push('/');
+                }
+                let name: String = annotation().into();
+                path.push_str(&name);
+                self.layout.entries.push(PublicInputEntry {
+                    name: path,
+                    ty: None,
+                    offset: self.num_inputs,
+                    len: 1,
+                });
+            },
+        }
+        self.num_inputs += 1;
+        Ok(Variable::new_unchecked(Index::Input(self.num_inputs)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, _: LA, _: LB, _: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        self.num_constraints += 1;
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.current_namespace.push(name_fn().into());
+    }
+
+    fn pop_namespace(&mut self) {
+        assert!(self.current_namespace.pop().is_some());
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_constraints
+    }
+
+    fn is_in_setup_mode(&self) -> bool {
+        true
+    }
+
+    fn namespace_path(&self) -> Option<String> {
+        Some(self.current_namespace.join("/"))
+    }
+
+    fn begin_public_input(&mut self, name: String, ty: String) {
+        match &mut self.current_input {
+            Some((_, depth)) => *depth += 1,
+            None => {
+                let entry = PublicInputEntry {
+                    name,
+                    ty: Some(ty),
+                    offset: self.num_inputs,
+                    len: 0,
+                };
+                self.current_input = Some((entry, 0));
+            },
+        }
+    }
+
+    fn end_public_input(&mut self) {
+        match self.current_input.take() {
+            Some((entry, 0)) => self.layout.entries.push(entry),
+            Some((entry, depth)) => self.current_input = Some((entry, depth - 1)),
+            None => panic!("end_public_input called without a matching begin_public_input"),
+        }
+    }
+}
+
+fn serialize_string<W: Write>(s: &str, writer: W) -> Result<(), SerializationError> {
+    s.as_bytes().to_vec().serialize(writer)
+}
+
+fn deserialize_string<R: Read>(reader: R) -> Result<String, SerializationError> {
+    String::from_utf8(Vec::<u8>::deserialize(reader)?).map_err(|_| SerializationError::InvalidData)
+}
+
+impl CanonicalSerialize for PublicInputEntry {
+    #[inline]
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        serialize_string(&self.name, &mut writer)?;
+        self.ty.is_some().serialize(&mut writer)?;
+        if let Some(ty) = &self.ty {
+            serialize_string(ty, &mut writer)?;
+        }
+        self.offset.serialize(&mut writer)?;
+        self.len.serialize(&mut writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.name.as_bytes().to_vec().serialized_size()
+            + self.ty.is_some().serialized_size()
+            + self
+                .ty
+                .as_ref()
+                .map_or(0, |ty| ty.as_bytes().to_vec().serialized_size())
+            + self.offset.serialized_size()
+            + self.len.serialized_size()
+    }
+}
+
+impl CanonicalDeserialize for PublicInputEntry {
+    #[inline]
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let name = deserialize_string(&mut reader)?;
+        let ty = if bool::deserialize(&mut reader)? {
+            Some(deserialize_string(&mut reader)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            name,
+            ty,
+            offset: usize::deserialize(&mut reader)?,
+            len: usize::deserialize(&mut reader)?,
+        })
+    }
+}
+
+impl CanonicalSerialize for PublicInputLayout {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.entries.serialize(writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.entries.serialized_size()
+    }
+}
+
+impl CanonicalDeserialize for PublicInputLayout {
+    /// Fails unless the entries are contiguous, start at offset zero and have
+    /// distinct names.
+    #[inline]
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        let layout = Self {
+            entries: Vec::deserialize(reader)?,
+        };
+        if !layout.is_well_formed() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstraintMatrices;
+    use algebra::bls12_381::Fr;
+
+    /// Proves knowledge of the preimage of a "commitment" `x + r`, revealing a
+    /// named commitment, a named pair of bounds, and an unnamed serial number.
+    struct Commitment {
+        x: Option<Fr>,
+        r: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Commitment {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let (x_val, r_val) = (self.x, self.r);
+            let cm_val = x_val.and_then(|x| r_val.map(|r| x + &r));
+            let x = cs.alloc(|| "x", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+            let r = cs.alloc(|| "r", || r_val.ok_or(SynthesisError::AssignmentMissing))?;
+            let cm = cs.public_input("commitment", "Fr", |cs| {
+                cs.alloc_input(|| "cm", || cm_val.ok_or(SynthesisError::AssignmentMissing))
+            })?;
+            cs.enforce(
+                || "x + r = cm",
+                |lc| lc + x + r,
+                |lc| lc + CS::one(),
+                |lc| lc + cm,
+            );
+            cs.public_input("bounds", "(Fr, Fr)", |cs| {
+                // Nested public inputs belong to the outermost one.
+                cs.public_input("lower", "Fr", |cs| {
+                    cs.alloc_input(|| "lower", || Ok(Fr::from(0u64)))
+                })?;
+                cs.alloc_input(|| "upper", || Ok(Fr::from(10u64)))
+            })?;
+            let mut cs = cs.ns(|| "serial");
+            cs.alloc_input(|| "sn", || Ok(Fr::from(7u64)))?;
+            Ok(())
+        }
+    }
+
+    fn layout() -> PublicInputLayout {
+        PublicInputLayout::synthesize::<Fr, _>(Commitment { x: None, r: None }).unwrap()
+    }
+
+    #[test]
+    fn records_named_and_unnamed_inputs() {
+        let layout = layout();
+        let entry = |name: &str, ty: Option<&str>, offset, len| PublicInputEntry {
+            name: name.into(),
+            ty: ty.map(String::from),
+            offset,
+            len,
+        };
+        assert_eq!(
+            layout.entries,
+            vec![
+                entry("commitment", Some("Fr"), 0, 1),
+                entry("bounds", Some("(Fr, Fr)"), 1, 2),
+                entry("serial/sn", None, 3, 1),
+            ]
+        );
+        assert_eq!(layout.num_inputs(), 4);
+
+        let mut bytes = Vec::new();
+        layout.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), layout.serialized_size());
+        assert_eq!(PublicInputLayout::deserialize(&bytes[..]).unwrap(), layout);
+    }
+
+    #[test]
+    fn assembles_inputs_matching_the_circuit() {
+        let (x, r) = (Fr::from(2u64), Fr::from(3u64));
+        let matrices = ConstraintMatrices::synthesize(Commitment {
+            x: Some(x),
+            r: Some(r),
+        })
+        .unwrap();
+        let layout = layout();
+        let bounds = [Fr::from(0u64), Fr::from(10u64)];
+        let inputs = layout
+            .assemble(&[
+                ("serial/sn", &[Fr::from(7u64)][..]),
+                ("bounds", &bounds[..]),
+                ("commitment", &[x + &r][..]),
+            ])
+            .unwrap();
+        assert_eq!(&inputs[..], &matrices.input_assignment.unwrap()[1..]);
+
+        let mismatch = |values: &[(&str, &[Fr])]| match layout.assemble(values) {
+            Err(SynthesisError::PublicInputMismatch(message)) => message,
+            result => panic!("unexpected result {:?}", result),
+        };
+        assert_eq!(
+            mismatch(&[("commitment", &bounds[..])]),
+            "public input `commitment` is encoded by 1 field elements, but 2 were supplied"
+        );
+        assert_eq!(
+            mismatch(&[("nullifier", &[x][..])]),
+            "unknown public input `nullifier`"
+        );
+        assert_eq!(
+            mismatch(&[("commitment", &[x][..]), ("commitment", &[r][..])]),
+            "public input `commitment` was supplied twice"
+        );
+        assert_eq!(
+            mismatch(&[("commitment", &[x][..]), ("bounds", &bounds[..])]),
+            "public input `serial/sn` is missing"
+        );
+    }
+
+    /// Allocates two public inputs with the same name.
+    struct DuplicateInputs;
+
+    impl ConstraintSynthesizer<Fr> for DuplicateInputs {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            for i in 0..2 {
+                cs.public_input("nullifier", "Fr", |cs| {
+                    cs.alloc_input(|| format!("nullifier {}", i), || Ok(Fr::from(0u64)))
+                })?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn rejects_duplicate_names() {
+        match PublicInputLayout::synthesize::<Fr, _>(DuplicateInputs) {
+            Err(SynthesisError::PublicInputMismatch(message)) => {
+                assert_eq!(message, "public input `nullifier` is allocated twice")
+            },
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let entry = |offset| PublicInputEntry {
+            name: "nullifier".into(),
+            ty: None,
+            offset,
+            len: 1,
+        };
+        let mut bytes = Vec::new();
+        vec![entry(0), entry(1)].serialize(&mut bytes).unwrap();
+        assert!(matches!(
+            PublicInputLayout::deserialize(&bytes[..]),
+            Err(SerializationError::InvalidData)
+        ));
+    }
+}
@@ -0,0 +1,530 @@
+use crate::{format, String, Vec};
+use algebra_core::Field;
+use core::fmt;
+
+use crate::{
+    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+};
+
+/// A constraint `A * B = C` that is not satisfied by the assignment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsatisfiedConstraint<F: Field> {
+    /// The index of the constraint, in the order in which constraints were
+    /// enforced.
+    pub index: usize,
+    /// The names of the namespaces enclosing the constraint, followed by the
+    /// name of the constraint, separated by `/`.
+    pub path: String,
+    /// The value of `A`.
+    pub a: F,
+    /// The value of `B`.
+    pub b: F,
+    /// The value of `C`.
+    pub c: F,
+}
+
+impl<F: Field> fmt::Display for UnsatisfiedConstraint<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraint {} ({}) is unsatisfied: A = {}, B = {}, A * B = {}, C = {}",
+            self.index,
+            self.path,
+            self.a,
+            self.b,
+            self.a * &self.b,
+            self.c
+        )
+    }
+}
+
+/// Constraint system that checks each constraint against the assignment as it
+/// is enforced, and records the constraints that are not satisfied along with
+/// their namespace paths.
+///
+/// Unlike the test constraint system of `r1cs-std`, the checker does not keep
+/// the constraints or the names of variables around, so that it can be used on
+/// large circuits in release builds. It can also check a circuit while it is
+/// synthesized into another constraint system, see
+/// [`synthesize_checked`](Self::synthesize_checked) and [`CheckedCircuit`].
+#[derive(Clone, Debug)]
+pub struct SatisfiabilityChecker<F: Field> {
+    inputs: Vec<Option<F>>,
+    aux: Vec<Option<F>>,
+    current_namespace: Vec<String>,
+    num_constraints: usize,
+    unsatisfied: Vec<UnsatisfiedConstraint<F>>,
+    /// The namespace path and name of the first constraint referring to a
+    /// variable whose value the checker does not know.
+    unknown_variable: Option<(String, String)>,
+}
+
+impl<F: Field> Default for SatisfiabilityChecker<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> SatisfiabilityChecker<F> {
+    /// Creates a checker whose only variable is the "one" input.
+    pub fn new() -> Self {
+        Self {
+            inputs: vec![Some(F::one())],
+            aux: Vec::new(),
+            current_namespace: Vec::new(),
+            num_constraints: 0,
+            unsatisfied: Vec::new(),
+            unknown_variable: None,
+        }
+    }
+
+    /// Synthesizes `circuit` and checks its constraints.
+    ///
+    /// Fails with `SynthesisError::AssignmentMissing` if a constraint refers
+    /// to a variable that `circuit` did not allocate, other than the "one"
+    /// input.
+    pub fn check<C: ConstraintSynthesizer<F>>(circuit: C) -> Result<Self, SynthesisError> {
+        let mut checker = Self::new();
+        circuit.generate_constraints(&mut checker)?;
+        checker.check_variables()?;
+        Ok(checker)
+    }
+
+    /// Synthesizes `circuit` into `cs` and checks its constraints at the same
+    /// time. The variables of `circuit` are those allocated by `cs`.
+    ///
+    /// Fails with `SynthesisError::AssignmentMissing` if a constraint refers
+    /// to a variable that `circuit` did not allocate, other than the "one"
+    /// input.
+    pub fn synthesize_checked<C, CS>(circuit: C, cs: &mut CS) -> Result<Self, SynthesisError>
+    where
+        C: ConstraintSynthesizer<F>,
+        CS: ConstraintSystem<F>,
+    {
+        let mut checker = Self::new();
+        circuit.generate_constraints(&mut Tee {
+            checker: &mut checker,
+            cs,
+        })?;
+        checker.check_variables()?;
+        Ok(checker)
+    }
+
+    /// Returns the first constraint that is not satisfied, if any.
+    pub fn which_is_unsatisfied(&self) -> Option<&UnsatisfiedConstraint<F>> {
+        self.unsatisfied.first()
+    }
+
+    /// Returns all constraints that are not satisfied, in order.
+    pub fn unsatisfied_constraints(&self) -> &[UnsatisfiedConstraint<F>] {
+        &self.unsatisfied
+    }
+
+    /// Returns whether all constraints are satisfied.
+    pub fn is_satisfied(&self) -> bool {
+        self.unsatisfied.is_empty()
+    }
+
+    /// Describes the constraints that are not satisfied, one per line.
+    pub fn report(&self) -> String {
+        let lines: Vec<_> = self.unsatisfied.iter().map(|c| format!("{}", c)).collect();
+        lines.join("\n")
+    }
+
+    /// Returns `SynthesisError::Unsatisfiable` with the
+    /// [`report`](Self::report) attached as context unless all constraints
+    /// are satisfied.
+    pub fn into_result(self) -> Result<(), SynthesisError> {
+        self.check_variables()?;
+        if self.is_satisfied() {
+            Ok(())
+        } else {
+            Err(SynthesisError::Unsatisfiable.context(self.report()))
+        }
+    }
+
+    /// Fails with `SynthesisError::AssignmentMissing`, at the namespace path
+    /// of the first constraint that refers to a variable unknown to the
+    /// checker, if there is one.
+    fn check_variables(&self) -> Result<(), SynthesisError> {
+        match &self.unknown_variable {
+            Some((path, name)) => Err(SynthesisError::AssignmentMissing
+                .context(format!(
+                    "constraint {} refers to a variable unknown to the checker",
+                    name
+                ))
+                .at_path(path.clone())),
+            None => Ok(()),
+        }
+    }
+
+    fn set_assignment(assignment: &mut Vec<Option<F>>, index: usize, value: F) {
+        if assignment.len() <= index {
+            assignment.resize(index + 1, None);
+        }
+        assignment[index] = Some(value);
+    }
+
+    /// Evaluates `lc`, or returns `None` if it refers to a variable unknown to
+    /// the checker.
+    fn eval(&self, lc: &LinearCombination<F>) -> Option<F> {
+        let mut acc = F::zero();
+        for (var, coeff) in lc.as_ref() {
+            let value = match var.get_unchecked() {
+                Index::Input(i) => self.inputs.get(i),
+                Index::Aux(i) => self.aux.get(i),
+            };
+            acc += &(value.copied().flatten()? * coeff);
+        }
+        Some(acc)
+    }
+
+    fn path_of(&self, name: &str) -> String {
+        let mut path = self.current_namespace.join("/");
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(name);
+        path
+    }
+
+    /// Checks the constraint `a * b = c`, and records it if it is not
+    /// satisfied or refers to a variable unknown to the checker.
+    /// `annotation` is only invoked in those cases, and its result is
+    /// returned.
+    fn check_constraint(
+        &mut self,
+        annotation: impl FnOnce() -> String,
+        a: &LinearCombination<F>,
+        b: &LinearCombination<F>,
+        c: &LinearCombination<F>,
+    ) -> Option<String> {
+        let index = self.num_constraints;
+        self.num_constraints += 1;
+        let (a, b, c) = match (self.eval(a), self.eval(b), self.eval(c)) {
+            (Some(a), Some(b), Some(c)) => (a, b, c),
+            _ => {
+                let name = annotation();
+                if self.unknown_variable.is_none() {
+                    let path = self.current_namespace.join("/");
+                    self.unknown_variable = Some((path, name.clone()));
+                }
+                return Some(name);
+            },
+        };
+        if a * &b == c {
+            return None;
+        }
+        let name = annotation();
+        let path = self.path_of(&name);
+        self.unsatisfied.push(UnsatisfiedConstraint {
+            index,
+            path,
+            a,
+            b,
+            c,
+        });
+        Some(name)
+    }
+}
+
+impl<F: Field> ConstraintSystem<F> for SatisfiabilityChecker<F> {
+    type Root = Self;
+
+    fn alloc<FN, A, AR>(&mut self, _: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.aux.len();
+        self.aux.push(Some(f()?));
+        Ok(Variable::new_unchecked(Index::Aux(index)))
+    }
+
+    fn alloc_input<FN, A, AR>(&mut self, _: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.inputs.len();
+        self.inputs.push(Some(f()?));
+        Ok(Variable::new_unchecked(Index::Input(index)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        self.check_constraint(
+            || annotation().into(),
+            &a(LinearCombination::zero()),
+            &b(LinearCombination::zero()),
+            &c(LinearCombination::zero()),
+        );
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.current_namespace.push(name_fn().into());
+    }
+
+    fn pop_namespace(&mut self) {
+        assert!(self.current_namespace.pop().is_some());
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_constraints
+    }
+
+    fn namespace_path(&self) -> Option<String> {
+        Some(self.current_namespace.join("/"))
+    }
+}
+
+/// Forwards everything to `cs` while checking the constraints with `checker`.
+struct Tee<'a, F: Field, CS: ConstraintSystem<F>> {
+    checker: &'a mut SatisfiabilityChecker<F>,
+    cs: &'a mut CS,
+}
+
+impl<'a, F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for Tee<'a, F, CS> {
+    type Root = Self;
+
+    fn alloc<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let value = f()?;
+        let var = self.cs.alloc(annotation, || Ok(value))?;
+        match var.get_unchecked() {
+            Index::Input(i) => {
+                SatisfiabilityChecker::set_assignment(&mut self.checker.inputs, i, value)
+            },
+            Index::Aux(i) => SatisfiabilityChecker::set_assignment(&mut self.checker.aux, i, value),
+        }
+        Ok(var)
+    }
+
+    fn alloc_input<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let value = f()?;
+        let var = self.cs.alloc_input(annotation, || Ok(value))?;
+        match var.get_unchecked() {
+            Index::Input(i) => {
+                SatisfiabilityChecker::set_assignment(&mut self.checker.inputs, i, value)
+            },
+            Index::Aux(i) => SatisfiabilityChecker::set_assignment(&mut self.checker.aux, i, value),
+        }
+        Ok(var)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        let a = a(LinearCombination::zero());
+        let b = b(LinearCombination::zero());
+        let c = c(LinearCombination::zero());
+        let mut annotation = Some(annotation);
+        let name =
+            self.checker
+                .check_constraint(|| annotation.take().unwrap()().into(), &a, &b, &c);
+        let la = move |lc: LinearCombination<F>| lc + a;
+        let lb = move |lc: LinearCombination<F>| lc + b;
+        let lc = move |lc: LinearCombination<F>| lc + c;
+        match (name, annotation) {
+            (Some(name), _) => self.cs.enforce(|| name, la, lb, lc),
+            (None, Some(annotation)) => self.cs.enforce(annotation, la, lb, lc),
+            (None, None) => unreachable!(),
+        }
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name = name_fn().into();
+        self.checker.current_namespace.push(name.clone());
+        self.cs.get_root().push_namespace(|| name);
+    }
+
+    fn pop_namespace(&mut self) {
+        self.checker.pop_namespace();
+        self.cs.get_root().pop_namespace();
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.cs.num_constraints()
+    }
+
+    fn is_in_setup_mode(&self) -> bool {
+        self.cs.is_in_setup_mode()
+    }
+
+    fn namespace_path(&self) -> Option<String> {
+        self.checker.namespace_path()
+    }
+
+    fn begin_public_input(&mut self, name: String, ty: String) {
+        self.cs.get_root().begin_public_input(name, ty);
+    }
+
+    fn end_public_input(&mut self) {
+        self.cs.get_root().end_public_input();
+    }
+}
+
+/// A circuit that is checked with a [`SatisfiabilityChecker`] while it is
+/// synthesized, and fails with `SynthesisError::Unsatisfiable`, with a
+/// report of the unsatisfied constraints attached as context, if the
+/// assignment does not satisfy it.
+///
+/// Wrapping a circuit in `CheckedCircuit` when proving, as the
+/// `create_proof_checked` entry points of the provers do, turns the invalid
+/// proof that would result from a wrong witness into an error. The circuit
+/// must not be wrapped during parameter generation, where there is no
+/// assignment to check.
+pub struct CheckedCircuit<C>(pub C);
+
+impl<F: Field, C: ConstraintSynthesizer<F>> ConstraintSynthesizer<F> for CheckedCircuit<C> {
+    fn generate_constraints<CS: ConstraintSystem<F>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        SatisfiabilityChecker::synthesize_checked(self.0, cs)?.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstraintMatrices;
+    use algebra::{bls12_381::Fr, One};
+
+    /// Proves knowledge of the factors `a` and `b` of a public `c`, with one
+    /// constraint in nested namespaces.
+    struct Factors {
+        a: Fr,
+        b: Fr,
+        c: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Factors {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let c = cs.alloc_input(|| "c", || Ok(self.c))?;
+            let mut cs = cs.ns(|| "factors");
+            let a = cs.alloc(|| "a", || Ok(self.a))?;
+            let b = cs.alloc(|| "b", || Ok(self.b))?;
+            cs.ns(|| "product")
+                .enforce(|| "a * b = c", |lc| lc + a, |lc| lc + b, |lc| lc + c);
+            cs.enforce(
+                || "a != 0",
+                |lc| lc + a,
+                |lc| lc + CS::one(),
+                |lc| lc + (self.a, CS::one()),
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reports_unsatisfied_constraints() {
+        let (a, b) = (Fr::from(3u64), Fr::from(5u64));
+        let checker = SatisfiabilityChecker::check(Factors { a, b, c: a * &b }).unwrap();
+        assert!(checker.is_satisfied());
+        assert_eq!(checker.num_constraints(), 2);
+        assert_eq!(checker.report(), "");
+
+        let checker = SatisfiabilityChecker::check(Factors { a, b, c: Fr::one() }).unwrap();
+        assert!(!checker.is_satisfied());
+        assert_eq!(
+            checker.which_is_unsatisfied(),
+            Some(&UnsatisfiedConstraint {
+                index: 0,
+                path: "factors/product/a * b = c".into(),
+                a,
+                b,
+                c: Fr::one(),
+            })
+        );
+        assert_eq!(checker.unsatisfied_constraints().len(), 1);
+        assert!(checker
+            .report()
+            .starts_with("constraint 0 (factors/product/a * b = c) is unsatisfied"));
+    }
+
+    #[test]
+    fn checks_while_synthesizing_into_another_system() {
+        let (a, b) = (Fr::from(3u64), Fr::from(5u64));
+        let mut matrices = ConstraintMatrices::new();
+        CheckedCircuit(Factors { a, b, c: a * &b })
+            .generate_constraints(&mut matrices)
+            .unwrap();
+        assert_eq!(
+            matrices,
+            ConstraintMatrices::synthesize(Factors { a, b, c: a * &b }).unwrap()
+        );
+
+        let result = CheckedCircuit(Factors { a, b, c: b })
+            .generate_constraints(&mut ConstraintMatrices::new());
+        let error = result.unwrap_err();
+        assert!(matches!(error.root_cause(), SynthesisError::Unsatisfiable));
+        assert!(error.messages()[0].contains("factors/product/a * b = c"));
+    }
+
+    #[test]
+    fn rejects_unknown_variables() {
+        struct UnknownVariable;
+
+        impl ConstraintSynthesizer<Fr> for UnknownVariable {
+            fn generate_constraints<CS: ConstraintSystem<Fr>>(
+                self,
+                cs: &mut CS,
+            ) -> Result<(), SynthesisError> {
+                let unknown = Variable::new_unchecked(Index::Aux(0));
+                cs.ns(|| "outer")
+                    .enforce(|| "x = x", |lc| lc + unknown, |lc| lc, |lc| lc);
+                Ok(())
+            }
+        }
+
+        let error = SatisfiabilityChecker::check(UnknownVariable).unwrap_err();
+        assert!(matches!(
+            error.root_cause(),
+            SynthesisError::AssignmentMissing
+        ));
+        assert_eq!(error.path(), Some("outer"));
+        assert!(error.messages()[0].contains("constraint x = x"));
+    }
+}
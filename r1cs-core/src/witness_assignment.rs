@@ -0,0 +1,161 @@
+use crate::{String, Vec};
+use algebra_core::Field;
+
+use crate::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination,
+    SynthesisError, Variable,
+};
+
+/// Constraint system that only records the assignment of variables.
+///
+/// `enforce` does not even build the linear combinations of a constraint, so
+/// synthesizing a circuit into a `WitnessAssignment` is much cheaper than
+/// synthesizing it into a prover's constraint system. The constraints
+/// themselves are computed once, for instance with
+/// [`ConstraintMatrices::synthesize`] at setup, and paired with the witness
+/// by the proving systems' `create_proof_with_matrices`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WitnessAssignment<F: Field> {
+    /// The assignment of the public inputs, starting with the "one" input.
+    pub input_assignment: Vec<F>,
+    /// The assignment of the private variables.
+    pub aux_assignment: Vec<F>,
+    num_constraints: usize,
+}
+
+impl<F: Field> Default for WitnessAssignment<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> WitnessAssignment<F> {
+    /// Creates an assignment whose only variable is the "one" input.
+    pub fn new() -> Self {
+        Self {
+            input_assignment: vec![F::one()],
+            aux_assignment: Vec::new(),
+            num_constraints: 0,
+        }
+    }
+
+    /// Synthesizes `circuit` and records its assignment.
+    pub fn generate<C: ConstraintSynthesizer<F>>(circuit: C) -> Result<Self, SynthesisError> {
+        let mut cs = Self::new();
+        circuit.generate_constraints(&mut cs)?;
+        Ok(cs)
+    }
+
+    /// Returns whether the assignment has as many public inputs and private
+    /// variables as `matrices`.
+    pub fn fits(&self, matrices: &ConstraintMatrices<F>) -> bool {
+        self.input_assignment.len() == matrices.num_inputs
+            && self.aux_assignment.len() == matrices.num_aux
+    }
+}
+
+impl<F: Field> ConstraintSystem<F> for WitnessAssignment<F> {
+    type Root = Self;
+
+    #[inline]
+    fn alloc<FN, A, AR>(&mut self, _: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.aux_assignment.len();
+        self.aux_assignment.push(f()?);
+        Ok(Variable::new_unchecked(Index::Aux(index)))
+    }
+
+    #[inline]
+    fn alloc_input<FN, A, AR>(&mut self, _: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.input_assignment.len();
+        self.input_assignment.push(f()?);
+        Ok(Variable::new_unchecked(Index::Input(index)))
+    }
+
+    #[inline]
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, _: LA, _: LB, _: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        self.num_constraints += 1;
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self) {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_constraints
+    }
+
+    fn supports_parallel_synthesis(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algebra::bls12_381::Fr;
+
+    struct Square(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Square {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let x_val = self.0;
+            let y = cs.alloc_input(
+                || "y",
+                || {
+                    x_val
+                        .map(|x| x * &x)
+                        .ok_or(SynthesisError::AssignmentMissing)
+                },
+            )?;
+            let x = cs.alloc(|| "x", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "x * x = y", |lc| lc + x, |lc| lc + x, |lc| lc + y);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn matches_full_synthesis() {
+        let x = Fr::from(7u64);
+        let witness = WitnessAssignment::generate(Square(Some(x))).unwrap();
+        assert_eq!(witness.num_constraints(), 1);
+
+        let index = ConstraintMatrices::synthesize(Square(None)).unwrap();
+        assert!(witness.fits(&index));
+        assert!(!WitnessAssignment::new().fits(&index));
+        let full = ConstraintMatrices::synthesize(Square(Some(x))).unwrap();
+        assert_eq!(full.input_assignment, Some(witness.input_assignment));
+        assert_eq!(full.aux_assignment, Some(witness.aux_assignment));
+    }
+}
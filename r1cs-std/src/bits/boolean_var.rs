@@ -0,0 +1,193 @@
+use algebra::Field;
+use core::{
+    borrow::Borrow,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not},
+};
+use r1cs_core::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+
+use crate::prelude::*;
+
+/// A [`Boolean`] together with a handle to the constraint system it lives in,
+/// so that boolean logic can be written with operators.
+///
+/// Panics if the operands of an operator belong to different constraint
+/// systems.
+#[derive(Clone, Debug)]
+pub struct BooleanVar<ConstraintF: Field> {
+    cs: ConstraintSystemRef<ConstraintF>,
+    gadget: Boolean,
+}
+
+impl<ConstraintF: Field> BooleanVar<ConstraintF> {
+    pub fn new(cs: ConstraintSystemRef<ConstraintF>, gadget: Boolean) -> Self {
+        Self { cs, gadget }
+    }
+
+    /// Allocates a private variable in `cs`.
+    pub fn new_witness<FN, T>(
+        cs: ConstraintSystemRef<ConstraintF>,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<bool>,
+    {
+        let gadget = Boolean::alloc(cs.clone().ns(|| "alloc"), f)?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    /// Allocates a public input in `cs`.
+    pub fn new_input<FN, T>(
+        cs: ConstraintSystemRef<ConstraintF>,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<bool>,
+    {
+        let gadget = Boolean::alloc_input(cs.clone().ns(|| "alloc input"), f)?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    /// Creates a constant, which needs no variables or constraints.
+    pub fn constant(cs: ConstraintSystemRef<ConstraintF>, value: bool) -> Self {
+        Self::new(cs, Boolean::constant(value))
+    }
+
+    pub fn cs(&self) -> &ConstraintSystemRef<ConstraintF> {
+        &self.cs
+    }
+
+    pub fn gadget(&self) -> &Boolean {
+        &self.gadget
+    }
+
+    pub fn into_gadget(self) -> Boolean {
+        self.gadget
+    }
+
+    pub fn value(&self) -> Option<bool> {
+        self.gadget.get_value()
+    }
+
+    pub fn enforce_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+        self.assert_same_cs(other);
+        self.gadget
+            .enforce_equal(self.cs.clone().ns(|| "enforce equal"), &other.gadget)
+    }
+
+    fn assert_same_cs(&self, other: &Self) {
+        assert!(
+            self.cs.is_same(&other.cs),
+            "operands belong to different constraint systems"
+        );
+    }
+}
+
+macro_rules! impl_boolean_var_op {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $op:ident) => {
+        impl<'a, ConstraintF: Field> $trait<&'a BooleanVar<ConstraintF>>
+            for &BooleanVar<ConstraintF>
+        {
+            type Output = BooleanVar<ConstraintF>;
+
+            fn $fn(self, other: &'a BooleanVar<ConstraintF>) -> Self::Output {
+                self.assert_same_cs(other);
+                let mut cs = self.cs.clone();
+                let gadget = Boolean::$op(cs.ns(|| stringify!($op)), &self.gadget, &other.gadget)
+                    .unwrap_or_else(|e| panic!("could not synthesize {}: {}", stringify!($op), e));
+                BooleanVar::new(self.cs.clone(), gadget)
+            }
+        }
+
+        impl<ConstraintF: Field> $trait<BooleanVar<ConstraintF>> for &BooleanVar<ConstraintF> {
+            type Output = BooleanVar<ConstraintF>;
+
+            fn $fn(self, other: BooleanVar<ConstraintF>) -> Self::Output {
+                self.$fn(&other)
+            }
+        }
+
+        impl<'a, ConstraintF: Field> $trait<&'a BooleanVar<ConstraintF>>
+            for BooleanVar<ConstraintF>
+        {
+            type Output = BooleanVar<ConstraintF>;
+
+            fn $fn(self, other: &'a BooleanVar<ConstraintF>) -> Self::Output {
+                (&self).$fn(other)
+            }
+        }
+
+        impl<ConstraintF: Field> $trait<BooleanVar<ConstraintF>> for BooleanVar<ConstraintF> {
+            type Output = BooleanVar<ConstraintF>;
+
+            fn $fn(self, other: BooleanVar<ConstraintF>) -> Self::Output {
+                (&self).$fn(&other)
+            }
+        }
+
+        impl<'a, ConstraintF: Field> $assign_trait<&'a BooleanVar<ConstraintF>>
+            for BooleanVar<ConstraintF>
+        {
+            fn $assign_fn(&mut self, other: &'a BooleanVar<ConstraintF>) {
+                *self = (&*self).$fn(other);
+            }
+        }
+
+        impl<ConstraintF: Field> $assign_trait<BooleanVar<ConstraintF>>
+            for BooleanVar<ConstraintF>
+        {
+            fn $assign_fn(&mut self, other: BooleanVar<ConstraintF>) {
+                *self = (&*self).$fn(&other);
+            }
+        }
+    };
+}
+
+impl_boolean_var_op!(BitAnd, bitand, BitAndAssign, bitand_assign, and);
+impl_boolean_var_op!(BitOr, bitor, BitOrAssign, bitor_assign, or);
+impl_boolean_var_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, xor);
+
+impl<ConstraintF: Field> Not for &BooleanVar<ConstraintF> {
+    type Output = BooleanVar<ConstraintF>;
+
+    fn not(self) -> Self::Output {
+        BooleanVar::new(self.cs.clone(), self.gadget.not())
+    }
+}
+
+impl<ConstraintF: Field> Not for BooleanVar<ConstraintF> {
+    type Output = BooleanVar<ConstraintF>;
+
+    fn not(self) -> Self::Output {
+        !&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algebra::bls12_381::Fr;
+
+    #[test]
+    fn operators_match_native_logic() {
+        let cs = ConstraintSystemRef::<Fr>::new();
+        for &(a, b) in &[(false, false), (false, true), (true, false), (true, true)] {
+            let a_var = BooleanVar::new_witness(cs.clone(), || Ok(a)).unwrap();
+            let b_var = BooleanVar::new_input(cs.clone(), || Ok(b)).unwrap();
+            let c_var = BooleanVar::constant(cs.clone(), true);
+
+            assert_eq!((&a_var & &b_var).value(), Some(a & b));
+            assert_eq!((&a_var | &b_var).value(), Some(a | b));
+            assert_eq!((&a_var ^ &b_var).value(), Some(a ^ b));
+            assert_eq!((!&a_var).value(), Some(!a));
+
+            let mut acc = a_var.clone();
+            acc ^= &c_var;
+            acc |= b_var.clone();
+            acc &= !a_var;
+            assert_eq!(acc.value(), Some((!a | b) & !a));
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -6,10 +6,13 @@ use algebra::Field;
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
 pub mod boolean;
+pub mod boolean_var;
 pub mod uint32;
 pub mod uint64;
 pub mod uint8;
 
+pub use self::boolean_var::BooleanVar;
+
 pub trait ToBitsGadget<ConstraintF: Field> {
     /// Outputs the canonical bit-wise representation of `self`.
     ///
@@ -295,9 +295,13 @@ impl<ConstraintF: Field> AllocGadget<u64, ConstraintF> for UInt64 {
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<u64>,
     {
-        let val = value_gen()?.borrow().clone();
+        let val = match cs.compute_assignment(value_gen) {
+            Ok(val) => Some(val.borrow().clone()),
+            Err(SynthesisError::AssignmentMissing) if cs.is_in_setup_mode() => None,
+            Err(e) => return Err(e),
+        };
 
-        Self::_alloc(&mut cs.ns(|| "alloc u64"), Some(val))
+        Self::_alloc(&mut cs.ns(|| "alloc u64"), val)
     }
 
     fn alloc_input<F, T, CS: ConstraintSystem<ConstraintF>>(
@@ -308,8 +312,12 @@ impl<ConstraintF: Field> AllocGadget<u64, ConstraintF> for UInt64 {
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<u64>,
     {
-        let val = value_gen()?.borrow().clone();
-        Self::_alloc(&mut cs.ns(|| "alloc u64"), Some(val))
+        let val = match cs.compute_assignment(value_gen) {
+            Ok(val) => Some(val.borrow().clone()),
+            Err(SynthesisError::AssignmentMissing) if cs.is_in_setup_mode() => None,
+            Err(e) => return Err(e),
+        };
+        Self::_alloc(&mut cs.ns(|| "alloc u64"), val)
     }
 }
 
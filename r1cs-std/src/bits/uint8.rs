@@ -232,7 +232,7 @@ impl<ConstraintF: Field> AllocGadget<u8, ConstraintF> for UInt8 {
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<u8>,
     {
-        let value = value_gen().map(|val| *val.borrow());
+        let value = cs.compute_assignment(value_gen).map(|val| *val.borrow());
         let values = match value {
             Ok(mut val) => {
                 let mut v = Vec::with_capacity(8);
@@ -272,7 +272,7 @@ impl<ConstraintF: Field> AllocGadget<u8, ConstraintF> for UInt8 {
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<u8>,
     {
-        let value = value_gen().map(|val| *val.borrow());
+        let value = cs.compute_assignment(value_gen).map(|val| *val.borrow());
         let values = match value {
             Ok(mut val) => {
                 let mut v = Vec::with_capacity(8);
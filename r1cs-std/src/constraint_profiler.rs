@@ -0,0 +1,341 @@
+use crate::{String, Vec};
+use algebra::Field;
+use core::fmt::Write;
+use r1cs_core::{ConstraintSystem, Index, LinearCombination, SynthesisError, Variable};
+
+/// The constraints and variables created directly in a namespace, and the
+/// namespaces nested in it.
+///
+/// Namespaces with the same name and parent are merged, so that, for example,
+/// the constraints of a gadget invoked in a loop under a fixed name are
+/// reported together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NamespaceProfile {
+    /// The name of the namespace, without the names of its parents.
+    pub name: String,
+    /// The number of constraints enforced directly in the namespace.
+    pub num_constraints: usize,
+    /// The number of private variables allocated directly in the namespace.
+    pub num_aux: usize,
+    /// The number of public inputs allocated directly in the namespace.
+    pub num_inputs: usize,
+    /// The namespaces nested in this one, in the order in which they were
+    /// first entered.
+    pub children: Vec<NamespaceProfile>,
+}
+
+impl NamespaceProfile {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            num_constraints: 0,
+            num_aux: 0,
+            num_inputs: 0,
+            children: Vec::new(),
+        }
+    }
+
+    /// The number of constraints in this namespace and the namespaces nested
+    /// in it.
+    pub fn total_constraints(&self) -> usize {
+        self.num_constraints
+            + self
+                .children
+                .iter()
+                .map(Self::total_constraints)
+                .sum::<usize>()
+    }
+
+    /// The number of private variables in this namespace and the namespaces
+    /// nested in it.
+    pub fn total_aux(&self) -> usize {
+        self.num_aux + self.children.iter().map(Self::total_aux).sum::<usize>()
+    }
+
+    /// The number of public inputs in this namespace and the namespaces nested
+    /// in it.
+    pub fn total_inputs(&self) -> usize {
+        self.num_inputs + self.children.iter().map(Self::total_inputs).sum::<usize>()
+    }
+
+    /// Returns the namespace at `path` relative to `self`, if any.
+    pub fn get(&self, path: &[&str]) -> Option<&Self> {
+        match path.split_first() {
+            Some((name, rest)) => self
+                .children
+                .iter()
+                .find(|child| child.name == *name)
+                .and_then(|child| child.get(rest)),
+            None => Some(self),
+        }
+    }
+
+    /// Renders the tree of namespaces, one per line, with the total number of
+    /// constraints, private variables and public inputs of each. Nested
+    /// namespaces are sorted by decreasing number of constraints.
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        writeln!(
+            report,
+            "{:>12} {:>12} {:>12}  namespace",
+            "constraints", "aux", "inputs"
+        )
+        .unwrap();
+        self.write_report(&mut report, 0);
+        report
+    }
+
+    fn write_report(&self, report: &mut String, depth: usize) {
+        writeln!(
+            report,
+            "{:>12} {:>12} {:>12}  {:indent$}{}",
+            self.total_constraints(),
+            self.total_aux(),
+            self.total_inputs(),
+            "",
+            self.name,
+            indent = 2 * depth
+        )
+        .unwrap();
+        let mut children: Vec<_> = self.children.iter().collect();
+        children.sort_by(|a, b| {
+            b.total_constraints()
+                .cmp(&a.total_constraints())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        for child in children {
+            child.write_report(report, depth + 1);
+        }
+    }
+
+    /// Renders the number of constraints created directly in each namespace as
+    /// folded stacks, as consumed by `flamegraph.pl` and `inferno`: one line
+    /// per namespace with its path, separated by `;`, followed by the count.
+    /// Namespaces without constraints of their own are omitted.
+    pub fn folded_stacks(&self) -> String {
+        let mut folded = String::new();
+        self.write_folded_stacks(&mut folded, &mut Vec::new());
+        folded
+    }
+
+    fn write_folded_stacks<'a>(&'a self, folded: &mut String, path: &mut Vec<&'a str>) {
+        path.push(&self.name);
+        if self.num_constraints > 0 {
+            for (i, name) in path.iter().enumerate() {
+                if i > 0 {
+                    folded.push(';');
+                }
+                // `;` separates frames and each stack takes up one line.
+                for c in name.chars() {
+                    folded.push(match c {
+                        ';' => ',',
+                        '\n' => ' ',
+                        c => c,
+                    });
+                }
+            }
+            writeln!(folded, " {}", self.num_constraints).unwrap();
+        }
+        for child in &self.children {
+            child.write_folded_stacks(folded, path);
+        }
+        path.pop();
+    }
+}
+
+/// Constraint system that records the number of constraints, private variables
+/// and public inputs created in each namespace, without computing any
+/// assignments.
+pub struct ConstraintProfiler {
+    root: NamespaceProfile,
+    num_constraints: usize,
+    num_aux: usize,
+    num_inputs: usize,
+    /// The indices of the children leading from `root` to the current
+    /// namespace.
+    current_namespace: Vec<usize>,
+}
+
+impl Default for ConstraintProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConstraintProfiler {
+    /// Creates a profiler with an empty root namespace.
+    pub fn new() -> Self {
+        Self {
+            root: NamespaceProfile::new("root".into()),
+            num_constraints: 0,
+            num_aux: 0,
+            num_inputs: 0,
+            current_namespace: Vec::new(),
+        }
+    }
+
+    /// The profile of all namespaces. The implicit "one" input is not counted.
+    pub fn profile(&self) -> &NamespaceProfile {
+        &self.root
+    }
+
+    /// Consumes the profiler and returns the profile of all namespaces.
+    pub fn into_profile(self) -> NamespaceProfile {
+        self.root
+    }
+
+    fn current(&mut self) -> &mut NamespaceProfile {
+        let mut namespace = &mut self.root;
+        for &i in &self.current_namespace {
+            namespace = &mut namespace.children[i];
+        }
+        namespace
+    }
+}
+
+impl<ConstraintF: Field> ConstraintSystem<ConstraintF> for ConstraintProfiler {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<ConstraintF, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let var = Variable::new_unchecked(Index::Aux(self.num_aux));
+        self.num_aux += 1;
+        self.current().num_aux += 1;
+        Ok(var)
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<ConstraintF, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        // Input 0 is the implicit "one" input.
+        self.num_inputs += 1;
+        let var = Variable::new_unchecked(Index::Input(self.num_inputs));
+        self.current().num_inputs += 1;
+        Ok(var)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, _: LA, _: LB, _: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<ConstraintF>) -> LinearCombination<ConstraintF>,
+        LB: FnOnce(LinearCombination<ConstraintF>) -> LinearCombination<ConstraintF>,
+        LC: FnOnce(LinearCombination<ConstraintF>) -> LinearCombination<ConstraintF>,
+    {
+        self.num_constraints += 1;
+        self.current().num_constraints += 1;
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name = name_fn().into();
+        let current = self.current();
+        let i = match current.children.iter().position(|c| c.name == name) {
+            Some(i) => i,
+            None => {
+                current.children.push(NamespaceProfile::new(name));
+                current.children.len() - 1
+            },
+        };
+        self.current_namespace.push(i);
+    }
+
+    fn pop_namespace(&mut self) {
+        assert!(self.current_namespace.pop().is_some());
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_constraints
+    }
+
+    /// The profiler discards assignments, so it reports setup mode, in which
+    /// gadgets skip computing their witnesses. Circuits whose witness
+    /// computation would fail can thus be profiled too.
+    fn is_in_setup_mode(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::AllocGadget, bits::boolean::Boolean, fields::fp::FpGadget, fields::FieldGadget,
+        test_constraint_counter::ConstraintCounter,
+    };
+    use algebra::bls12_381::Fr;
+    use r1cs_core::{ConstraintSynthesizer, SynthesisError};
+
+    struct TestCircuit;
+
+    impl ConstraintSynthesizer<Fr> for TestCircuit {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let x = FpGadget::alloc_input(cs.ns(|| "x"), || Ok(Fr::from(3u64)))?;
+            let mut acc = x.clone();
+            for i in 0..3 {
+                let mut cs = cs.ns(|| "square");
+                acc = acc.square(cs.ns(|| "acc"))?;
+                Boolean::alloc(cs.ns(|| i.to_string()), || Ok(true))?;
+            }
+            let _ = acc.mul(cs.ns(|| "mul;by x"), &x)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn profiles_namespaces() {
+        let mut counter = ConstraintCounter::new();
+        TestCircuit.generate_constraints(&mut counter).unwrap();
+        let mut profiler = ConstraintProfiler::new();
+        TestCircuit.generate_constraints(&mut profiler).unwrap();
+        let profile = profiler.into_profile();
+
+        assert_eq!(profile.total_constraints(), counter.num_constraints);
+        assert_eq!(profile.total_aux(), counter.num_aux);
+        assert_eq!(profile.total_inputs(), counter.num_inputs);
+        assert_eq!(profile.total_inputs(), 1);
+
+        // The three `square` namespaces are merged.
+        let square = profile.get(&["square"]).unwrap();
+        assert_eq!(square.children.len(), 4);
+        assert_eq!(square.get(&["acc"]).unwrap().total_constraints(), 3);
+        assert_eq!(square.get(&["acc"]).unwrap().total_aux(), 3);
+        assert_eq!(square.get(&["1"]).unwrap().total_constraints(), 1);
+        assert_eq!(square.total_constraints(), 6);
+        assert_eq!(profile.get(&["mul;by x"]).unwrap().total_constraints(), 1);
+
+        let report = profile.report();
+        let lines: Vec<_> = report.lines().collect();
+        // Gadgets open namespaces of their own, such as `mul` in `square`.
+        assert_eq!(lines.len(), 11);
+        assert!(lines[1].ends_with("  root"));
+        assert!(lines[2].ends_with("    square"));
+        assert!(lines[3].ends_with("      acc"));
+
+        let folded = profile.folded_stacks();
+        assert!(folded.contains("root;square;acc 3\n"));
+        assert!(folded.contains("root;mul,by x 1\n"));
+        let total: usize = folded
+            .lines()
+            .map(|line| line.rsplit(' ').next().unwrap().parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(total, profile.total_constraints());
+    }
+}
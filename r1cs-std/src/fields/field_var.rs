@@ -0,0 +1,379 @@
+use algebra::Field;
+use core::{
+    borrow::Borrow,
+    marker::PhantomData,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+use r1cs_core::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+
+use crate::prelude::*;
+
+/// A field gadget together with a handle to the constraint system it lives
+/// in, so that arithmetic can be written with operators.
+///
+/// Operators panic if they fail to synthesize their constraints, which field
+/// gadgets only do if an assignment is missing while the constraint system
+/// is computing assignments. Use the methods of [`FieldGadget`] on
+/// [`gadget`](Self::gadget) to handle such errors instead.
+///
+/// Panics if the operands of an operator belong to different constraint
+/// systems.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct FieldVar<F: Field, ConstraintF: Field, FG: FieldGadget<F, ConstraintF>> {
+    cs: ConstraintSystemRef<ConstraintF>,
+    gadget: FG,
+    #[derivative(Debug = "ignore")]
+    _field: PhantomData<F>,
+}
+
+impl<F: Field, ConstraintF: Field, FG: FieldGadget<F, ConstraintF>> FieldVar<F, ConstraintF, FG> {
+    /// Wraps `gadget`, which must have been allocated in `cs`.
+    pub fn new(cs: ConstraintSystemRef<ConstraintF>, gadget: FG) -> Self {
+        Self {
+            cs,
+            gadget,
+            _field: PhantomData,
+        }
+    }
+
+    /// Allocates a private variable in `cs`.
+    pub fn new_witness<FN, T>(
+        cs: ConstraintSystemRef<ConstraintF>,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<F>,
+    {
+        let gadget = FG::alloc(cs.clone().ns(|| "alloc"), f)?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    /// Allocates a public input in `cs`.
+    pub fn new_input<FN, T>(
+        cs: ConstraintSystemRef<ConstraintF>,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<F>,
+    {
+        let gadget = FG::alloc_input(cs.clone().ns(|| "alloc input"), f)?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    /// Creates a constant in `cs`.
+    pub fn new_constant(
+        cs: ConstraintSystemRef<ConstraintF>,
+        value: F,
+    ) -> Result<Self, SynthesisError> {
+        let gadget = FG::alloc_constant(cs.clone().ns(|| "alloc constant"), value)?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    /// Creates the constant zero in `cs`.
+    pub fn zero(cs: ConstraintSystemRef<ConstraintF>) -> Result<Self, SynthesisError> {
+        let gadget = FG::zero(cs.clone().ns(|| "zero"))?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    /// Creates the constant one in `cs`.
+    pub fn one(cs: ConstraintSystemRef<ConstraintF>) -> Result<Self, SynthesisError> {
+        let gadget = FG::one(cs.clone().ns(|| "one"))?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    /// Returns the constraint system that `self` lives in.
+    pub fn cs(&self) -> &ConstraintSystemRef<ConstraintF> {
+        &self.cs
+    }
+
+    /// Returns the underlying field gadget.
+    pub fn gadget(&self) -> &FG {
+        &self.gadget
+    }
+
+    /// Unwraps the underlying field gadget.
+    pub fn into_gadget(self) -> FG {
+        self.gadget
+    }
+
+    /// Returns the value of `self`, or `None` if its assignment is missing.
+    pub fn value(&self) -> Option<F> {
+        self.gadget.get_value()
+    }
+
+    /// Returns `self + self`.
+    pub fn double(&self) -> Self {
+        self.unary_op("double", |g, cs| g.double(cs))
+    }
+
+    /// Returns `self * self`.
+    pub fn square(&self) -> Self {
+        self.unary_op("square", |g, cs| g.square(cs))
+    }
+
+    /// Returns the inverse of `self`, and enforces that `self` is not zero.
+    pub fn inverse(&self) -> Result<Self, SynthesisError> {
+        let gadget = self.gadget.inverse(self.cs.clone().ns(|| "inverse"))?;
+        Ok(Self::new(self.cs.clone(), gadget))
+    }
+
+    /// Enforces that `self` and `other` are equal.
+    pub fn enforce_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+        self.assert_same_cs(other);
+        self.gadget
+            .enforce_equal(self.cs.clone().ns(|| "enforce equal"), &other.gadget)
+    }
+
+    fn assert_same_cs(&self, other: &Self) {
+        assert!(
+            self.cs.is_same(&other.cs),
+            "operands belong to different constraint systems"
+        );
+    }
+
+    fn unary_op(
+        &self,
+        name: &'static str,
+        op: impl FnOnce(
+            &FG,
+            r1cs_core::Namespace<'_, ConstraintF, ConstraintSystemRef<ConstraintF>>,
+        ) -> Result<FG, SynthesisError>,
+    ) -> Self {
+        let mut cs = self.cs.clone();
+        let gadget = op(&self.gadget, cs.ns(|| name))
+            .unwrap_or_else(|e| panic!("could not synthesize {}: {}", name, e));
+        Self::new(self.cs.clone(), gadget)
+    }
+}
+
+macro_rules! impl_field_var_op {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $op:ident, $constant_op:ident) => {
+        impl<'a, F, ConstraintF, FG> $trait<&'a FieldVar<F, ConstraintF, FG>>
+            for &FieldVar<F, ConstraintF, FG>
+        where
+            F: Field,
+            ConstraintF: Field,
+            FG: FieldGadget<F, ConstraintF>,
+        {
+            type Output = FieldVar<F, ConstraintF, FG>;
+
+            fn $fn(self, other: &'a FieldVar<F, ConstraintF, FG>) -> Self::Output {
+                self.assert_same_cs(other);
+                self.unary_op(stringify!($op), |g, cs| g.$op(cs, &other.gadget))
+            }
+        }
+
+        impl<F, ConstraintF, FG> $trait<FieldVar<F, ConstraintF, FG>>
+            for &FieldVar<F, ConstraintF, FG>
+        where
+            F: Field,
+            ConstraintF: Field,
+            FG: FieldGadget<F, ConstraintF>,
+        {
+            type Output = FieldVar<F, ConstraintF, FG>;
+
+            fn $fn(self, other: FieldVar<F, ConstraintF, FG>) -> Self::Output {
+                self.$fn(&other)
+            }
+        }
+
+        impl<'a, F, ConstraintF, FG> $trait<&'a FieldVar<F, ConstraintF, FG>>
+            for FieldVar<F, ConstraintF, FG>
+        where
+            F: Field,
+            ConstraintF: Field,
+            FG: FieldGadget<F, ConstraintF>,
+        {
+            type Output = FieldVar<F, ConstraintF, FG>;
+
+            fn $fn(self, other: &'a FieldVar<F, ConstraintF, FG>) -> Self::Output {
+                (&self).$fn(other)
+            }
+        }
+
+        impl<F, ConstraintF, FG> $trait<FieldVar<F, ConstraintF, FG>>
+            for FieldVar<F, ConstraintF, FG>
+        where
+            F: Field,
+            ConstraintF: Field,
+            FG: FieldGadget<F, ConstraintF>,
+        {
+            type Output = FieldVar<F, ConstraintF, FG>;
+
+            fn $fn(self, other: FieldVar<F, ConstraintF, FG>) -> Self::Output {
+                (&self).$fn(&other)
+            }
+        }
+
+        impl<F, ConstraintF, FG> $trait<F> for &FieldVar<F, ConstraintF, FG>
+        where
+            F: Field,
+            ConstraintF: Field,
+            FG: FieldGadget<F, ConstraintF>,
+        {
+            type Output = FieldVar<F, ConstraintF, FG>;
+
+            fn $fn(self, other: F) -> Self::Output {
+                self.unary_op(stringify!($constant_op), |g, cs| g.$constant_op(cs, &other))
+            }
+        }
+
+        impl<F, ConstraintF, FG> $trait<F> for FieldVar<F, ConstraintF, FG>
+        where
+            F: Field,
+            ConstraintF: Field,
+            FG: FieldGadget<F, ConstraintF>,
+        {
+            type Output = FieldVar<F, ConstraintF, FG>;
+
+            fn $fn(self, other: F) -> Self::Output {
+                (&self).$fn(other)
+            }
+        }
+
+        impl<'a, F, ConstraintF, FG> $assign_trait<&'a FieldVar<F, ConstraintF, FG>>
+            for FieldVar<F, ConstraintF, FG>
+        where
+            F: Field,
+            ConstraintF: Field,
+            FG: FieldGadget<F, ConstraintF>,
+        {
+            fn $assign_fn(&mut self, other: &'a FieldVar<F, ConstraintF, FG>) {
+                *self = (&*self).$fn(other);
+            }
+        }
+
+        impl<F, ConstraintF, FG> $assign_trait<FieldVar<F, ConstraintF, FG>>
+            for FieldVar<F, ConstraintF, FG>
+        where
+            F: Field,
+            ConstraintF: Field,
+            FG: FieldGadget<F, ConstraintF>,
+        {
+            fn $assign_fn(&mut self, other: FieldVar<F, ConstraintF, FG>) {
+                *self = (&*self).$fn(&other);
+            }
+        }
+
+        impl<F, ConstraintF, FG> $assign_trait<F> for FieldVar<F, ConstraintF, FG>
+        where
+            F: Field,
+            ConstraintF: Field,
+            FG: FieldGadget<F, ConstraintF>,
+        {
+            fn $assign_fn(&mut self, other: F) {
+                *self = (&*self).$fn(other);
+            }
+        }
+    };
+}
+
+impl_field_var_op!(Add, add, AddAssign, add_assign, add, add_constant);
+impl_field_var_op!(Sub, sub, SubAssign, sub_assign, sub, sub_constant);
+impl_field_var_op!(Mul, mul, MulAssign, mul_assign, mul, mul_by_constant);
+
+impl<F: Field, ConstraintF: Field, FG: FieldGadget<F, ConstraintF>> Neg
+    for &FieldVar<F, ConstraintF, FG>
+{
+    type Output = FieldVar<F, ConstraintF, FG>;
+
+    fn neg(self) -> Self::Output {
+        self.unary_op("negate", |g, cs| g.negate(cs))
+    }
+}
+
+impl<F: Field, ConstraintF: Field, FG: FieldGadget<F, ConstraintF>> Neg
+    for FieldVar<F, ConstraintF, FG>
+{
+    type Output = FieldVar<F, ConstraintF, FG>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fields::fp::FpGadget, test_constraint_system::TestConstraintSystem, Assignment};
+    use algebra::{bls12_381::Fr, test_rng, One, UniformRand};
+    use r1cs_core::{ConstraintSynthesizer, RefCircuit, RefConstraintSynthesizer};
+
+    type FrVar = FieldVar<Fr, Fr, FpGadget<Fr>>;
+
+    // Proves knowledge of `x` such that `(x + 1)^2 * x - 5 = y` for a public `y`.
+    struct Cubic(Option<Fr>);
+
+    impl RefConstraintSynthesizer<Fr> for Cubic {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x = FrVar::new_witness(cs.clone(), || self.0.get())?;
+            let y = FrVar::new_input(cs.clone(), || {
+                self.0
+                    .map(|x| (x + &Fr::one()).square() * &x - &Fr::from(5u64))
+                    .get()
+            })?;
+            let mut z = (&x + Fr::one()).square();
+            z *= &x;
+            (z - Fr::from(5u64)).enforce_equal(&y)
+        }
+    }
+
+    #[test]
+    fn operators_match_native_arithmetic() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystemRef::new();
+        let (a, b, c) = (Fr::rand(&mut rng), Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let a_var = FrVar::new_witness(cs.clone(), || Ok(a)).unwrap();
+        let b_var = FrVar::new_input(cs.clone(), || Ok(b)).unwrap();
+        let c_var = FrVar::new_constant(cs.clone(), c).unwrap();
+
+        assert_eq!((&a_var + &b_var).value(), Some(a + &b));
+        assert_eq!((&a_var - &b_var).value(), Some(a - &b));
+        assert_eq!((&a_var * &b_var).value(), Some(a * &b));
+        assert_eq!((&a_var * c).value(), Some(a * &c));
+        assert_eq!((-&a_var).value(), Some(-a));
+        assert_eq!(
+            (a_var.clone() * c_var + b_var.square()).value(),
+            Some(a * &c + &b.square())
+        );
+        let mut d_var = a_var.clone();
+        d_var -= &b_var;
+        d_var += b;
+        assert_eq!(d_var.value(), Some(a));
+        assert_eq!(a_var.inverse().unwrap().value(), a.inverse());
+        d_var.enforce_equal(&a_var).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "different constraint systems")]
+    fn operands_from_different_systems_panic() {
+        let a = FrVar::one(ConstraintSystemRef::new()).unwrap();
+        let b = FrVar::one(ConstraintSystemRef::new()).unwrap();
+        let _ = a + b;
+    }
+
+    #[test]
+    fn ref_circuits_work_with_existing_systems() {
+        let x = Fr::from(3u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        RefCircuit(Cubic(Some(x)))
+            .generate_constraints(&mut cs)
+            .unwrap();
+        assert!(cs.is_satisfied());
+
+        let cs = ConstraintSystemRef::new();
+        Cubic(Some(x)).generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        // Without a witness, all assignments are missing but the constraints
+        // are the same.
+        let setup = ConstraintSystemRef::new();
+        Cubic(None).generate_constraints(setup.clone()).unwrap();
+        assert_eq!(setup.num_constraints(), cs.num_constraints());
+        assert!(setup.is_satisfied().is_err());
+    }
+}
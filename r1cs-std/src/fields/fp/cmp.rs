@@ -5,7 +5,7 @@ use crate::{
 };
 use algebra::PrimeField;
 use core::cmp::Ordering;
-use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_core::{ConstraintSystem, SynthesisError, SynthesisResultExt};
 
 impl<F: PrimeField> FpGadget<F> {
     /// This function enforces the ordering between `self` and `b`. The
@@ -117,7 +117,9 @@ impl<F: PrimeField> FpGadget<F> {
                 right = a;
             }
             Ordering::Equal => {
-                return Err(SynthesisError::Unsatisfiable);
+                return Err(SynthesisError::Unsatisfiable)
+                    .context("`Ordering::Equal` is not a strict ordering")
+                    .at_namespace_of(&cs);
             }
         };
         let right_for_check = if should_also_check_equality {
@@ -875,7 +875,7 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp12<P>>,
     {
-        let (c0, c1) = match value_gen() {
+        let (c0, c1) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1))
@@ -900,7 +900,7 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp12<P>>,
     {
-        let (c0, c1) = match value_gen() {
+        let (c0, c1) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1))
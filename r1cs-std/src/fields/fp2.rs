@@ -650,7 +650,7 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> AllocGadget<Fp
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp2<P>>,
     {
-        let (c0, c1) = match value_gen() {
+        let (c0, c1) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1))
@@ -675,7 +675,7 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> AllocGadget<Fp
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp2<P>>,
     {
-        let (c0, c1) = match value_gen() {
+        let (c0, c1) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1))
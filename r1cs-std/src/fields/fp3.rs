@@ -908,7 +908,7 @@ impl<P: Fp3Parameters<Fp = ConstraintF>, ConstraintF: PrimeField + SquareRootFie
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp3<P>>,
     {
-        let (c0, c1, c2) = match value_gen() {
+        let (c0, c1, c2) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1), Ok(fe.c2))
@@ -935,7 +935,7 @@ impl<P: Fp3Parameters<Fp = ConstraintF>, ConstraintF: PrimeField + SquareRootFie
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp3<P>>,
     {
-        let (c0, c1, c2) = match value_gen() {
+        let (c0, c1, c2) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1), Ok(fe.c2))
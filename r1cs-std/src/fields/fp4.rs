@@ -714,7 +714,7 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp4<P>>,
     {
-        let (c0, c1) = match value_gen() {
+        let (c0, c1) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1))
@@ -739,7 +739,7 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp4<P>>,
     {
-        let (c0, c1) = match value_gen() {
+        let (c0, c1) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1))
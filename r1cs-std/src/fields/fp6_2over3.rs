@@ -706,7 +706,7 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp6<P>>,
     {
-        let (c0, c1) = match value_gen() {
+        let (c0, c1) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1))
@@ -731,7 +731,7 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp6<P>>,
     {
-        let (c0, c1) = match value_gen() {
+        let (c0, c1) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1))
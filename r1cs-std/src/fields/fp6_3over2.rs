@@ -1007,7 +1007,7 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp6<P>>,
     {
-        let (c0, c1, c2) = match value_gen() {
+        let (c0, c1, c2) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1), Ok(fe.c2))
@@ -1034,7 +1034,7 @@ where
         F: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<Fp6<P>>,
     {
-        let (c0, c1, c2) = match value_gen() {
+        let (c0, c1, c2) = match cs.compute_assignment(value_gen) {
             Ok(fe) => {
                 let fe = *fe.borrow();
                 (Ok(fe.c0), Ok(fe.c1), Ok(fe.c2))
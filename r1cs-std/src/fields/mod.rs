@@ -4,6 +4,7 @@ use r1cs_core::{ConstraintSystem, SynthesisError};
 
 use crate::{prelude::*, Assignment};
 
+pub mod field_var;
 pub mod fp;
 pub mod fp12;
 pub mod fp2;
@@ -12,6 +13,7 @@ pub mod fp4;
 pub mod fp6_2over3;
 pub mod fp6_3over2;
 
+pub use self::field_var::FieldVar;
 use crate::fields::fp::FpGadget;
 pub trait ToConstraintFieldGadget<ConstraintF: PrimeField> {
     fn to_constraint_field<CS: ConstraintSystem<ConstraintF>>(
@@ -76,7 +76,7 @@ impl<P: SWModelParameters, ConstraintF: Field, F: FieldGadget<P::BaseField, Cons
     where
         F: FnOnce() -> Result<SWProjective<P>, SynthesisError>,
     {
-        let (x, y, infinity) = match value_gen() {
+        let (x, y, infinity) = match cs.compute_assignment(value_gen) {
             Ok(ge) => {
                 let ge = ge.into_affine();
                 (Ok(ge.x), Ok(ge.y), Ok(ge.infinity))
@@ -491,7 +491,7 @@ where
         FN: FnOnce() -> Result<T, SynthesisError>,
         T: Borrow<SWProjective<P>>,
     {
-        let (x, y, infinity) = match value_gen() {
+        let (x, y, infinity) = match cs.compute_assignment(value_gen) {
             Ok(ge) => {
                 let ge = ge.borrow().into_affine();
                 (Ok(ge.x), Ok(ge.y), Ok(ge.infinity))
@@ -615,7 +615,7 @@ where
     {
         // When allocating the input we assume that the verifier has performed
         // any on curve checks already.
-        let (x, y, infinity) = match value_gen() {
+        let (x, y, infinity) = match cs.compute_assignment(value_gen) {
             Ok(ge) => {
                 let ge = ge.borrow().into_affine();
                 (Ok(ge.x), Ok(ge.y), Ok(ge.infinity))
@@ -6,7 +6,7 @@ use algebra::{
     BitIterator, Field, One, PrimeField, Zero,
 };
 
-use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_core::{ConstraintSystem, SynthesisError, SynthesisResultExt};
 
 use crate::{prelude::*, Vec};
 
@@ -221,7 +221,7 @@ impl<P: TEModelParameters, ConstraintF: Field, F: FieldGadget<P::BaseField, Cons
     where
         F: FnOnce() -> Result<TEAffine<P>, SynthesisError>,
     {
-        let (x, y) = match value_gen() {
+        let (x, y) = match cs.compute_assignment(value_gen) {
             Ok(fe) => (Ok(fe.x), Ok(fe.y)),
             _ => (
                 Err(SynthesisError::AssignmentMissing),
@@ -540,7 +540,7 @@ mod affine_impl {
             FN: FnOnce() -> Result<T, SynthesisError>,
             T: Borrow<TEAffine<P>>,
         {
-            let (x, y) = match value_gen() {
+            let (x, y) = match cs.compute_assignment(value_gen) {
                 Ok(ge) => {
                     let ge = *ge.borrow();
                     (Ok(ge.x), Ok(ge.y))
@@ -657,7 +657,7 @@ mod affine_impl {
             FN: FnOnce() -> Result<T, SynthesisError>,
             T: Borrow<TEAffine<P>>,
         {
-            let (x, y) = match value_gen() {
+            let (x, y) = match cs.compute_assignment(value_gen) {
                 Ok(ge) => {
                     let ge = *ge.borrow();
                     (Ok(ge.x), Ok(ge.y))
@@ -1035,7 +1035,15 @@ mod projective_impl {
                         &mut cs.ns(|| format!("Convert Scalar {}, {} to bits", segment_i, i)),
                     )?;
                     if bits.len() != CHUNK_SIZE {
-                        return Err(SynthesisError::Unsatisfiable);
+                        return Err(SynthesisError::Unsatisfiable)
+                            .with_context(|| {
+                                format!(
+                                    "scalar chunk has {} bits instead of {}",
+                                    bits.len(),
+                                    CHUNK_SIZE
+                                )
+                            })
+                            .at_namespace_of(&cs);
                     }
 
                     let coords = coords
@@ -1154,7 +1162,7 @@ mod projective_impl {
             FN: FnOnce() -> Result<T, SynthesisError>,
             T: Borrow<TEProjective<P>>,
         {
-            let (x, y) = match value_gen() {
+            let (x, y) = match cs.compute_assignment(value_gen) {
                 Ok(ge) => {
                     let ge = ge.borrow().into_affine();
                     (Ok(ge.x), Ok(ge.y))
@@ -1276,7 +1284,7 @@ mod projective_impl {
             FN: FnOnce() -> Result<T, SynthesisError>,
             T: Borrow<TEProjective<P>>,
         {
-            let (x, y) = match value_gen() {
+            let (x, y) = match cs.compute_assignment(value_gen) {
                 Ok(ge) => {
                     let ge = ge.borrow().into_affine();
                     (Ok(ge.x), Ok(ge.y))
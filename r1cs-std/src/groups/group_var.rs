@@ -0,0 +1,338 @@
+use algebra::{Field, Group};
+use core::{
+    borrow::Borrow,
+    marker::PhantomData,
+    ops::{Add, AddAssign, Neg, Sub, SubAssign},
+};
+use r1cs_core::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+
+use crate::{bits::boolean_var::BooleanVar, prelude::*};
+
+/// A group gadget together with a handle to the constraint system it lives
+/// in, so that group operations can be written with operators.
+///
+/// Operators panic if they fail to synthesize their constraints, for example
+/// if an assignment is missing while the constraint system is computing
+/// assignments. Use the methods of [`GroupGadget`] on
+/// [`gadget`](Self::gadget) to handle such errors instead.
+///
+/// Panics if the operands of an operator belong to different constraint
+/// systems.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct GroupVar<G: Group, ConstraintF: Field, GG: GroupGadget<G, ConstraintF>> {
+    cs: ConstraintSystemRef<ConstraintF>,
+    gadget: GG,
+    #[derivative(Debug = "ignore")]
+    _group: PhantomData<G>,
+}
+
+impl<G: Group, ConstraintF: Field, GG: GroupGadget<G, ConstraintF>> GroupVar<G, ConstraintF, GG> {
+    pub fn new(cs: ConstraintSystemRef<ConstraintF>, gadget: GG) -> Self {
+        Self {
+            cs,
+            gadget,
+            _group: PhantomData,
+        }
+    }
+
+    /// Allocates a private variable in `cs`.
+    pub fn new_witness<FN, T>(
+        cs: ConstraintSystemRef<ConstraintF>,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<G>,
+    {
+        let gadget = GG::alloc(cs.clone().ns(|| "alloc"), f)?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    /// Allocates a public input in `cs`.
+    pub fn new_input<FN, T>(
+        cs: ConstraintSystemRef<ConstraintF>,
+        f: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<G>,
+    {
+        let gadget = GG::alloc_input(cs.clone().ns(|| "alloc input"), f)?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    /// Creates a constant in `cs`.
+    pub fn new_constant(
+        cs: ConstraintSystemRef<ConstraintF>,
+        value: G,
+    ) -> Result<Self, SynthesisError> {
+        let gadget = GG::alloc_constant(cs.clone().ns(|| "alloc constant"), value)?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    pub fn zero(cs: ConstraintSystemRef<ConstraintF>) -> Result<Self, SynthesisError> {
+        let gadget = GG::zero(cs.clone().ns(|| "zero"))?;
+        Ok(Self::new(cs, gadget))
+    }
+
+    pub fn cs(&self) -> &ConstraintSystemRef<ConstraintF> {
+        &self.cs
+    }
+
+    pub fn gadget(&self) -> &GG {
+        &self.gadget
+    }
+
+    pub fn into_gadget(self) -> GG {
+        self.gadget
+    }
+
+    pub fn value(&self) -> Option<GG::Value> {
+        self.gadget.get_value()
+    }
+
+    pub fn double(&self) -> Self {
+        self.unary_op("double", |g, cs| {
+            let mut result = g.clone();
+            result.double_in_place(cs)?;
+            Ok(result)
+        })
+    }
+
+    /// Computes `result + bits * self`, with `bits` in little-endian order.
+    /// If the addition law is incomplete for the identity element, `result`
+    /// must not be the identity element.
+    pub fn mul_bits(&self, result: &Self, bits: &[BooleanVar<ConstraintF>]) -> Self {
+        self.assert_same_cs(result);
+        for bit in bits {
+            assert!(
+                self.cs.is_same(bit.cs()),
+                "operands belong to different constraint systems"
+            );
+        }
+        self.unary_op("mul bits", |g, cs| {
+            g.mul_bits(cs, &result.gadget, bits.iter().map(BooleanVar::gadget))
+        })
+    }
+
+    pub fn enforce_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+        self.assert_same_cs(other);
+        self.gadget
+            .enforce_equal(self.cs.clone().ns(|| "enforce equal"), &other.gadget)
+    }
+
+    fn assert_same_cs(&self, other: &Self) {
+        assert!(
+            self.cs.is_same(&other.cs),
+            "operands belong to different constraint systems"
+        );
+    }
+
+    fn unary_op(
+        &self,
+        name: &'static str,
+        op: impl FnOnce(
+            &GG,
+            r1cs_core::Namespace<'_, ConstraintF, ConstraintSystemRef<ConstraintF>>,
+        ) -> Result<GG, SynthesisError>,
+    ) -> Self {
+        let mut cs = self.cs.clone();
+        let gadget = op(&self.gadget, cs.ns(|| name))
+            .unwrap_or_else(|e| panic!("could not synthesize {}: {}", name, e));
+        Self::new(self.cs.clone(), gadget)
+    }
+}
+
+macro_rules! impl_group_var_op {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $op:ident, $constant_op:ident) => {
+        impl<'a, G, ConstraintF, GG> $trait<&'a GroupVar<G, ConstraintF, GG>>
+            for &GroupVar<G, ConstraintF, GG>
+        where
+            G: Group,
+            ConstraintF: Field,
+            GG: GroupGadget<G, ConstraintF>,
+        {
+            type Output = GroupVar<G, ConstraintF, GG>;
+
+            fn $fn(self, other: &'a GroupVar<G, ConstraintF, GG>) -> Self::Output {
+                self.assert_same_cs(other);
+                self.unary_op(stringify!($op), |g, cs| g.$op(cs, &other.gadget))
+            }
+        }
+
+        impl<G, ConstraintF, GG> $trait<GroupVar<G, ConstraintF, GG>>
+            for &GroupVar<G, ConstraintF, GG>
+        where
+            G: Group,
+            ConstraintF: Field,
+            GG: GroupGadget<G, ConstraintF>,
+        {
+            type Output = GroupVar<G, ConstraintF, GG>;
+
+            fn $fn(self, other: GroupVar<G, ConstraintF, GG>) -> Self::Output {
+                self.$fn(&other)
+            }
+        }
+
+        impl<'a, G, ConstraintF, GG> $trait<&'a GroupVar<G, ConstraintF, GG>>
+            for GroupVar<G, ConstraintF, GG>
+        where
+            G: Group,
+            ConstraintF: Field,
+            GG: GroupGadget<G, ConstraintF>,
+        {
+            type Output = GroupVar<G, ConstraintF, GG>;
+
+            fn $fn(self, other: &'a GroupVar<G, ConstraintF, GG>) -> Self::Output {
+                (&self).$fn(other)
+            }
+        }
+
+        impl<G, ConstraintF, GG> $trait<GroupVar<G, ConstraintF, GG>>
+            for GroupVar<G, ConstraintF, GG>
+        where
+            G: Group,
+            ConstraintF: Field,
+            GG: GroupGadget<G, ConstraintF>,
+        {
+            type Output = GroupVar<G, ConstraintF, GG>;
+
+            fn $fn(self, other: GroupVar<G, ConstraintF, GG>) -> Self::Output {
+                (&self).$fn(&other)
+            }
+        }
+
+        impl<G, ConstraintF, GG> $trait<G> for &GroupVar<G, ConstraintF, GG>
+        where
+            G: Group,
+            ConstraintF: Field,
+            GG: GroupGadget<G, ConstraintF>,
+        {
+            type Output = GroupVar<G, ConstraintF, GG>;
+
+            fn $fn(self, other: G) -> Self::Output {
+                self.unary_op(stringify!($constant_op), |g, cs| g.$constant_op(cs, &other))
+            }
+        }
+
+        impl<G, ConstraintF, GG> $trait<G> for GroupVar<G, ConstraintF, GG>
+        where
+            G: Group,
+            ConstraintF: Field,
+            GG: GroupGadget<G, ConstraintF>,
+        {
+            type Output = GroupVar<G, ConstraintF, GG>;
+
+            fn $fn(self, other: G) -> Self::Output {
+                (&self).$fn(other)
+            }
+        }
+
+        impl<'a, G, ConstraintF, GG> $assign_trait<&'a GroupVar<G, ConstraintF, GG>>
+            for GroupVar<G, ConstraintF, GG>
+        where
+            G: Group,
+            ConstraintF: Field,
+            GG: GroupGadget<G, ConstraintF>,
+        {
+            fn $assign_fn(&mut self, other: &'a GroupVar<G, ConstraintF, GG>) {
+                *self = (&*self).$fn(other);
+            }
+        }
+
+        impl<G, ConstraintF, GG> $assign_trait<GroupVar<G, ConstraintF, GG>>
+            for GroupVar<G, ConstraintF, GG>
+        where
+            G: Group,
+            ConstraintF: Field,
+            GG: GroupGadget<G, ConstraintF>,
+        {
+            fn $assign_fn(&mut self, other: GroupVar<G, ConstraintF, GG>) {
+                *self = (&*self).$fn(&other);
+            }
+        }
+
+        impl<G, ConstraintF, GG> $assign_trait<G> for GroupVar<G, ConstraintF, GG>
+        where
+            G: Group,
+            ConstraintF: Field,
+            GG: GroupGadget<G, ConstraintF>,
+        {
+            fn $assign_fn(&mut self, other: G) {
+                *self = (&*self).$fn(other);
+            }
+        }
+    };
+}
+
+impl_group_var_op!(Add, add, AddAssign, add_assign, add, add_constant);
+impl_group_var_op!(Sub, sub, SubAssign, sub_assign, sub, sub_constant);
+
+impl<G: Group, ConstraintF: Field, GG: GroupGadget<G, ConstraintF>> Neg
+    for &GroupVar<G, ConstraintF, GG>
+{
+    type Output = GroupVar<G, ConstraintF, GG>;
+
+    fn neg(self) -> Self::Output {
+        self.unary_op("negate", |g, cs| g.negate(cs))
+    }
+}
+
+impl<G: Group, ConstraintF: Field, GG: GroupGadget<G, ConstraintF>> Neg
+    for GroupVar<G, ConstraintF, GG>
+{
+    type Output = GroupVar<G, ConstraintF, GG>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::curves::short_weierstrass::bls12::G1Gadget;
+    use algebra::{
+        bls12_381::{Fq, G1Projective, Parameters},
+        test_rng, UniformRand,
+    };
+
+    type G1Var = GroupVar<G1Projective, Fq, G1Gadget<Parameters>>;
+
+    #[test]
+    fn operators_match_native_arithmetic() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystemRef::<Fq>::new();
+        let (a, b, c) = (
+            G1Projective::rand(&mut rng),
+            G1Projective::rand(&mut rng),
+            G1Projective::rand(&mut rng),
+        );
+        let a_var = G1Var::new_witness(cs.clone(), || Ok(a)).unwrap();
+        let b_var = G1Var::new_input(cs.clone(), || Ok(b)).unwrap();
+        let c_var = G1Var::new_constant(cs.clone(), c).unwrap();
+
+        assert_eq!((&a_var + &b_var).value(), Some(a + &b));
+        assert_eq!((&a_var - &b_var).value(), Some(a - &b));
+        assert_eq!((&a_var + c).value(), Some(a + &c));
+        assert_eq!((-&a_var).value(), Some(-a));
+        assert_eq!(a_var.double().value(), Some(a.double()));
+
+        let mut acc = a_var.clone();
+        acc += &b_var;
+        acc -= c;
+        acc += c_var;
+        assert_eq!(acc.value(), Some(a + &b));
+
+        // 5 * b + a
+        let bits = [true, false, true]
+            .iter()
+            .map(|&bit| BooleanVar::new_witness(cs.clone(), || Ok(bit)).unwrap())
+            .collect::<Vec<_>>();
+        let expected = a + &b.double().double() + &b;
+        assert_eq!(b_var.mul_bits(&a_var, &bits).value(), Some(expected));
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -5,8 +5,10 @@ use r1cs_core::{ConstraintSystem, SynthesisError};
 use core::{borrow::Borrow, fmt::Debug};
 
 pub mod curves;
+pub mod group_var;
 
 pub use self::curves::short_weierstrass::{bls12, mnt4, mnt6};
+pub use self::group_var::GroupVar;
 
 pub trait GroupGadget<G: Group, ConstraintF: Field>:
     Sized
@@ -25,3 +25,61 @@ fn bls12_377_field_gadgets_test() {
     field_test::<_, Fq, Fq12Gadget>();
     frobenius_tests::<Fq12, Fq, Fq12Gadget>(13);
 }
+
+#[test]
+fn bls12_377_field_gadgets_skip_witness_in_setup_mode() {
+    use super::*;
+    use crate::{prelude::*, test_constraint_counter::ConstraintCounter};
+    use algebra::bls12_377::Fq12;
+    use r1cs_core::{ConstraintSystem, SynthesisError};
+
+    fn alloc<CS: ConstraintSystem<Fq>>(mut cs: CS) -> (Fq12Gadget, UInt8) {
+        assert!(cs.is_in_setup_mode());
+        let a = Fq12Gadget::alloc(cs.ns(|| "a"), || -> Result<Fq12, SynthesisError> {
+            panic!("witness computed during setup")
+        })
+        .unwrap();
+        let b = UInt8::alloc(cs.ns(|| "b"), || -> Result<u8, SynthesisError> {
+            panic!("witness computed during setup")
+        })
+        .unwrap();
+        (a, b)
+    }
+
+    let (a, b) = alloc(ConstraintCounter::new());
+    assert!(a.get_value().is_none());
+    assert!(b.value.is_none());
+}
+
+#[test]
+fn bls12_377_field_gadgets_skip_witness_in_snark_setup() {
+    use super::*;
+    use crate::prelude::*;
+    use algebra::{bls12_377::Fq12, cp6_782::CP6_782, test_rng};
+    use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+
+    struct PanickingWitness;
+
+    impl ConstraintSynthesizer<Fq> for PanickingWitness {
+        fn generate_constraints<CS: ConstraintSystem<Fq>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a = Fq12Gadget::alloc(cs.ns(|| "a"), || -> Result<Fq12, SynthesisError> {
+                panic!("witness computed during setup")
+            })?;
+            let b = Fq12Gadget::alloc_input(cs.ns(|| "b"), || -> Result<Fq12, SynthesisError> {
+                panic!("witness computed during setup")
+            })?;
+            a.enforce_equal(cs.ns(|| "a == b"), &b)?;
+            UInt8::alloc(cs.ns(|| "c"), || -> Result<u8, SynthesisError> {
+                panic!("witness computed during setup")
+            })?;
+            Ok(())
+        }
+    }
+
+    let rng = &mut test_rng();
+    groth16::generate_random_parameters::<CP6_782, _, _>(PanickingWitness, rng).unwrap();
+    gm17::generate_random_parameters::<CP6_782, _, _>(PanickingWitness, rng).unwrap();
+}
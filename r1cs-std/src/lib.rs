@@ -33,6 +33,7 @@ use ralloc::{collections::BTreeMap, string::String, vec::Vec};
 #[cfg(feature = "std")]
 use std::{collections::BTreeMap, string::String, vec::Vec};
 
+pub mod constraint_profiler;
 pub mod test_constraint_counter;
 pub mod test_constraint_system;
 
@@ -76,6 +76,13 @@ impl<ConstraintF: Field> ConstraintSystem<ConstraintF> for ConstraintCounter {
     fn num_constraints(&self) -> usize {
         self.num_constraints
     }
+
+    /// The counter discards assignments, so it reports setup mode, in which
+    /// gadgets skip computing their witnesses. Circuits whose witness
+    /// computation would fail can thus be counted too.
+    fn is_in_setup_mode(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
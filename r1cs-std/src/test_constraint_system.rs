@@ -163,8 +163,13 @@ impl<ConstraintF: Field> ConstraintSystem<ConstraintF> for TestConstraintSystem<
         AR: Into<String>,
     {
         let index = self.aux.len();
-        let path = compute_path(&self.current_namespace, annotation().into());
-        self.aux.push((f()?, path.clone()));
+        let name = annotation().into();
+        let value = f().map_err(|e| {
+            e.context(format!("allocating {}", name))
+                .at_namespace_of(self)
+        })?;
+        let path = compute_path(&self.current_namespace, name);
+        self.aux.push((value, path.clone()));
         let var = Variable::new_unchecked(Index::Aux(index));
         self.set_named_obj(path, NamedObject::Var(var));
 
@@ -178,8 +183,13 @@ impl<ConstraintF: Field> ConstraintSystem<ConstraintF> for TestConstraintSystem<
         AR: Into<String>,
     {
         let index = self.inputs.len();
-        let path = compute_path(&self.current_namespace, annotation().into());
-        self.inputs.push((f()?, path.clone()));
+        let name = annotation().into();
+        let value = f().map_err(|e| {
+            e.context(format!("allocating {}", name))
+                .at_namespace_of(self)
+        })?;
+        let path = compute_path(&self.current_namespace, name);
+        self.inputs.push((value, path.clone()));
         let var = Variable::new_unchecked(Index::Input(index));
         self.set_named_obj(path, NamedObject::Var(var));
 
@@ -230,4 +240,8 @@ impl<ConstraintF: Field> ConstraintSystem<ConstraintF> for TestConstraintSystem<
     fn num_constraints(&self) -> usize {
         self.constraints.len()
     }
+
+    fn namespace_path(&self) -> Option<String> {
+        Some(self.current_namespace.join("/"))
+    }
 }
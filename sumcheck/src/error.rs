@@ -0,0 +1,77 @@
+use core::fmt;
+
+/// The error type for the sumcheck protocol.
+#[derive(Debug)]
+pub enum Error {
+    /// A multilinear polynomial does not have the expected number of
+    /// variables.
+    IncorrectNumberOfVariables {
+        /// The expected number of variables.
+        expected: usize,
+        /// The number of variables of the supplied polynomial.
+        found: usize,
+    },
+
+    /// The proof does not contain one message per variable.
+    IncorrectNumberOfRounds {
+        /// The expected number of rounds.
+        expected: usize,
+        /// The number of rounds in the proof.
+        found: usize,
+    },
+
+    /// A prover message does not describe a polynomial of the expected
+    /// degree.
+    IncorrectRoundDegree {
+        /// The round in which the message was sent.
+        round: usize,
+        /// The expected number of evaluations in the message.
+        expected: usize,
+        /// The number of evaluations in the message.
+        found: usize,
+    },
+
+    /// A prover message is inconsistent with the claim from the previous
+    /// round.
+    InconsistentRound(usize),
+
+    /// The final claim does not match the evaluation of the polynomial.
+    InconsistentFinalEvaluation,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IncorrectNumberOfVariables { expected, found } => write!(
+                f,
+                "expected a polynomial in {:?} variables, but found {:?} variables",
+                expected, found
+            ),
+            Error::IncorrectNumberOfRounds { expected, found } => write!(
+                f,
+                "expected {:?} rounds in the proof, but found {:?}",
+                expected, found
+            ),
+            Error::IncorrectRoundDegree {
+                round,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {:?} evaluations in round {:?}, but found {:?}",
+                expected, round, found
+            ),
+            Error::InconsistentRound(round) => write!(
+                f,
+                "the prover message in round {:?} is inconsistent with the previous claim",
+                round
+            ),
+            Error::InconsistentFinalEvaluation => write!(
+                f,
+                "the final claim does not match the evaluation of the polynomial"
+            ),
+        }
+    }
+}
+
+impl algebra_core::Error for Error {}
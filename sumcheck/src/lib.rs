@@ -0,0 +1,37 @@
+//! A crate for the sumcheck protocol of [Lund, Fortnow, Karloff and
+//! Nisan][lfkn92].
+//!
+//! The protocol is made non-interactive via the Fiat--Shamir transform, using
+//! a [`Transcript`] that is driven by a cryptographic hash function.
+//!
+//! [lfkn92]: https://dl.acm.org/doi/10.1145/146585.146605
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_import_braces, unused_qualifications, trivial_casts)]
+#![deny(trivial_numeric_casts, private_in_public, variant_size_differences)]
+#![deny(stable_features, unreachable_pub, non_shorthand_field_patterns)]
+#![deny(unused_attributes, unused_imports, unused_mut, missing_docs)]
+#![deny(renamed_and_removed_lints, unused_allocation)]
+#![deny(unused_comparisons, bare_trait_objects, unused_must_use, const_err)]
+#![forbid(unsafe_code)]
+
+#[macro_use]
+extern crate bench_utils;
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+pub(crate) use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+pub(crate) use std::vec::Vec;
+
+mod error;
+pub use error::*;
+
+mod transcript;
+pub use transcript::Transcript;
+
+/// The sumcheck protocol for sums of products of multilinear polynomials.
+pub mod ml_sumcheck;
@@ -0,0 +1,152 @@
+use crate::{Error, Vec};
+use algebra_core::{serialize::*, Field};
+use ff_fft::DenseMultilinearExtension;
+
+/// `SumOfProducts` is the polynomial `\sum_i c_i \prod_j f_{i, j}(X)`, where
+/// every `f_{i, j}` is a multilinear polynomial in `num_vars` variables.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SumOfProducts<F: Field> {
+    /// The number of variables of every multilinear polynomial.
+    pub num_vars: usize,
+    /// The summands `(c_i, [f_{i, 0}, f_{i, 1}, ...])`.
+    pub products: Vec<(F, Vec<DenseMultilinearExtension<F>>)>,
+}
+
+impl<F: Field> SumOfProducts<F> {
+    /// Returns the zero polynomial in `num_vars` variables.
+    pub fn new(num_vars: usize) -> Self {
+        Self {
+            num_vars,
+            products: Vec::new(),
+        }
+    }
+
+    /// Adds `coefficient * \prod_j factors[j]` to `self`.
+    pub fn add_product(
+        &mut self,
+        coefficient: F,
+        factors: Vec<DenseMultilinearExtension<F>>,
+    ) -> Result<(), Error> {
+        for factor in &factors {
+            if factor.num_vars != self.num_vars {
+                return Err(Error::IncorrectNumberOfVariables {
+                    expected: self.num_vars,
+                    found: factor.num_vars,
+                });
+            }
+        }
+        self.products.push((coefficient, factors));
+        Ok(())
+    }
+
+    /// Returns the maximum degree of `self` in any single variable, which is
+    /// the largest number of factors in a product. This is at least 1, so
+    /// that every round message determines the sum over the round variable.
+    pub fn max_degree(&self) -> usize {
+        self.products
+            .iter()
+            .map(|(_, factors)| factors.len())
+            .max()
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    /// Evaluates `self` at the given `point`, or returns `None` if `point`
+    /// does not have `num_vars` entries.
+    pub fn evaluate(&self, point: &[F]) -> Option<F> {
+        let mut result = F::zero();
+        for (coefficient, factors) in &self.products {
+            let mut product = *coefficient;
+            for factor in factors {
+                product *= &factor.evaluate(point)?;
+            }
+            result += &product;
+        }
+        if point.len() == self.num_vars {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Computes the sum of `self` over the boolean hypercube.
+    pub fn sum_over_hypercube(&self) -> F {
+        let mut result = F::zero();
+        for (coefficient, factors) in &self.products {
+            let mut sum = F::zero();
+            for b in 0..1 << self.num_vars {
+                sum += &factors.iter().map(|factor| factor[b]).product::<F>();
+            }
+            result += &(*coefficient * &sum);
+        }
+        result
+    }
+}
+
+/// `RoundMessage` is the prover message in a single round of the protocol.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RoundMessage<F: Field> {
+    /// The evaluations of the univariate round polynomial at
+    /// `0, 1, ..., max_degree`.
+    pub evaluations: Vec<F>,
+}
+
+impl<F: Field> RoundMessage<F> {
+    /// Evaluates the round polynomial at `point` by Lagrange interpolation
+    /// over `0, 1, ..., max_degree`.
+    pub fn evaluate(&self, point: F) -> F {
+        let points: Vec<F> = (0..self.evaluations.len())
+            .scan(F::zero(), |acc, _| {
+                let current = *acc;
+                *acc += &F::one();
+                Some(current)
+            })
+            .collect();
+        let mut result = F::zero();
+        for (i, (x_i, y_i)) in points.iter().zip(&self.evaluations).enumerate() {
+            let mut numerator = *y_i;
+            let mut denominator = F::one();
+            for (j, x_j) in points.iter().enumerate() {
+                if i != j {
+                    numerator *= &(point - x_j);
+                    denominator *= &(*x_i - x_j);
+                }
+            }
+            result += &(numerator * &denominator.inverse().unwrap());
+        }
+        result
+    }
+}
+
+/// `Proof` is the non-interactive proof output by `MLSumcheck::prove`. It
+/// contains one message per variable.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<F: Field> {
+    /// The prover messages, in the order of the rounds.
+    pub rounds: Vec<RoundMessage<F>>,
+}
+
+/// `SubClaim` is what remains to be checked after the verifier accepts a
+/// proof: that the polynomial evaluates to `expected_evaluation` at `point`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubClaim<F: Field> {
+    /// The point formed by the verifier challenges.
+    pub point: Vec<F>,
+    /// The claimed evaluation of the polynomial at `point`.
+    pub expected_evaluation: F,
+}
+
+impl<F: Field> SubClaim<F> {
+    /// Checks the sub-claim against `polynomial`, which the verifier has
+    /// oracle access to.
+    pub fn check(&self, polynomial: &SumOfProducts<F>) -> Result<(), Error> {
+        match polynomial.evaluate(&self.point) {
+            Some(evaluation) if evaluation == self.expected_evaluation => Ok(()),
+            Some(_) => Err(Error::InconsistentFinalEvaluation),
+            None => Err(Error::IncorrectNumberOfVariables {
+                expected: self.point.len(),
+                found: polynomial.num_vars,
+            }),
+        }
+    }
+}
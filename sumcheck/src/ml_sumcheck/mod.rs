@@ -0,0 +1,361 @@
+//! The sumcheck protocol for polynomials of the form
+//! `\sum_i c_i \prod_j f_{i, j}(X)`, where every `f_{i, j}` is multilinear.
+//!
+//! The prover convinces the verifier that the sum of such a polynomial over
+//! the boolean hypercube `{0, 1}^n` equals a claimed value. In round `k` the
+//! prover sends the univariate polynomial obtained by summing over all
+//! remaining variables but the `k`-th one, and the verifier replies with a
+//! random challenge at which the `k`-th variable is fixed. At the end, the
+//! verifier is left with a `SubClaim` about a single evaluation of the
+//! polynomial.
+
+use crate::{Error, Transcript, Vec};
+use algebra_core::Field;
+use core::marker::PhantomData;
+use digest::Digest;
+use ff_fft::DenseMultilinearExtension;
+
+mod data_structures;
+pub use data_structures::*;
+
+/// A non-interactive sumcheck protocol for sums of products of multilinear
+/// polynomials, made non-interactive via a Fiat--Shamir `Transcript`.
+pub struct MLSumcheck<F: Field> {
+    _field: PhantomData<F>,
+}
+
+impl<F: Field> MLSumcheck<F> {
+    /// Produces a proof that `polynomial` sums to
+    /// `polynomial.sum_over_hypercube()` over the boolean hypercube.
+    ///
+    /// Along with the proof, this returns the `SubClaim` that the verifier
+    /// will be left with, so that the prover can continue with a protocol
+    /// that proves it.
+    pub fn prove<D: Digest>(
+        polynomial: &SumOfProducts<F>,
+        transcript: &mut Transcript<D>,
+    ) -> Result<(Proof<F>, SubClaim<F>), Error> {
+        let num_vars = polynomial.num_vars;
+        let max_degree = polynomial.max_degree();
+        let prove_time = start_timer!(|| format!(
+            "Sumcheck prover for {} variables and degree {}",
+            num_vars, max_degree
+        ));
+        for (_, factors) in &polynomial.products {
+            for factor in factors {
+                if factor.num_vars != num_vars {
+                    return Err(Error::IncorrectNumberOfVariables {
+                        expected: num_vars,
+                        found: factor.num_vars,
+                    });
+                }
+            }
+        }
+
+        let claimed_sum = polynomial.sum_over_hypercube();
+        Self::append_statement(transcript, num_vars, max_degree, claimed_sum);
+
+        let mut products: Vec<(F, Vec<DenseMultilinearExtension<F>>)> = polynomial.products.clone();
+        let mut rounds = Vec::with_capacity(num_vars);
+        let mut point = Vec::with_capacity(num_vars);
+        for round in 0..num_vars {
+            let round_time = start_timer!(|| format!("Round {}", round));
+            let message = Self::round_message(&products, num_vars - round, max_degree);
+            transcript.append_serializable(b"round-message", &message);
+            rounds.push(message);
+
+            let challenge: F = transcript.challenge(b"round-challenge");
+            for (_, factors) in &mut products {
+                for factor in factors.iter_mut() {
+                    *factor = factor.fix_variables(&[challenge]);
+                }
+            }
+            point.push(challenge);
+            end_timer!(round_time);
+        }
+
+        let expected_evaluation = products
+            .iter()
+            .map(|(coefficient, factors)| {
+                factors
+                    .iter()
+                    .fold(*coefficient, |acc, factor| acc * &factor.evaluations[0])
+            })
+            .sum();
+
+        end_timer!(prove_time);
+        Ok((
+            Proof { rounds },
+            SubClaim {
+                point,
+                expected_evaluation,
+            },
+        ))
+    }
+
+    /// Verifies that `proof` shows that a polynomial in `num_vars` variables
+    /// of degree at most `max_degree` in each variable sums to `claimed_sum`
+    /// over the boolean hypercube.
+    ///
+    /// On success, the returned `SubClaim` must still be checked against the
+    /// polynomial, for example via `SubClaim::check` or a polynomial
+    /// commitment.
+    pub fn verify<D: Digest>(
+        num_vars: usize,
+        max_degree: usize,
+        claimed_sum: F,
+        proof: &Proof<F>,
+        transcript: &mut Transcript<D>,
+    ) -> Result<SubClaim<F>, Error> {
+        let verify_time = start_timer!(|| format!(
+            "Sumcheck verifier for {} variables and degree {}",
+            num_vars, max_degree
+        ));
+        if proof.rounds.len() != num_vars {
+            return Err(Error::IncorrectNumberOfRounds {
+                expected: num_vars,
+                found: proof.rounds.len(),
+            });
+        }
+        let max_degree = max_degree.max(1);
+        Self::append_statement(transcript, num_vars, max_degree, claimed_sum);
+
+        let mut expected = claimed_sum;
+        let mut point = Vec::with_capacity(num_vars);
+        for (round, message) in proof.rounds.iter().enumerate() {
+            if message.evaluations.len() != max_degree + 1 {
+                return Err(Error::IncorrectRoundDegree {
+                    round,
+                    expected: max_degree + 1,
+                    found: message.evaluations.len(),
+                });
+            }
+            if message.evaluations[0] + &message.evaluations[1] != expected {
+                return Err(Error::InconsistentRound(round));
+            }
+            transcript.append_serializable(b"round-message", message);
+
+            let challenge: F = transcript.challenge(b"round-challenge");
+            expected = message.evaluate(challenge);
+            point.push(challenge);
+        }
+
+        end_timer!(verify_time);
+        Ok(SubClaim {
+            point,
+            expected_evaluation: expected,
+        })
+    }
+
+    /// Binds the transcript to the public parameters of the claim.
+    fn append_statement<D: Digest>(
+        transcript: &mut Transcript<D>,
+        num_vars: usize,
+        max_degree: usize,
+        claimed_sum: F,
+    ) {
+        transcript.append_message(b"protocol", b"ml-sumcheck");
+        transcript.append_message(b"num-vars", &(num_vars as u64).to_le_bytes());
+        transcript.append_message(b"max-degree", &(max_degree as u64).to_le_bytes());
+        transcript.append_serializable(b"claimed-sum", &claimed_sum);
+    }
+
+    /// Computes the evaluations at `0, 1, ..., max_degree` of the polynomial
+    /// in the first remaining variable, summed over all the other variables.
+    fn round_message(
+        products: &[(F, Vec<DenseMultilinearExtension<F>>)],
+        num_remaining_vars: usize,
+        max_degree: usize,
+    ) -> RoundMessage<F> {
+        let mut evaluations = vec![F::zero(); max_degree + 1];
+        let mut product_evals = vec![F::zero(); max_degree + 1];
+        let mut factor_evals = vec![F::zero(); max_degree + 1];
+        for (coefficient, factors) in products {
+            for b in 0..1 << (num_remaining_vars - 1) {
+                product_evals.iter_mut().for_each(|e| *e = *coefficient);
+                for factor in factors {
+                    // A multilinear polynomial restricted to a line is linear,
+                    // so its evaluations at `0, 1, 2, ...` differ by a constant.
+                    let (low, high) = (factor[2 * b], factor[2 * b + 1]);
+                    let step = high - &low;
+                    factor_evals[0] = low;
+                    for t in 1..=max_degree {
+                        factor_evals[t] = factor_evals[t - 1] + &step;
+                    }
+                    for (e, f) in product_evals.iter_mut().zip(&factor_evals) {
+                        *e *= f;
+                    }
+                }
+                for (e, p) in evaluations.iter_mut().zip(&product_evals) {
+                    *e += p;
+                }
+            }
+        }
+        RoundMessage { evaluations }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ml_sumcheck::*, Transcript};
+    use algebra::bls12_381::Fr;
+    use algebra_core::{serialize::*, test_rng, One, UniformRand};
+    use blake2::Blake2s;
+    use rand::Rng;
+
+    fn random_sum_of_products<R: Rng>(
+        num_vars: usize,
+        num_products: usize,
+        max_factors: usize,
+        rng: &mut R,
+    ) -> SumOfProducts<Fr> {
+        let mut polynomial = SumOfProducts::new(num_vars);
+        for _ in 0..num_products {
+            let num_factors = rng.gen_range(1, max_factors + 1);
+            let factors = (0..num_factors)
+                .map(|_| DenseMultilinearExtension::rand(num_vars, rng))
+                .collect();
+            polynomial.add_product(Fr::rand(rng), factors).unwrap();
+        }
+        polynomial
+    }
+
+    #[test]
+    fn end_to_end_test() {
+        let rng = &mut test_rng();
+        for num_vars in 0..8 {
+            for max_factors in 1..5 {
+                let polynomial = random_sum_of_products(num_vars, 3, max_factors, rng);
+                let mut prover_transcript = Transcript::<Blake2s>::new(b"test");
+                let (proof, prover_subclaim) =
+                    MLSumcheck::prove(&polynomial, &mut prover_transcript).unwrap();
+
+                let mut verifier_transcript = Transcript::<Blake2s>::new(b"test");
+                let subclaim = MLSumcheck::verify(
+                    num_vars,
+                    polynomial.max_degree(),
+                    polynomial.sum_over_hypercube(),
+                    &proof,
+                    &mut verifier_transcript,
+                )
+                .unwrap();
+                assert_eq!(subclaim, prover_subclaim);
+                subclaim.check(&polynomial).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn constant_summands() {
+        let rng = &mut test_rng();
+        let mut polynomial = random_sum_of_products(4, 2, 2, rng);
+        polynomial.add_product(Fr::one(), vec![]).unwrap();
+        let mut transcript = Transcript::<Blake2s>::new(b"test");
+        let (proof, _) = MLSumcheck::prove(&polynomial, &mut transcript).unwrap();
+
+        let mut transcript = Transcript::<Blake2s>::new(b"test");
+        MLSumcheck::verify(
+            4,
+            polynomial.max_degree(),
+            polynomial.sum_over_hypercube(),
+            &proof,
+            &mut transcript,
+        )
+        .unwrap()
+        .check(&polynomial)
+        .unwrap();
+    }
+
+    #[test]
+    fn wrong_sum_is_rejected() {
+        let rng = &mut test_rng();
+        let polynomial = random_sum_of_products(5, 2, 3, rng);
+        let mut transcript = Transcript::<Blake2s>::new(b"test");
+        let (proof, _) = MLSumcheck::prove(&polynomial, &mut transcript).unwrap();
+
+        let mut transcript = Transcript::<Blake2s>::new(b"test");
+        let result = MLSumcheck::verify(
+            5,
+            polynomial.max_degree(),
+            polynomial.sum_over_hypercube() + &Fr::one(),
+            &proof,
+            &mut transcript,
+        );
+        assert!(matches!(result, Err(Error::InconsistentRound(0))));
+    }
+
+    #[test]
+    fn tampered_proof_is_rejected() {
+        let rng = &mut test_rng();
+        let polynomial = random_sum_of_products(5, 2, 3, rng);
+        let claimed_sum = polynomial.sum_over_hypercube();
+        let mut transcript = Transcript::<Blake2s>::new(b"test");
+        let (proof, _) = MLSumcheck::prove(&polynomial, &mut transcript).unwrap();
+
+        // Changing a message while keeping it consistent with the previous
+        // claim leads to a different final claim.
+        let mut tampered = proof.clone();
+        tampered.rounds[2].evaluations[0] += &Fr::one();
+        tampered.rounds[2].evaluations[1] -= &Fr::one();
+        let mut transcript = Transcript::<Blake2s>::new(b"test");
+        let result = MLSumcheck::verify(
+            5,
+            polynomial.max_degree(),
+            claimed_sum,
+            &tampered,
+            &mut transcript,
+        );
+        match result {
+            Ok(subclaim) => assert!(subclaim.check(&polynomial).is_err()),
+            Err(Error::InconsistentRound(3)) => {},
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+
+        let mut truncated = proof;
+        truncated.rounds.pop();
+        let mut transcript = Transcript::<Blake2s>::new(b"test");
+        let result = MLSumcheck::verify(
+            5,
+            polynomial.max_degree(),
+            claimed_sum,
+            &truncated,
+            &mut transcript,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::IncorrectNumberOfRounds {
+                expected: 5,
+                found: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn proof_serialization() {
+        let rng = &mut test_rng();
+        let polynomial = random_sum_of_products(6, 2, 3, rng);
+        let mut transcript = Transcript::<Blake2s>::new(b"test");
+        let (proof, _) = MLSumcheck::prove(&polynomial, &mut transcript).unwrap();
+
+        let mut bytes = vec![];
+        proof.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), proof.serialized_size());
+        let deserialized = Proof::<Fr>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(proof, deserialized);
+    }
+
+    #[test]
+    fn mismatched_variables_are_rejected() {
+        let rng = &mut test_rng();
+        let mut polynomial = SumOfProducts::<Fr>::new(3);
+        let result =
+            polynomial.add_product(Fr::one(), vec![DenseMultilinearExtension::rand(4, rng)]);
+        assert!(matches!(
+            result,
+            Err(Error::IncorrectNumberOfVariables {
+                expected: 3,
+                found: 4
+            })
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::Vec;
+use algebra_core::{serialize::CanonicalSerialize, Field};
+use core::marker::PhantomData;
+use digest::Digest;
+
+/// A Fiat--Shamir transcript that derives verifier challenges from all the
+/// messages appended to it so far.
+///
+/// The state of the transcript is a digest of the hash function `D`, which is
+/// updated every time a message is appended or a challenge is derived.
+/// Every message is prefixed with a label and both are length-delimited, so
+/// that different sequences of messages result in different states.
+#[derive(Clone, Debug)]
+pub struct Transcript<D: Digest> {
+    state: Vec<u8>,
+    _hash: PhantomData<D>,
+}
+
+impl<D: Digest> Transcript<D> {
+    /// Creates a new transcript, separated from transcripts for other
+    /// protocols by the given `label`.
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = Self {
+            state: Vec::new(),
+            _hash: PhantomData,
+        };
+        transcript.append_message(b"domain-separator", label);
+        transcript
+    }
+
+    /// Appends the given `message` to the transcript.
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        let mut hasher = D::new();
+        hasher.input(&self.state);
+        hasher.input((label.len() as u64).to_le_bytes());
+        hasher.input(label);
+        hasher.input((message.len() as u64).to_le_bytes());
+        hasher.input(message);
+        self.state = hasher.result().to_vec();
+    }
+
+    /// Appends the canonical serialization of `value` to the transcript.
+    pub fn append_serializable<T: CanonicalSerialize>(&mut self, label: &[u8], value: &T) {
+        let mut bytes = Vec::with_capacity(value.serialized_size());
+        value
+            .serialize(&mut bytes)
+            .expect("serialization into a vector cannot fail");
+        self.append_message(label, &bytes);
+    }
+
+    /// Derives a field element from the current state of the transcript.
+    ///
+    /// The element is sampled by rejection from the output of the hash
+    /// function, so it is (close to) uniformly distributed if the hash
+    /// function behaves like a random oracle.
+    pub fn challenge<F: Field>(&mut self, label: &[u8]) -> F {
+        self.append_message(label, b"challenge");
+        let mut counter = 0u64;
+        loop {
+            // Expand the state until it covers the serialized size of `F`.
+            let mut bytes = Vec::with_capacity(F::SERIALIZED_SIZE);
+            while bytes.len() < F::SERIALIZED_SIZE {
+                let mut hasher = D::new();
+                hasher.input(&self.state);
+                hasher.input(counter.to_le_bytes());
+                bytes.extend_from_slice(&hasher.result());
+                counter += 1;
+            }
+            if let Some(challenge) = F::from_random_bytes(&bytes) {
+                return challenge;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Transcript;
+    use algebra::bls12_381::Fr;
+    use blake2::Blake2s;
+
+    #[test]
+    fn challenges_depend_on_messages() {
+        let mut t1 = Transcript::<Blake2s>::new(b"test");
+        let mut t2 = Transcript::<Blake2s>::new(b"test");
+        t1.append_message(b"message", b"hello");
+        t2.append_message(b"message", b"hello");
+        let c1: Fr = t1.challenge(b"c");
+        assert_eq!(c1, t2.challenge(b"c"));
+        // Successive challenges differ.
+        assert_ne!(c1, t1.challenge::<Fr>(b"c"));
+
+        let mut t3 = Transcript::<Blake2s>::new(b"test");
+        t3.append_message(b"message", b"hellp");
+        assert_ne!(c1, t3.challenge(b"c"));
+
+        // Moving bytes between the label and the message changes the state.
+        let mut t4 = Transcript::<Blake2s>::new(b"test");
+        t4.append_message(b"messageh", b"ello");
+        assert_ne!(c1, t4.challenge(b"c"));
+
+        let mut t5 = Transcript::<Blake2s>::new(b"other");
+        t5.append_message(b"message", b"hello");
+        assert_ne!(c1, t5.challenge(b"c"));
+    }
+
+    #[test]
+    fn serializable_messages() {
+        let mut t1 = Transcript::<Blake2s>::new(b"test");
+        let mut t2 = Transcript::<Blake2s>::new(b"test");
+        t1.append_serializable(b"value", &vec![Fr::from(7u64), Fr::from(9u64)]);
+        t2.append_serializable(b"value", &vec![Fr::from(7u64), Fr::from(8u64)]);
+        assert_ne!(t1.challenge::<Fr>(b"c"), t2.challenge::<Fr>(b"c"));
+    }
+}